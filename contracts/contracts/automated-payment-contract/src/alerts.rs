@@ -0,0 +1,140 @@
+use soroban_sdk::{contracttype, symbol_short, Env, String, Symbol, Vec};
+
+/// Notable events surfaced from schedule processing for an off-chain observer to act on.
+/// Scoped to this contract's own schedules, distinct from the `security-limits` contract's
+/// own spending-anomaly `Alert`/`AlertType`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum AlertType {
+    PaymentFailed,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Alert {
+    pub alert_id: u64,
+    pub schedule_id: u64,
+    pub alert_type: AlertType,
+    pub triggered_at: u64,
+    pub message: String,
+    pub is_resolved: bool,
+    pub last_touched: u64,
+}
+
+const ALERT_COUNTER: Symbol = symbol_short!("ALRT_CNT");
+const ALERT_IDX_PFX: Symbol = symbol_short!("ALRT_IDX");
+
+pub fn generate_alert_id(env: &Env) -> u64 {
+    let current: u64 = env.storage().instance().get(&ALERT_COUNTER).unwrap_or(0);
+    let next = current + 1;
+    env.storage().instance().set(&ALERT_COUNTER, &next);
+    next
+}
+
+fn alert_key(schedule_id: u64, alert_id: u64) -> (u64, u64) {
+    (schedule_id, alert_id)
+}
+
+fn alert_index_key(schedule_id: u64) -> (Symbol, u64) {
+    (ALERT_IDX_PFX, schedule_id)
+}
+
+/// Alert ids recorded against `schedule_id`, in the order `store_alert` first saw them. Alert
+/// ids are drawn from a single contract-wide counter, so this index — not a fixed id range —
+/// is what lets a schedule's alerts stay reachable once the counter has passed any such range.
+fn load_schedule_alert_index(env: &Env, schedule_id: u64) -> Vec<u64> {
+    env.storage().persistent().get(&alert_index_key(schedule_id)).unwrap_or(Vec::new(env))
+}
+
+/// Stores `alert`, stamping `last_touched` at the current ledger time regardless of whatever
+/// the caller set it to — the same way `payment_schedule::set_schedule` owns its own
+/// `last_touched`, so every write is automatically visible to `collect_rent`. Also records
+/// `alert.alert_id` in its schedule's index the first time it's seen, the same way
+/// `security-limits::alert_rules::store_alert` maintains a per-user index.
+pub fn store_alert(env: &Env, alert: &Alert) {
+    let mut alert = alert.clone();
+    alert.last_touched = env.ledger().timestamp();
+    let key = alert_key(alert.schedule_id, alert.alert_id);
+    env.storage().persistent().set(&key, &alert);
+
+    let index_key = alert_index_key(alert.schedule_id);
+    let mut index = load_schedule_alert_index(env, alert.schedule_id);
+    if !index.contains(&alert.alert_id) {
+        index.push_back(alert.alert_id);
+        env.storage().persistent().set(&index_key, &index);
+    }
+}
+
+/// Marks every unresolved alert recorded against `schedule_id` as resolved, e.g. once the
+/// payer has addressed whatever `execute_payment` raised the alert for.
+pub fn resolve_schedule_alerts(env: &Env, schedule_id: u64) {
+    let index = load_schedule_alert_index(env, schedule_id);
+    for i in 0..index.len() {
+        let key = alert_key(schedule_id, index.get(i).unwrap());
+        if let Some(mut alert) = env.storage().persistent().get::<_, Alert>(&key) {
+            if !alert.is_resolved {
+                alert.is_resolved = true;
+                store_alert(env, &alert);
+            }
+        }
+    }
+}
+
+/// Alerts recorded against `schedule_id`, up to `limit`.
+pub fn get_schedule_alerts(env: &Env, schedule_id: u64, limit: u32) -> Vec<Alert> {
+    let index = load_schedule_alert_index(env, schedule_id);
+    let mut alerts = Vec::new(env);
+    let mut count = 0u32;
+
+    for i in 0..index.len() {
+        if count >= limit {
+            break;
+        }
+
+        let key = alert_key(schedule_id, index.get(i).unwrap());
+        if let Some(alert) = env.storage().persistent().get(&key) {
+            alerts.push_back(alert);
+            count += 1;
+        }
+    }
+
+    alerts
+}
+
+/// Bumps the persistent TTL on every still-relevant alert against `schedule_id` (anything
+/// unresolved), and removes ones that have sat resolved for longer than `grace_period` seconds.
+/// Returns `(extended, pruned)`. `ttl_extend_to`/`ttl_threshold` are in ledger sequences, the
+/// same units `extend_ttl` itself takes.
+pub fn collect_schedule_alert_rent(
+    env: &Env,
+    schedule_id: u64,
+    now: u64,
+    grace_period: u64,
+    ttl_threshold: u32,
+    ttl_extend_to: u32,
+) -> (u32, u32) {
+    let index_key = alert_index_key(schedule_id);
+    let index = load_schedule_alert_index(env, schedule_id);
+    let mut extended = 0u32;
+    let mut pruned = 0u32;
+
+    if !index.is_empty() {
+        env.storage().persistent().extend_ttl(&index_key, ttl_threshold, ttl_extend_to);
+    }
+
+    for i in 0..index.len() {
+        let key = alert_key(schedule_id, index.get(i).unwrap());
+
+        if let Some(alert) = env.storage().persistent().get::<_, Alert>(&key) {
+            if alert.is_resolved && now.saturating_sub(alert.last_touched) > grace_period {
+                env.storage().persistent().remove(&key);
+                pruned += 1;
+            } else {
+                env.storage().persistent().extend_ttl(&key, ttl_threshold, ttl_extend_to);
+                extended += 1;
+            }
+        }
+    }
+
+    (extended, pruned)
+}
@@ -1,9 +1,12 @@
+use crate::alerts;
 use crate::error::ContractError;
 use crate::events::*;
 use crate::payment_schedule;
 use crate::payment_schedule::*;
+use crate::plan;
+use crate::plan::{ConditionalPayment, PaymentPlan};
 use crate::validation;
-use soroban_sdk::{token, Address, Env, Vec};
+use soroban_sdk::{token, Address, Env, String, Vec};
 
 pub fn create_schedule(
     env: &Env,
@@ -14,16 +17,40 @@ pub fn create_schedule(
     frequency: PaymentFrequency,
     start_time: u64,
     end_time: Option<u64>,
+    price_guard: Option<PriceGuard>,
+    price_trigger: Option<PriceTrigger>,
+    release_condition: Option<ReleaseCondition>,
+    vesting: Option<VestingPlan>,
+    denomination: Option<Denomination>,
+    retry_policy: Option<RetryPolicy>,
 ) -> Result<u64, ContractError> {
     let current_time = env.ledger().timestamp();
 
     validation::validate_schedule_params(payer, recipient, amount, start_time, end_time, current_time)?;
-    
-    let required_funds = validation::calculate_required_funds(&frequency, amount, start_time, end_time);
+
+    if let Some(condition) = &release_condition {
+        validation::validate_release_condition(condition)?;
+    }
+
+    let required_funds = if let Some(plan) = &vesting {
+        validation::validate_vesting_params(plan)?;
+        plan.total_amount().ok_or(ContractError::InvalidAmount)?
+    } else if let Some(denom) = &denomination {
+        let price_client = price_oracle::PriceOracleClient::new(env, &denom.oracle_contract);
+        let aggregated = match price_client.try_get_price(&denom.asset_symbol) {
+            Ok(Ok(price)) => price,
+            _ => return Err(ContractError::PriceUnavailable),
+        };
+        let per_payment = validation::convert_quote_to_token(denom, amount, aggregated.price)?;
+        let buffered_per_payment = validation::apply_price_drift_buffer(per_payment)?;
+        validation::calculate_required_funds(&frequency, buffered_per_payment, start_time, end_time)
+    } else {
+        validation::calculate_required_funds(&frequency, amount, start_time, end_time)
+    };
     validation::validate_funds(env, token, payer, required_funds)?;
 
     let schedule_id = get_next_schedule_id(env);
-    
+
     let next_payment_time = start_time;
 
     // Transfer initial funds to contract
@@ -48,6 +75,16 @@ pub fn create_schedule(
         next_payment_time,
         created_at: current_time,
         last_payment_at: None,
+        price_guard,
+        price_trigger,
+        release_condition,
+        satisfied_approvals: Vec::new(env),
+        vesting,
+        denomination,
+        last_touched: current_time,
+        retry_policy,
+        next_retry_at: None,
+        recipients: None,
     };
 
     set_schedule(env, &schedule);
@@ -69,76 +106,261 @@ pub fn create_schedule(
     Ok(schedule_id)
 }
 
+/// Like `create_schedule`, but fans a single recurring debit out to several recipients by
+/// relative weight instead of paying one `recipient`. `recipients` pairs each payee with a
+/// `u32` weight; `execute_payment` later divides `amount` across them proportionally to
+/// weight, same as a single-recipient schedule divides nothing. Escrows `amount * n_periods`
+/// up front exactly as `create_schedule` does.
+pub fn create_split_schedule(
+    env: &Env,
+    payer: &Address,
+    recipients: Vec<(Address, u32)>,
+    token: &Address,
+    amount: u128,
+    frequency: PaymentFrequency,
+    start_time: u64,
+    end_time: Option<u64>,
+) -> Result<u64, ContractError> {
+    let current_time = env.ledger().timestamp();
+
+    validation::validate_split_recipients(payer, &recipients)?;
+    validation::validate_schedule_timing(amount, start_time, end_time, current_time)?;
+
+    let required_funds = validation::calculate_required_funds(&frequency, amount, start_time, end_time);
+    validation::validate_funds(env, token, payer, required_funds)?;
+
+    let schedule_id = get_next_schedule_id(env);
+    let next_payment_time = start_time;
+
+    let contract_address = env.current_contract_address();
+    let token_client = token::Client::new(env, token);
+    token_client.transfer(payer, &contract_address, &(required_funds as i128));
+
+    // The first recipient doubles as `recipient` for code paths that only know about a
+    // single payee (e.g. indexing this schedule under its own user list).
+    let (primary_recipient, _) = recipients.get(0).ok_or(ContractError::InvalidInput)?;
+
+    let schedule = PaymentSchedule {
+        id: schedule_id,
+        payer: payer.clone(),
+        recipient: primary_recipient.clone(),
+        token: token.clone(),
+        amount,
+        frequency: frequency.clone(),
+        status: ScheduleStatus::Active,
+        balance: required_funds,
+        total_paid: 0,
+        payment_count: 0,
+        failed_attempts: 0,
+        start_time,
+        end_time,
+        next_payment_time,
+        created_at: current_time,
+        last_payment_at: None,
+        price_guard: None,
+        price_trigger: None,
+        release_condition: None,
+        satisfied_approvals: Vec::new(env),
+        vesting: None,
+        denomination: None,
+        last_touched: current_time,
+        retry_policy: None,
+        next_retry_at: None,
+        recipients: Some(recipients.clone()),
+    };
+
+    set_schedule(env, &schedule);
+    add_user_schedule(env, payer, schedule_id);
+    for (recipient, _) in recipients.iter() {
+        add_user_schedule(env, &recipient, schedule_id);
+    }
+
+    emit_schedule_created(
+        env,
+        schedule_id,
+        payer.clone(),
+        primary_recipient.clone(),
+        token.clone(),
+        amount,
+        frequency,
+        start_time,
+        end_time,
+    );
+
+    Ok(schedule_id)
+}
+
 pub fn execute_payment(
     env: &Env,
     schedule_id: u64,
+    expected_price: Option<u64>,
 ) -> Result<(), ContractError> {
     let mut schedule = get_schedule(env, schedule_id)?;
-    
+
     if schedule.status != ScheduleStatus::Active {
         return Err(ContractError::ScheduleNotActive);
     }
 
     let current_time = env.ledger().timestamp();
-    
+
     if !validation::can_execute_payment(&schedule, current_time) {
         return Err(ContractError::PaymentNotDue);
     }
 
+    if let Some(guard) = &schedule.price_guard {
+        let expected_price = expected_price.ok_or(ContractError::PriceViewRequired)?;
+        // Delegates to the oracle's own assertion so a single, shared definition of
+        // "within bounds" governs both standalone callers and scheduled payments; this
+        // call reverts the whole transaction if the market has drifted past the guard.
+        price_oracle::PriceOracleClient::new(env, &guard.oracle_contract).assert_price_view(
+            &guard.asset_symbol,
+            &expected_price,
+            &guard.max_age_seconds,
+            &guard.max_deviation_bps,
+        );
+    }
+
+    if let Some(condition) = &schedule.release_condition {
+        if !evaluate_release_condition(condition, current_time, &schedule.satisfied_approvals) {
+            return Err(ContractError::ConditionNotSatisfied);
+        }
+    }
+
+    // A `PriceTrigger`d schedule withholds payment rather than reverting until the oracle's
+    // own aggregated price (not a caller-supplied `expected_price`) crosses the threshold
+    // with sufficient confidence — a stop-loss / DCA-on-dip gate rather than a drift bound.
+    if let Some(trigger) = &schedule.price_trigger {
+        let price_client = price_oracle::PriceOracleClient::new(env, &trigger.oracle_contract);
+        let aggregated = match price_client.try_get_price(&trigger.asset_symbol) {
+            Ok(Ok(price)) => price,
+            _ => return Err(ContractError::PriceUnavailable),
+        };
+        if !trigger.is_met(aggregated.price, aggregated.confidence) {
+            return Err(ContractError::PriceTriggerNotMet);
+        }
+    }
+
+    // If the schedule is oracle-denominated, re-price `schedule.amount` (held as a quote-unit
+    // value, e.g. "100 USD") into however many `token` units that's worth right now, rather
+    // than transferring a fixed token quantity.
+    let payment_amount = if let Some(denom) = &schedule.denomination {
+        let price_client = price_oracle::PriceOracleClient::new(env, &denom.oracle_contract);
+        let aggregated = match price_client.try_get_price(&denom.asset_symbol) {
+            Ok(Ok(price)) => price,
+            _ => return Err(ContractError::PriceUnavailable),
+        };
+        validation::convert_quote_to_token(denom, schedule.amount, aggregated.price)?
+    } else {
+        schedule.amount
+    };
+
     // Attempt payment
     let contract_address = env.current_contract_address();
     let token_client = token::Client::new(env, &schedule.token);
-    
-    match token_client.try_transfer(&contract_address, &schedule.recipient, &(schedule.amount as i128)) {
-        Ok(_) => {
-            // Payment successful
-            schedule.balance = schedule.balance.checked_sub(schedule.amount)
-                .ok_or(ContractError::InvalidAmount)?;
-            schedule.total_paid = schedule.total_paid.checked_add(schedule.amount)
-                .ok_or(ContractError::InvalidAmount)?;
-            schedule.payment_count += 1;
-            schedule.failed_attempts = 0;
-            schedule.last_payment_at = Some(current_time);
-            schedule.next_payment_time = calculate_next_payment_time(&schedule.frequency, current_time);
-
-            // Check if schedule should be completed
-            if let Some(end_time) = schedule.end_time {
-                if schedule.next_payment_time > end_time || schedule.balance < schedule.amount {
-                    schedule.status = ScheduleStatus::Completed;
-                    
-                    // Refund remaining balance
-                    if schedule.balance > 0 {
-                        token_client.transfer(&contract_address, &schedule.payer, &(schedule.balance as i128));
-                        emit_schedule_refunded(env, schedule_id, schedule.payer.clone(), schedule.balance);
-                        schedule.balance = 0;
-                    }
-                }
-            } else {
-                // For infinite schedules, mark as completed if no balance left
-                if schedule.balance < schedule.amount {
-                    schedule.status = ScheduleStatus::Completed;
+
+    // A split schedule divides `payment_amount` across its weighted recipients and pays each
+    // in turn; any one transfer failing stops the loop and falls through to the same
+    // failed-attempt handling as a single-recipient schedule's failed transfer.
+    let transfer_succeeded = if let Some(weighted) = &schedule.recipients {
+        let shares = validation::split_weighted_amount(env, weighted, payment_amount)?;
+        shares.iter().all(|(recipient, share)| {
+            share == 0
+                || token_client
+                    .try_transfer(&contract_address, &recipient, &(share as i128))
+                    .is_ok()
+        })
+    } else {
+        token_client
+            .try_transfer(&contract_address, &schedule.recipient, &(payment_amount as i128))
+            .is_ok()
+    };
+
+    if transfer_succeeded {
+        // Payment successful
+        schedule.balance = schedule.balance.checked_sub(payment_amount)
+            .ok_or(ContractError::InvalidAmount)?;
+        schedule.total_paid = schedule.total_paid.checked_add(payment_amount)
+            .ok_or(ContractError::InvalidAmount)?;
+        schedule.payment_count += 1;
+        schedule.failed_attempts = 0;
+        schedule.next_retry_at = None;
+        schedule.last_payment_at = Some(current_time);
+        schedule.next_payment_time = calculate_next_payment_time(
+            &schedule.frequency,
+            schedule.start_time,
+            schedule.payment_count,
+            current_time,
+        );
+
+        // Check if schedule should be completed
+        if let Some(end_time) = schedule.end_time {
+            if schedule.next_payment_time > end_time || schedule.balance < payment_amount {
+                schedule.status = ScheduleStatus::Completed;
+
+                // Refund remaining balance
+                if schedule.balance > 0 {
+                    token_client.transfer(&contract_address, &schedule.payer, &(schedule.balance as i128));
+                    emit_schedule_refunded(env, schedule_id, schedule.payer.clone(), schedule.balance);
+                    schedule.balance = 0;
                 }
             }
+        } else {
+            // For infinite schedules, mark as completed if no balance left
+            if schedule.balance < payment_amount {
+                schedule.status = ScheduleStatus::Completed;
+            }
+        }
 
-            set_schedule(env, &schedule);
-            emit_payment_executed(env, schedule_id, schedule.recipient.clone(), schedule.amount);
+        set_schedule(env, &schedule);
+        emit_payment_executed(env, schedule_id, schedule.recipient.clone(), payment_amount);
 
-            Ok(())
-        }
-        Err(_) => {
-            // Payment failed
-            schedule.failed_attempts += 1;
-            
-            if !validation::should_retry_payment(schedule.failed_attempts) {
-                schedule.status = ScheduleStatus::Inactive;
-                emit_schedule_deactivated(env, schedule_id, schedule.payer.clone());
+        Ok(())
+    } else {
+        // Payment failed
+        schedule.failed_attempts += 1;
+
+        if let Some(policy) = schedule.retry_policy.clone() {
+            // Schedule-configured policy: retries gate on `next_retry_at` rather than the
+            // ordinary `next_payment_time` cadence, and exhausting `max_attempts` abandons
+            // the schedule outright — refunding its escrow — rather than just deactivating.
+            if schedule.failed_attempts >= policy.max_attempts {
+                let refund_amount = schedule.balance;
+                if refund_amount > 0 {
+                    token_client.transfer(&contract_address, &schedule.payer, &(refund_amount as i128));
+                    schedule.balance = 0;
+                }
+                schedule.status = ScheduleStatus::Abandoned;
+                emit_schedule_abandoned(env, schedule_id, schedule.payer.clone(), refund_amount);
+            } else {
+                schedule.next_retry_at = Some(policy.retry_at(current_time, schedule.failed_attempts));
             }
+        } else if !validation::should_retry_payment(schedule.failed_attempts) {
+            schedule.status = ScheduleStatus::Inactive;
+            emit_schedule_deactivated(env, schedule_id, schedule.payer.clone());
+
+            let alert_id = alerts::generate_alert_id(env);
+            alerts::store_alert(
+                env,
+                &alerts::Alert {
+                    alert_id,
+                    schedule_id,
+                    alert_type: alerts::AlertType::PaymentFailed,
+                    triggered_at: current_time,
+                    message: String::from_str(env, "schedule deactivated after repeated payment failures"),
+                    is_resolved: false,
+                    last_touched: current_time,
+                },
+            );
+        } else {
+            // Back off the retry instead of hammering the same failure every scan.
+            schedule.next_payment_time =
+                validation::calculate_backoff_retry_time(current_time, schedule.failed_attempts);
+        }
 
-            set_schedule(env, &schedule);
-            emit_payment_failed(env, schedule_id, schedule.amount, schedule.failed_attempts);
+        set_schedule(env, &schedule);
+        emit_payment_failed(env, schedule_id, payment_amount, schedule.failed_attempts);
 
-            Err(ContractError::PaymentFailed)
-        }
+        Err(ContractError::PaymentFailed)
     }
 }
 
@@ -154,7 +376,10 @@ pub fn update_schedule_status(
         return Err(ContractError::PayerOnly);
     }
 
-    if schedule.status == ScheduleStatus::Cancelled || schedule.status == ScheduleStatus::Completed {
+    if schedule.status == ScheduleStatus::Cancelled
+        || schedule.status == ScheduleStatus::Completed
+        || schedule.status == ScheduleStatus::Abandoned
+    {
         return Err(ContractError::OperationNotAllowed);
     }
 
@@ -251,6 +476,376 @@ pub fn top_up_schedule(
     Ok(())
 }
 
+/// Records a witness against a schedule's escrow condition. `Witness::Approval` requires the
+/// named arbiter's own `require_auth`, so recording it here is equivalent to that arbiter having
+/// signed off — `execute_payment` then re-evaluates the condition tree against whatever
+/// approvals have accumulated.
+pub fn apply_witness(env: &Env, schedule_id: u64, witness: Witness) -> Result<(), ContractError> {
+    let mut schedule = get_schedule(env, schedule_id)?;
+
+    match witness {
+        Witness::Approval(arbiter) => {
+            arbiter.require_auth();
+            if !schedule.satisfied_approvals.contains(&arbiter) {
+                schedule.satisfied_approvals.push_back(arbiter.clone());
+            }
+            set_schedule(env, &schedule);
+            emit_witness_applied(env, schedule_id, arbiter);
+        }
+    }
+
+    Ok(())
+}
+
+/// Escrows up front whatever `payment_plan` could settle for (see `plan::required_escrow`) and
+/// records it as a `ConditionalPayment`, with the same transfer-into-contract step as
+/// `create_schedule`. A branch already satisfied at creation time (e.g. a `Pay`, or an `After`/`Race` whose
+/// `Timestamp` condition has already elapsed) settles immediately rather than waiting for a
+/// witness.
+pub fn create_conditional_payment(
+    env: &Env,
+    payer: &Address,
+    payment_plan: PaymentPlan,
+    token: &Address,
+) -> Result<u64, ContractError> {
+    let escrowed = plan::required_escrow(&payment_plan);
+    if escrowed == 0 {
+        return Err(ContractError::InvalidPlan);
+    }
+
+    validation::validate_funds(env, token, payer, escrowed)?;
+
+    let plan_id = plan::get_next_plan_id(env);
+    let current_time = env.ledger().timestamp();
+
+    let contract_address = env.current_contract_address();
+    let token_client = token::Client::new(env, token);
+    token_client.transfer(payer, &contract_address, &(escrowed as i128));
+
+    let conditional_payment = ConditionalPayment {
+        id: plan_id,
+        payer: payer.clone(),
+        token: token.clone(),
+        escrowed,
+        plan: payment_plan,
+        executed: false,
+        created_at: current_time,
+    };
+    plan::set_plan(env, &conditional_payment);
+
+    emit_conditional_payment_created(env, plan_id, payer.clone(), token.clone(), escrowed);
+
+    settle_conditional_payment(env, plan_id, None)?;
+
+    Ok(plan_id)
+}
+
+/// Applies a witness to a `ConditionalPayment`'s plan and settles it if that's enough to
+/// satisfy a branch. `Some(signer)` requires `signer`'s own `require_auth` before it's
+/// considered a valid `PlanCondition::Signature` witness; `None` simply re-checks whichever
+/// branch is gated by a `PlanCondition::Timestamp` against the current ledger clock.
+pub fn apply_plan_witness(env: &Env, plan_id: u64, signer: Option<Address>) -> Result<(), ContractError> {
+    if let Some(addr) = &signer {
+        addr.require_auth();
+    }
+
+    settle_conditional_payment(env, plan_id, signer.as_ref())
+}
+
+/// Resolves `plan_id`'s plan against `witness` and the current ledger time; if a branch is
+/// satisfied, pays it out and retires the plan. A no-op, not an error, if nothing is satisfied
+/// yet or the plan has already executed — `Race`'s losing branch is cancelled implicitly by the
+/// same `executed` flag that retires the whole plan.
+fn settle_conditional_payment(env: &Env, plan_id: u64, witness: Option<&Address>) -> Result<(), ContractError> {
+    let mut conditional_payment = plan::get_plan(env, plan_id).ok_or(ContractError::PlanNotFound)?;
+    if conditional_payment.executed {
+        return Ok(());
+    }
+
+    let current_time = env.ledger().timestamp();
+
+    if let Some(pay) = plan::resolve(&conditional_payment.plan, current_time, witness) {
+        if pay.amount > conditional_payment.escrowed {
+            return Err(ContractError::InsufficientBalance);
+        }
+
+        let token_client = token::Client::new(env, &conditional_payment.token);
+        token_client.transfer(&env.current_contract_address(), &pay.to, &(pay.amount as i128));
+
+        conditional_payment.executed = true;
+        plan::set_plan(env, &conditional_payment);
+
+        emit_conditional_payment_executed(env, plan_id, pay.to, pay.amount);
+    }
+
+    Ok(())
+}
+
+pub fn get_conditional_payment(env: &Env, plan_id: u64) -> Result<ConditionalPayment, ContractError> {
+    plan::get_plan(env, plan_id).ok_or(ContractError::PlanNotFound)
+}
+
+/// Pulls whatever a graded-vesting schedule has unlocked since its last claim. Unlike
+/// `execute_payment`, nothing pushes this automatically — the recipient calls it whenever they
+/// want to collect what's vested so far.
+pub fn claim(env: &Env, schedule_id: u64, recipient: &Address) -> Result<(), ContractError> {
+    let mut schedule = get_schedule(env, schedule_id)?;
+
+    if schedule.recipient != *recipient {
+        return Err(ContractError::RecipientOnly);
+    }
+
+    if schedule.status != ScheduleStatus::Active {
+        return Err(ContractError::ScheduleNotActive);
+    }
+
+    let plan = schedule.vesting.clone().ok_or(ContractError::OperationNotAllowed)?;
+
+    let current_time = env.ledger().timestamp();
+    let unlocked_total = plan
+        .unlocked_amount(schedule.start_time, current_time)
+        .ok_or(ContractError::InvalidAmount)?;
+    let claimable = unlocked_total
+        .checked_sub(schedule.total_paid)
+        .ok_or(ContractError::InvalidAmount)?;
+
+    if claimable == 0 {
+        return Err(ContractError::PaymentNotDue);
+    }
+
+    let contract_address = env.current_contract_address();
+    let token_client = token::Client::new(env, &schedule.token);
+    token_client.transfer(&contract_address, &schedule.recipient, &(claimable as i128));
+
+    schedule.total_paid = unlocked_total;
+    schedule.balance = schedule.balance.checked_sub(claimable).ok_or(ContractError::InvalidAmount)?;
+    schedule.payment_count += 1;
+    schedule.last_payment_at = Some(current_time);
+
+    let fully_vested = plan.total_amount().ok_or(ContractError::InvalidAmount)?;
+    if schedule.total_paid == fully_vested {
+        schedule.status = ScheduleStatus::Completed;
+    }
+
+    set_schedule(env, &schedule);
+    emit_payment_executed(env, schedule_id, schedule.recipient.clone(), claimable);
+
+    Ok(())
+}
+
+/// A scan started via `process_due_payments` holds `SCAN_LOCK` for this many seconds before a
+/// later call is allowed to treat it as abandoned and start a fresh scan anyway.
+/// `run_due_payment_scan`/`run_due_payment_scan_all_users` take their own caller-supplied
+/// `staleness_window` instead.
+const SCAN_TIMEOUT_SECONDS: u64 = 600;
+
+/// Claims `SCAN_LOCK` for a new batch starting at `current_time`, refusing to start if another
+/// scan is already marked running and still within `staleness_window` of its own start — but
+/// reclaiming the lock for a scan that crashed or was otherwise abandoned past that window.
+fn acquire_scan_lock(env: &Env, current_time: u64, staleness_window: u64) -> Result<(), ContractError> {
+    if let Some(started_at) = payment_schedule::get_scan_in_progress_at(env) {
+        if current_time.saturating_sub(started_at) <= staleness_window {
+            return Err(ContractError::ScanInProgress);
+        }
+    }
+    payment_schedule::set_scan_in_progress_at(env, current_time);
+    Ok(())
+}
+
+/// Settles up to `limit` active schedules whose `next_payment_time` has passed, for an
+/// off-chain keeper to drive in a single transaction. Schedule ids are sequential, so the
+/// scan walks `(offset + 1)..=get_schedule_counter` — same offset/limit pagination as
+/// `get_user_schedules`, letting a keeper that can't fit the whole id range in one call page
+/// through it across several, passing back the id count it consumed as the next `offset`. A
+/// price-guarded schedule has no keeper-supplied `expected_price` to check against and is
+/// reported `Skipped` rather than guessed at. A single failed transfer is recorded in its
+/// `ProcessResult` and does not abort the batch.
+pub fn process_due_payments(env: &Env, offset: u32, limit: u32) -> Result<Vec<ProcessResult>, ContractError> {
+    let current_time = env.ledger().timestamp();
+    acquire_scan_lock(env, current_time, SCAN_TIMEOUT_SECONDS)?;
+
+    let mut results = Vec::new(env);
+    let last_schedule_id = payment_schedule::get_schedule_counter(env);
+    let mut schedule_id = offset as u64 + 1;
+
+    while schedule_id <= last_schedule_id && results.len() < limit {
+        if let Some(schedule) = payment_schedule::get_schedule(env, schedule_id) {
+            if schedule.status == ScheduleStatus::Active
+                && schedule.failed_attempts == 0
+                && schedule.next_payment_time <= current_time
+            {
+                let outcome = if schedule.price_guard.is_some() {
+                    ProcessOutcome::Skipped
+                } else {
+                    match execute_payment(env, schedule_id, None) {
+                        Ok(()) => ProcessOutcome::Succeeded,
+                        Err(_) => ProcessOutcome::Failed,
+                    }
+                };
+                results.push_back(ProcessResult { schedule_id, outcome });
+            }
+        }
+        schedule_id += 1;
+    }
+
+    payment_schedule::clear_scan_in_progress_at(env);
+
+    Ok(results)
+}
+
+/// A schedule is swept by `run_due_payment_scan`/`run_due_payment_scan_all_users` once it is
+/// `Active`, due, and funded — mirrors `validation::can_execute_payment`'s own gating (which
+/// also accounts for a denominated schedule's `amount` being a quote-unit value rather than a
+/// token quantity) so a schedule this sweep picks up is always one `execute_payment` will
+/// actually accept. A schedule with a prior failure is left to `process_pending_payments`
+/// instead, so a backlog of stuck retries can never crowd out the main due-payment sweep.
+fn is_due_for_scan(schedule: &PaymentSchedule, current_time: u64) -> bool {
+    schedule.status == ScheduleStatus::Active
+        && schedule.failed_attempts == 0
+        && validation::can_execute_payment(schedule, current_time)
+}
+
+/// Sweeps every schedule `user` is party to (as payer or recipient) and settles the ones that
+/// are due, returning the ids actually settled. Guards against overlapping runs the same way
+/// `process_due_payments` does, sharing its `SCAN_LOCK`; `staleness_window` lets the caller
+/// decide how long an abandoned scan is held against before it's reclaimed. A single failed
+/// settlement is skipped rather than aborting the rest of the sweep.
+pub fn run_due_payment_scan(
+    env: &Env,
+    user: &Address,
+    current_time: u64,
+    staleness_window: u64,
+) -> Result<Vec<u64>, ContractError> {
+    acquire_scan_lock(env, current_time, staleness_window)?;
+
+    let mut settled = Vec::new(env);
+    for schedule_id in payment_schedule::get_user_schedules(env, user).iter() {
+        if let Some(schedule) = payment_schedule::get_schedule(env, schedule_id) {
+            if is_due_for_scan(&schedule, current_time) && execute_payment(env, schedule_id, None).is_ok() {
+                settled.push_back(schedule_id);
+            }
+        }
+    }
+
+    payment_schedule::clear_scan_in_progress_at(env);
+
+    Ok(settled)
+}
+
+/// The all-users counterpart of `run_due_payment_scan`. There is no separate registry of every
+/// user who has ever been party to a schedule, so this walks every schedule id ever issued
+/// (`1..=get_schedule_counter`) instead of `get_user_schedules` per user — the same set of
+/// schedules a per-user pass over every known user would eventually reach, without needing
+/// that registry.
+pub fn run_due_payment_scan_all_users(
+    env: &Env,
+    current_time: u64,
+    staleness_window: u64,
+) -> Result<Vec<u64>, ContractError> {
+    acquire_scan_lock(env, current_time, staleness_window)?;
+
+    let mut settled = Vec::new(env);
+    let last_schedule_id = payment_schedule::get_schedule_counter(env);
+    let mut schedule_id = 1u64;
+
+    while schedule_id <= last_schedule_id {
+        if let Some(schedule) = payment_schedule::get_schedule(env, schedule_id) {
+            if is_due_for_scan(&schedule, current_time) && execute_payment(env, schedule_id, None).is_ok() {
+                settled.push_back(schedule_id);
+            }
+        }
+        schedule_id += 1;
+    }
+
+    payment_schedule::clear_scan_in_progress_at(env);
+
+    Ok(settled)
+}
+
+/// A `process_pending_payments` retry batch holds `RETRY_SCAN_LOCK` for this many seconds
+/// before a later call may treat it as abandoned and reclaim it.
+const RETRY_SCAN_TIMEOUT_SECONDS: u64 = 600;
+
+/// Claims `RETRY_SCAN_LOCK`, the retry scanner's own lock — kept separate from `SCAN_LOCK` so
+/// a stuck retry batch can never block `process_due_payments`/`run_due_payment_scan`, and vice
+/// versa.
+fn acquire_retry_scan_lock(env: &Env, current_time: u64, staleness_window: u64) -> Result<(), ContractError> {
+    if let Some(started_at) = payment_schedule::get_retry_scan_in_progress_at(env) {
+        if current_time.saturating_sub(started_at) <= staleness_window {
+            return Err(ContractError::ScanInProgress);
+        }
+    }
+    payment_schedule::set_retry_scan_in_progress_at(env, current_time);
+    Ok(())
+}
+
+/// Re-attempts up to `limit` schedules that have already failed at least once and are past
+/// their backoff `next_payment_time`. Runs under its own lock, independent of the due-payment
+/// scanners, so a backlog of stuck retries never blocks ordinary settlement. A schedule that
+/// fails again here keeps accumulating `failed_attempts` exactly as `execute_payment` already
+/// does on any other call path.
+pub fn process_pending_payments(env: &Env, limit: u32) -> Result<Vec<ProcessResult>, ContractError> {
+    let current_time = env.ledger().timestamp();
+    acquire_retry_scan_lock(env, current_time, RETRY_SCAN_TIMEOUT_SECONDS)?;
+
+    let mut results = Vec::new(env);
+    let last_schedule_id = payment_schedule::get_schedule_counter(env);
+    let mut schedule_id = 1u64;
+
+    while schedule_id <= last_schedule_id && results.len() < limit {
+        if let Some(schedule) = payment_schedule::get_schedule(env, schedule_id) {
+            if schedule.status == ScheduleStatus::Active
+                && schedule.failed_attempts > 0
+                && schedule.next_payment_time <= current_time
+            {
+                let outcome = match execute_payment(env, schedule_id, None) {
+                    Ok(()) => ProcessOutcome::Succeeded,
+                    Err(_) => ProcessOutcome::Failed,
+                };
+                results.push_back(ProcessResult { schedule_id, outcome });
+            }
+        }
+        schedule_id += 1;
+    }
+
+    payment_schedule::clear_retry_scan_in_progress_at(env);
+
+    Ok(results)
+}
+
+/// Lets the payer recover a schedule `execute_payment` deactivated after repeated failures —
+/// typically after topping up `balance` via `top_up_schedule`. Clears `failed_attempts`,
+/// reactivates the schedule if it went `Inactive`, and makes it immediately retryable rather
+/// than leaving it behind its last backoff delay.
+pub fn reset_failed_attempts(env: &Env, schedule_id: u64, payer: &Address) -> Result<(), ContractError> {
+    let mut schedule = get_schedule(env, schedule_id)?;
+
+    if schedule.payer != *payer {
+        return Err(ContractError::PayerOnly);
+    }
+
+    if schedule.status == ScheduleStatus::Cancelled
+        || schedule.status == ScheduleStatus::Completed
+        || schedule.status == ScheduleStatus::Abandoned
+    {
+        return Err(ContractError::OperationNotAllowed);
+    }
+
+    schedule.failed_attempts = 0;
+    schedule.next_retry_at = None;
+    schedule.next_payment_time = env.ledger().timestamp();
+
+    if schedule.status == ScheduleStatus::Inactive {
+        schedule.status = ScheduleStatus::Active;
+        emit_schedule_activated(env, schedule_id, payer.clone());
+    }
+
+    set_schedule(env, &schedule);
+    alerts::resolve_schedule_alerts(env, schedule_id);
+
+    Ok(())
+}
+
 pub fn get_schedule(env: &Env, schedule_id: u64) -> Result<PaymentSchedule, ContractError> {
     payment_schedule::get_schedule(env, schedule_id).ok_or(ContractError::ScheduleNotFound)
 }
@@ -33,4 +33,26 @@ pub enum ContractError {
     // General errors
     DataNotFound = 17,
     OperationNotAllowed = 18,
+
+    // Price guard errors
+    PriceViewRequired = 19,
+
+    // Escrow condition errors
+    ConditionNotSatisfied = 20,
+
+    // Oracle-denominated payment errors
+    PriceUnavailable = 21,
+
+    // Keeper batch errors
+    ScanInProgress = 22,
+
+    // Conditional payment plan errors
+    PlanNotFound = 23,
+    InvalidPlan = 24,
+
+    // Price-triggered payment errors
+    PriceTriggerNotMet = 25,
+
+    // Escrow condition errors (continued)
+    InvalidReleaseCondition = 26,
 }
\ No newline at end of file
@@ -62,6 +62,38 @@ pub struct ScheduleRefundedEvent {
     pub amount: u128,
 }
 
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ScheduleAbandonedEvent {
+    pub schedule_id: u64,
+    pub payer: Address,
+    pub refunded_amount: u128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WitnessAppliedEvent {
+    pub schedule_id: u64,
+    pub arbiter: Address,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ConditionalPaymentCreatedEvent {
+    pub plan_id: u64,
+    pub payer: Address,
+    pub token: Address,
+    pub escrowed: u128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ConditionalPaymentExecutedEvent {
+    pub plan_id: u64,
+    pub to: Address,
+    pub amount: u128,
+}
+
 pub fn emit_schedule_created(
     env: &Env,
     schedule_id: u64,
@@ -147,4 +179,40 @@ pub fn emit_schedule_refunded(env: &Env, schedule_id: u64, payer: Address, amoun
         amount,
     };
     env.events().publish(("schedule_refunded",), event);
+}
+
+pub fn emit_schedule_abandoned(env: &Env, schedule_id: u64, payer: Address, refunded_amount: u128) {
+    let event = ScheduleAbandonedEvent {
+        schedule_id,
+        payer,
+        refunded_amount,
+    };
+    env.events().publish(("schedule_abandoned",), event);
+}
+
+pub fn emit_witness_applied(env: &Env, schedule_id: u64, arbiter: Address) {
+    let event = WitnessAppliedEvent {
+        schedule_id,
+        arbiter,
+    };
+    env.events().publish(("witness_applied",), event);
+}
+
+pub fn emit_conditional_payment_created(env: &Env, plan_id: u64, payer: Address, token: Address, escrowed: u128) {
+    let event = ConditionalPaymentCreatedEvent {
+        plan_id,
+        payer,
+        token,
+        escrowed,
+    };
+    env.events().publish(("conditional_payment_created",), event);
+}
+
+pub fn emit_conditional_payment_executed(env: &Env, plan_id: u64, to: Address, amount: u128) {
+    let event = ConditionalPaymentExecutedEvent {
+        plan_id,
+        to,
+        amount,
+    };
+    env.events().publish(("conditional_payment_executed",), event);
 }
\ No newline at end of file
@@ -5,6 +5,9 @@ mod events;
 mod storage;
 mod payment_schedule;
 mod validation;
+mod alerts;
+mod rent;
+mod plan;
 mod contract;
 
 mod test;
@@ -40,17 +43,48 @@ impl AutomatedPaymentContract {
         frequency: payment_schedule::PaymentFrequency,
         start_time: u64,
         end_time: Option<u64>,
+        price_guard: Option<payment_schedule::PriceGuard>,
+        price_trigger: Option<payment_schedule::PriceTrigger>,
+        release_condition: Option<payment_schedule::ReleaseCondition>,
+        vesting: Option<payment_schedule::VestingPlan>,
+        denomination: Option<payment_schedule::Denomination>,
+        retry_policy: Option<payment_schedule::RetryPolicy>,
     ) -> Result<u64, ContractError> {
         payer.require_auth();
-        contract::create_schedule(&env, &payer, &recipient, &token, amount, frequency, start_time, end_time)
+        contract::create_schedule(&env, &payer, &recipient, &token, amount, frequency, start_time, end_time, price_guard, price_trigger, release_condition, vesting, denomination, retry_policy)
     }
 
-    /// Execute scheduled payment
+    /// Create a payment schedule that fans a single recurring debit out to several
+    /// recipients by relative weight, rather than paying one `recipient`. Each
+    /// `(Address, u32)` in `recipients` is a payee and its weight; `execute_payment`
+    /// divides the per-payment amount across them proportionally to weight.
+    pub fn create_split_schedule(
+        env: Env,
+        payer: Address,
+        recipients: Vec<(Address, u32)>,
+        token: Address,
+        amount: u128,
+        frequency: payment_schedule::PaymentFrequency,
+        start_time: u64,
+        end_time: Option<u64>,
+    ) -> Result<u64, ContractError> {
+        payer.require_auth();
+        contract::create_split_schedule(&env, &payer, recipients, &token, amount, frequency, start_time, end_time)
+    }
+
+    /// Execute scheduled payment. If the schedule was created with a `PriceGuard`,
+    /// `expected_price` must be supplied and match the live oracle price within the
+    /// guard's bounds or the call reverts before any funds move. If the schedule carries a
+    /// `PriceTrigger`, the call returns `PriceTriggerNotMet` until the oracle's own
+    /// aggregated price crosses the configured threshold at sufficient confidence. If the
+    /// schedule is oracle-`Denomination`d, the token amount transferred is re-priced from the
+    /// live oracle quote rather than being the schedule's stored `amount`.
     pub fn execute_payment(
         env: Env,
         schedule_id: u64,
+        expected_price: Option<u64>,
     ) -> Result<(), ContractError> {
-        contract::execute_payment(&env, schedule_id)
+        contract::execute_payment(&env, schedule_id, expected_price)
     }
 
     /// Update schedule status
@@ -102,4 +136,132 @@ impl AutomatedPaymentContract {
         payer.require_auth();
         contract::top_up_schedule(&env, schedule_id, &payer, amount)
     }
+
+    /// Apply a witness to a schedule's escrow `release_condition`. `execute_payment` checks
+    /// the condition tree on every call, so this only needs to record the fact — it does not
+    /// move funds itself.
+    pub fn apply_witness(
+        env: Env,
+        schedule_id: u64,
+        witness: payment_schedule::Witness,
+    ) -> Result<(), ContractError> {
+        contract::apply_witness(&env, schedule_id, witness)
+    }
+
+    /// Escrows funds up front for a one-shot `PaymentPlan` (`Pay`, `After`, or `Race`) rather
+    /// than a recurring schedule. A branch already satisfied at creation settles immediately.
+    pub fn create_conditional_payment(
+        env: Env,
+        payer: Address,
+        plan: plan::PaymentPlan,
+        token: Address,
+    ) -> Result<u64, ContractError> {
+        payer.require_auth();
+        contract::create_conditional_payment(&env, &payer, plan, &token)
+    }
+
+    /// Witnesses a conditional payment's plan and settles it if that satisfies a branch.
+    /// `Some(signer)` requires `signer`'s own `require_auth`; `None` just re-checks whichever
+    /// branch is gated by a `plan::PlanCondition::Timestamp` against the current ledger clock.
+    pub fn apply_plan_witness(
+        env: Env,
+        plan_id: u64,
+        signer: Option<Address>,
+    ) -> Result<(), ContractError> {
+        contract::apply_plan_witness(&env, plan_id, signer)
+    }
+
+    /// Get a conditional payment plan
+    pub fn get_conditional_payment(
+        env: Env,
+        plan_id: u64,
+    ) -> Result<plan::ConditionalPayment, ContractError> {
+        contract::get_conditional_payment(&env, plan_id)
+    }
+
+    /// Claim whatever a graded-vesting schedule has unlocked so far.
+    pub fn claim(
+        env: Env,
+        schedule_id: u64,
+        recipient: Address,
+    ) -> Result<(), ContractError> {
+        recipient.require_auth();
+        contract::claim(&env, schedule_id, &recipient)
+    }
+
+    /// Batch-settle up to `limit` due, active schedules starting after the `offset`th schedule
+    /// id, in one transaction. Permissionless, so any off-chain keeper can drive it; a keeper
+    /// covering more schedules than fit in one call passes the id count it consumed back in as
+    /// `offset` to page through the rest. Re-entrant calls while a batch is already in flight
+    /// are rejected with `ContractError::ScanInProgress`.
+    pub fn process_due_payments(
+        env: Env,
+        offset: u32,
+        limit: u32,
+    ) -> Result<Vec<payment_schedule::ProcessResult>, ContractError> {
+        contract::process_due_payments(&env, offset, limit)
+    }
+
+    /// Sweep `user`'s schedules and settle whichever are due, returning the settled ids.
+    /// `staleness_window` bounds how long a prior, still-marked-running scan is honored before
+    /// this call is allowed to reclaim the lock and proceed anyway.
+    pub fn run_due_payment_scan(
+        env: Env,
+        user: Address,
+        now: u64,
+        staleness_window: u64,
+    ) -> Result<Vec<u64>, ContractError> {
+        contract::run_due_payment_scan(&env, &user, now, staleness_window)
+    }
+
+    /// The all-users counterpart of `run_due_payment_scan`, sweeping every schedule ever
+    /// created rather than just one user's.
+    pub fn run_due_payment_scan_all_users(
+        env: Env,
+        now: u64,
+        staleness_window: u64,
+    ) -> Result<Vec<u64>, ContractError> {
+        contract::run_due_payment_scan_all_users(&env, now, staleness_window)
+    }
+
+    /// Re-attempts up to `limit` schedules sitting in backoff after a prior payment failure.
+    /// Runs under its own lock, independent of `process_due_payments`/`run_due_payment_scan`,
+    /// so a stuck retry batch can never block ordinary settlement.
+    pub fn process_pending_payments(
+        env: Env,
+        limit: u32,
+    ) -> Result<Vec<payment_schedule::ProcessResult>, ContractError> {
+        contract::process_pending_payments(&env, limit)
+    }
+
+    /// Clears a schedule's `failed_attempts` and reactivates it if `execute_payment` had
+    /// deactivated it, for the payer to call after topping up `balance`.
+    pub fn reset_failed_attempts(
+        env: Env,
+        schedule_id: u64,
+        payer: Address,
+    ) -> Result<(), ContractError> {
+        payer.require_auth();
+        contract::reset_failed_attempts(&env, schedule_id, &payer)
+    }
+
+    /// Alerts recorded against a schedule, e.g. from `execute_payment` deactivating it after
+    /// repeated failures.
+    pub fn get_schedule_alerts(
+        env: Env,
+        schedule_id: u64,
+        limit: u32,
+    ) -> Vec<alerts::Alert> {
+        alerts::get_schedule_alerts(&env, schedule_id, limit)
+    }
+
+    /// Permissionless rent-collection sweep over every schedule and its alerts: bumps the TTL
+    /// on anything still live, and prunes schedules/alerts that have gone terminal (`Cancelled`/
+    /// `Completed`/`Abandoned` schedules, resolved alerts) and sat untouched for longer than `grace_period`
+    /// seconds. Intended to be driven by an off-chain keeper on a regular cadence, the same way
+    /// `process_due_payments` is — `offset` pages through a schedule table larger than one call
+    /// can scan.
+    pub fn collect_rent(env: Env, offset: u32, now: u64, grace_period: u64) -> rent::RentReport {
+        rent::collect_rent(&env, offset, now, grace_period)
+    }
 }
\ No newline at end of file
@@ -6,6 +6,8 @@ pub enum PaymentFrequency {
     Daily,
     Weekly,
     Monthly,
+    /// An explicit interval in seconds, for cadences `Daily`/`Weekly`/`Monthly` don't cover.
+    Custom(u64),
 }
 
 #[contracttype]
@@ -15,6 +17,28 @@ pub enum ScheduleStatus {
     Inactive,
     Cancelled,
     Completed,
+    /// Terminal: `execute_payment` gave up retrying under the schedule's own `RetryPolicy`
+    /// after `failed_attempts` reached `max_attempts`, and its escrow balance was refunded.
+    /// Distinct from `Inactive`, which a payer can always reactivate.
+    Abandoned,
+}
+
+/// Outcome of a single schedule's settlement attempt inside a `process_due_payments` batch.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ProcessOutcome {
+    Succeeded,
+    Failed,
+    Skipped,
+}
+
+/// Per-schedule result returned from `process_due_payments`, so a keeper can tell which of
+/// the schedules it submitted for settlement actually moved funds.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProcessResult {
+    pub schedule_id: u64,
+    pub outcome: ProcessOutcome,
 }
 
 #[contracttype]
@@ -36,6 +60,201 @@ pub struct PaymentSchedule {
     pub next_payment_time: u64,
     pub created_at: u64,
     pub last_payment_at: Option<u64>,
+    pub price_guard: Option<PriceGuard>,
+    pub price_trigger: Option<PriceTrigger>,
+    pub release_condition: Option<ReleaseCondition>,
+    pub satisfied_approvals: Vec<Address>,
+    pub vesting: Option<VestingPlan>,
+    pub denomination: Option<Denomination>,
+    pub last_touched: u64,
+    pub retry_policy: Option<RetryPolicy>,
+    pub next_retry_at: Option<u64>,
+    /// Set by `create_split_schedule`, `None` for an ordinary single-`recipient` schedule.
+    /// Each `(Address, u32)` pairs a payee with its relative weight; `execute_payment` divides
+    /// the per-payment amount across them proportionally instead of paying `recipient` alone.
+    pub recipients: Option<Vec<(Address, u32)>>,
+}
+
+/// Per-schedule override for how `execute_payment` retries failed transfers. Omitted, prior
+/// behavior stands: bounded retries against the hardcoded global backoff, ending in a simple
+/// `Inactive` deactivation. Supplied, `execute_payment` gates retries on `next_retry_at`
+/// (independent of the schedule's ordinary `next_payment_time` cadence) and abandons the
+/// schedule — refunding its escrow balance — once `failed_attempts` reaches `max_attempts`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub base_backoff_secs: u64,
+}
+
+impl RetryPolicy {
+    /// `next_retry_at` for a payment that just failed for the `failed_attempts`-th time under
+    /// this policy: `base_backoff_secs * 2^(failed_attempts - 1)`, saturating.
+    pub fn retry_at(&self, current_time: u64, failed_attempts: u32) -> u64 {
+        let delay = 1u64
+            .checked_shl(failed_attempts.saturating_sub(1))
+            .and_then(|factor| self.base_backoff_secs.checked_mul(factor))
+            .unwrap_or(u64::MAX);
+        current_time.saturating_add(delay)
+    }
+}
+
+/// Graded vesting parameters: `per_period` unlocks every `period` seconds for `period_count`
+/// periods, starting at the schedule's `start_time`. Unlike the `PaymentFrequency`-driven flow,
+/// nothing is pushed automatically — the recipient pulls whatever has unlocked via `claim`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VestingPlan {
+    pub period: u64,
+    pub per_period: u128,
+    pub period_count: u32,
+}
+
+impl VestingPlan {
+    /// Total amount this plan unlocks once fully vested.
+    pub fn total_amount(&self) -> Option<u128> {
+        self.per_period.checked_mul(self.period_count as u128)
+    }
+
+    /// Amount unlocked as of `current_time`, clamped so the final period never over-unlocks.
+    /// Returns `0` before `start_time` (the cliff).
+    pub fn unlocked_amount(&self, start_time: u64, current_time: u64) -> Option<u128> {
+        if current_time < start_time || self.period == 0 {
+            return Some(0);
+        }
+        let elapsed_periods = ((current_time - start_time) / self.period).min(self.period_count as u64);
+        self.per_period.checked_mul(elapsed_periods as u128)
+    }
+}
+
+/// Bounds an oracle-derived price view that must hold at execution time for a schedule to
+/// pay out, guarding against a transaction landing after the market has moved.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PriceGuard {
+    pub oracle_contract: Address,
+    pub asset_symbol: Symbol,
+    pub max_age_seconds: u64,
+    pub max_deviation_bps: u32,
+}
+
+/// Which side of `threshold` a `PriceTrigger` fires on.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum PriceComparator {
+    Above,
+    Below,
+}
+
+/// Gates `execute_payment` on a live oracle price crossing `threshold`, turning a schedule
+/// into an on-chain stop-loss / DCA-on-dip order rather than a pure clock-driven payout.
+/// Unlike `PriceGuard` (which only bounds drift against a caller-supplied `expected_price`),
+/// this is checked unconditionally against the oracle's own aggregated price, and payment is
+/// withheld — not reverted — until the comparator holds, same as `ConditionKind::AfterTimestamp`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PriceTrigger {
+    pub oracle_contract: Address,
+    pub asset_symbol: Symbol,
+    pub comparator: PriceComparator,
+    pub threshold: u64,
+    /// Minimum `AggregatedPrice::confidence` required to fire; a technically-crossed but
+    /// low-confidence price (e.g. during a thin-quorum round) never triggers execution.
+    pub min_confidence: u32,
+}
+
+impl PriceTrigger {
+    /// Whether `price` at `confidence` satisfies this trigger.
+    pub fn is_met(&self, price: u64, confidence: u32) -> bool {
+        if confidence < self.min_confidence {
+            return false;
+        }
+        match self.comparator {
+            PriceComparator::Above => price >= self.threshold,
+            PriceComparator::Below => price <= self.threshold,
+        }
+    }
+}
+
+/// Denominates a schedule's `amount` in an oracle-quoted unit (e.g. "USD") rather than a
+/// fixed `token` quantity. `execute_payment` converts `amount` to `token` units at the latest
+/// reliable price for `asset_symbol` before every transfer, so the recipient keeps receiving
+/// a stable value even as `token` itself moves.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Denomination {
+    pub oracle_contract: Address,
+    pub asset_symbol: Symbol,
+    pub decimals: u32,
+}
+
+/// One node of a `ReleaseCondition` arena. `And`/`Or` reference sibling nodes by index rather
+/// than holding a boxed sub-condition directly, since `#![no_std]` has no `Box` to give an
+/// `enum` variant a heap-indirected recursive case.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ConditionKind {
+    AfterTimestamp(u64),
+    OnApproval(Address),
+    And(u32, u32),
+    Or(u32, u32),
+    /// Satisfied once at least `required` of `approvers` appear in `satisfied_approvals` — an
+    /// M-of-N threshold, unlike `OnApproval`'s single fixed arbiter.
+    MultiWitness { required: u32, approvers: Vec<Address> },
+}
+
+/// An escrow release condition, modeled on the witness/condition pattern from Solana's budget
+/// program: funds stay locked until this tree evaluates to true. Stored as a flat arena of
+/// `nodes`, with `nodes[0]` as the root, so arbitrarily deep `And`/`Or` composition needs no
+/// recursive type — `evaluate_release_condition` walks it via ordinary function recursion over
+/// indices instead.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReleaseCondition {
+    pub nodes: Vec<ConditionKind>,
+}
+
+/// A fact recorded against a schedule's escrow condition. `Approval` requires `require_auth`
+/// from the named arbiter before it's recorded; `AfterTimestamp` conditions need no witness
+/// since they're checked directly against the ledger clock.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Witness {
+    Approval(Address),
+}
+
+fn evaluate_node(
+    condition: &ReleaseCondition,
+    idx: u32,
+    current_time: u64,
+    satisfied_approvals: &Vec<Address>,
+) -> bool {
+    match condition.nodes.get(idx).expect("condition node index out of bounds") {
+        ConditionKind::AfterTimestamp(ts) => current_time >= ts,
+        ConditionKind::OnApproval(arbiter) => satisfied_approvals.contains(&arbiter),
+        ConditionKind::MultiWitness { required, approvers } => {
+            let met = approvers.iter().filter(|a| satisfied_approvals.contains(a)).count() as u32;
+            met >= required
+        }
+        ConditionKind::And(left, right) => {
+            evaluate_node(condition, left, current_time, satisfied_approvals)
+                && evaluate_node(condition, right, current_time, satisfied_approvals)
+        }
+        ConditionKind::Or(left, right) => {
+            evaluate_node(condition, left, current_time, satisfied_approvals)
+                || evaluate_node(condition, right, current_time, satisfied_approvals)
+        }
+    }
+}
+
+/// Evaluates `condition`'s root node against the current ledger time and the approvals
+/// recorded so far for the schedule it belongs to.
+pub fn evaluate_release_condition(
+    condition: &ReleaseCondition,
+    current_time: u64,
+    satisfied_approvals: &Vec<Address>,
+) -> bool {
+    evaluate_node(condition, 0, current_time, satisfied_approvals)
 }
 
 #[contracttype]
@@ -47,6 +266,8 @@ pub enum StorageKey {
 }
 
 const SCHEDULE_COUNTER: Symbol = symbol_short!("SCH_CNT");
+const SCAN_LOCK: Symbol = symbol_short!("SCANLOCK");
+const RETRY_SCAN_LOCK: Symbol = symbol_short!("RETRYLCK");
 
 pub fn get_next_schedule_id(env: &Env) -> u64 {
     let current = env.storage().instance().get(&SCHEDULE_COUNTER).unwrap_or(0u64);
@@ -55,14 +276,54 @@ pub fn get_next_schedule_id(env: &Env) -> u64 {
     next
 }
 
+/// Highest schedule id issued so far, i.e. the current value of the counter without
+/// advancing it. Schedule ids are assigned sequentially starting at 1, so `1..=this` covers
+/// every schedule ever created.
+pub fn get_schedule_counter(env: &Env) -> u64 {
+    env.storage().instance().get(&SCHEDULE_COUNTER).unwrap_or(0u64)
+}
+
+/// Timestamp a `process_due_payments` batch started at, if one is currently in flight.
+pub fn get_scan_in_progress_at(env: &Env) -> Option<u64> {
+    env.storage().instance().get(&SCAN_LOCK)
+}
+
+pub fn set_scan_in_progress_at(env: &Env, started_at: u64) {
+    env.storage().instance().set(&SCAN_LOCK, &started_at);
+}
+
+pub fn clear_scan_in_progress_at(env: &Env) {
+    env.storage().instance().remove(&SCAN_LOCK);
+}
+
+/// Timestamp a `process_pending_payments` retry batch started at, if one is currently in
+/// flight. Kept separate from `SCAN_LOCK` so the retry scanner and the due-payment scanners
+/// never block each other.
+pub fn get_retry_scan_in_progress_at(env: &Env) -> Option<u64> {
+    env.storage().instance().get(&RETRY_SCAN_LOCK)
+}
+
+pub fn set_retry_scan_in_progress_at(env: &Env, started_at: u64) {
+    env.storage().instance().set(&RETRY_SCAN_LOCK, &started_at);
+}
+
+pub fn clear_retry_scan_in_progress_at(env: &Env) {
+    env.storage().instance().remove(&RETRY_SCAN_LOCK);
+}
+
 pub fn get_schedule(env: &Env, schedule_id: u64) -> Option<PaymentSchedule> {
     let key = StorageKey::Schedule(schedule_id);
     env.storage().persistent().get(&key)
 }
 
+/// Writes `schedule`, stamping `last_touched` at the current ledger time regardless of
+/// whatever the caller set it to, so `collect_rent` always sees an accurate age for every
+/// write path without each call site having to remember to touch it itself.
 pub fn set_schedule(env: &Env, schedule: &PaymentSchedule) {
+    let mut schedule = schedule.clone();
+    schedule.last_touched = env.ledger().timestamp();
     let key = StorageKey::Schedule(schedule.id);
-    env.storage().persistent().set(&key, schedule);
+    env.storage().persistent().set(&key, &schedule);
 }
 
 pub fn get_user_schedules(env: &Env, user: &Address) -> Vec<u64> {
@@ -77,10 +338,138 @@ pub fn add_user_schedule(env: &Env, user: &Address, schedule_id: u64) {
     env.storage().persistent().set(&key, &schedules);
 }
 
-pub fn calculate_next_payment_time(frequency: &PaymentFrequency, current_time: u64) -> u64 {
+/// Removes `schedule_id` once it's `Cancelled`/`Completed`/`Abandoned` and has sat untouched
+/// for longer than `grace_period` seconds; otherwise (live, or terminal but still within its
+/// grace period) bumps its persistent TTL. Returns `(extended, pruned)` as `(1, 0)` or
+/// `(0, 1)`; `(0, 0)` if the schedule doesn't exist.
+pub fn collect_schedule_rent(
+    env: &Env,
+    schedule_id: u64,
+    now: u64,
+    grace_period: u64,
+    ttl_threshold: u32,
+    ttl_extend_to: u32,
+) -> (u32, u32) {
+    let key = StorageKey::Schedule(schedule_id);
+
+    if let Some(schedule) = env.storage().persistent().get::<_, PaymentSchedule>(&key) {
+        let is_terminal = schedule.status == ScheduleStatus::Cancelled
+            || schedule.status == ScheduleStatus::Completed
+            || schedule.status == ScheduleStatus::Abandoned;
+
+        if is_terminal && now.saturating_sub(schedule.last_touched) > grace_period {
+            env.storage().persistent().remove(&key);
+            (0, 1)
+        } else {
+            env.storage().persistent().extend_ttl(&key, ttl_threshold, ttl_extend_to);
+            extend_user_schedules_ttl(env, &schedule, ttl_threshold, ttl_extend_to);
+            (1, 0)
+        }
+    } else {
+        (0, 0)
+    }
+}
+
+/// Bumps the persistent TTL on `UserSchedules` for every address `add_user_schedule` indexed
+/// this schedule under — the payer, and either its single `recipient` or, for a split
+/// schedule, every entry in `recipients` — so a `collect_rent` sweep keeps that index alive
+/// too, not just the `Schedule` entry itself.
+fn extend_user_schedules_ttl(env: &Env, schedule: &PaymentSchedule, ttl_threshold: u32, ttl_extend_to: u32) {
+    let payer_key = StorageKey::UserSchedules(schedule.payer.clone());
+    if env.storage().persistent().has(&payer_key) {
+        env.storage().persistent().extend_ttl(&payer_key, ttl_threshold, ttl_extend_to);
+    }
+
+    if let Some(recipients) = &schedule.recipients {
+        for (recipient, _) in recipients.iter() {
+            let key = StorageKey::UserSchedules(recipient);
+            if env.storage().persistent().has(&key) {
+                env.storage().persistent().extend_ttl(&key, ttl_threshold, ttl_extend_to);
+            }
+        }
+    } else {
+        let key = StorageKey::UserSchedules(schedule.recipient.clone());
+        if env.storage().persistent().has(&key) {
+            env.storage().persistent().extend_ttl(&key, ttl_threshold, ttl_extend_to);
+        }
+    }
+}
+
+/// `start_time` and `payment_count` (already incremented for the payment that just settled)
+/// anchor `Monthly`'s calendar-month math; `Daily`/`Weekly`/`Custom` stay a fixed offset from
+/// `current_time` as before, since a fixed-length interval never drifts.
+pub fn calculate_next_payment_time(
+    frequency: &PaymentFrequency,
+    start_time: u64,
+    payment_count: u32,
+    current_time: u64,
+) -> u64 {
     match frequency {
         PaymentFrequency::Daily => current_time + 86400,      // 24 hours
         PaymentFrequency::Weekly => current_time + 604800,    // 7 days
-        PaymentFrequency::Monthly => current_time + 2592000,  // 30 days
+        PaymentFrequency::Monthly => next_monthly_payment_time(start_time, payment_count),
+        PaymentFrequency::Custom(interval_secs) => current_time + interval_secs,
+    }
+}
+
+/// `true` if `year` is a leap year in the proleptic Gregorian calendar.
+fn is_leap_year(year: i64) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+/// Number of days in `month` (1-12) of `year`.
+fn days_in_month(year: i64, month: u32) -> u32 {
+    match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 => if is_leap_year(year) { 29 } else { 28 },
+        _ => 30,
     }
-}
\ No newline at end of file
+}
+
+/// Days since the Unix epoch (1970-01-01) for a given proleptic-Gregorian civil date. Howard
+/// Hinnant's `days_from_civil` algorithm (http://howardhinnant.github.io/date_algorithms.html),
+/// chosen over a pull-in date crate since this is the only place the contract needs calendar
+/// math.
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = if y >= 0 { y } else { y - 399 } / 400;
+    let yoe = (y - era * 400) as u64;
+    let mp = if month > 2 { month - 3 } else { month + 9 } as u64;
+    let doy = (153 * mp + 2) / 5 + day as u64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe as i64 - 719468
+}
+
+/// Inverse of `days_from_civil`: the proleptic-Gregorian `(year, month, day)` for `days` since
+/// the Unix epoch.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719468;
+    let era = if z >= 0 { z } else { z - 146096 } / 146097;
+    let doe = (z - era * 146097) as u64;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let y = yoe as i64 + era * 400;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = if month <= 2 { y + 1 } else { y };
+    (year, month, day)
+}
+
+/// The `payment_count`-th Monthly due date after `start_time`: same day-of-month as
+/// `start_time` (and the same time-of-day), `payment_count` calendar months later, with the
+/// day clamped to the target month's last day so e.g. Jan 31 lands on Feb 28/29 rather than
+/// overflowing into March.
+fn next_monthly_payment_time(start_time: u64, payment_count: u32) -> u64 {
+    let time_of_day = start_time % 86400;
+    let (year, month, day) = civil_from_days((start_time / 86400) as i64);
+
+    let months_from_start = (month as i64 - 1) + payment_count as i64;
+    let target_year = year + months_from_start.div_euclid(12);
+    let target_month = (months_from_start.rem_euclid(12) + 1) as u32;
+    let target_day = day.min(days_in_month(target_year, target_month));
+
+    let target_days = days_from_civil(target_year, target_month, target_day);
+    (target_days * 86400) as u64 + time_of_day
+}
@@ -0,0 +1,117 @@
+use soroban_sdk::{contracttype, symbol_short, Address, Env, Symbol};
+
+/// A fact that can unlock a `PaymentPlan` branch. `Timestamp` needs no witness — it's checked
+/// directly against the ledger clock; `Signature` requires `require_auth` from the named
+/// address before it's considered satisfied. Distinct from `payment_schedule::Witness`, which
+/// only ever gates a single recurring schedule's ordinary recipient payout rather than
+/// branching to a different payee per condition.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum PlanCondition {
+    Timestamp(u64),
+    Signature(Address),
+}
+
+/// A single payout leg of a `PaymentPlan`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Pay {
+    pub amount: u128,
+    pub to: Address,
+}
+
+/// A small escrow-release expression, modeled on the "budget program" pattern: `Pay` releases
+/// unconditionally, `After` releases once its `PlanCondition` is satisfied, and `Race` retires
+/// as soon as *either* branch's condition is satisfied — the other is cancelled atomically by
+/// the same `executed` flag that retires the whole plan. This is the shape a cancelable future
+/// payment takes: a timestamp branch pays the recipient, while the payer's own signature
+/// branch refunds the payer if claimed first.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum PaymentPlan {
+    Pay(Pay),
+    After(PlanCondition, Pay),
+    Race((PlanCondition, Pay), (PlanCondition, Pay)),
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ConditionalPayment {
+    pub id: u64,
+    pub payer: Address,
+    pub token: Address,
+    pub escrowed: u128,
+    pub plan: PaymentPlan,
+    pub executed: bool,
+    pub created_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum StorageKey {
+    Plan(u64),
+}
+
+const PLAN_COUNTER: Symbol = symbol_short!("PLAN_CNT");
+
+pub fn get_next_plan_id(env: &Env) -> u64 {
+    let current = env.storage().instance().get(&PLAN_COUNTER).unwrap_or(0u64);
+    let next = current + 1;
+    env.storage().instance().set(&PLAN_COUNTER, &next);
+    next
+}
+
+pub fn get_plan(env: &Env, plan_id: u64) -> Option<ConditionalPayment> {
+    let key = StorageKey::Plan(plan_id);
+    env.storage().persistent().get(&key)
+}
+
+pub fn set_plan(env: &Env, plan: &ConditionalPayment) {
+    let key = StorageKey::Plan(plan.id);
+    env.storage().persistent().set(&key, plan);
+}
+
+/// The amount that must be escrowed up front to cover every way `plan` could settle. `Race`
+/// escrows the larger of its two branches rather than their sum, since at most one of them
+/// ever pays out.
+pub fn required_escrow(plan: &PaymentPlan) -> u128 {
+    match plan {
+        PaymentPlan::Pay(pay) => pay.amount,
+        PaymentPlan::After(_, pay) => pay.amount,
+        PaymentPlan::Race((_, left), (_, right)) => left.amount.max(right.amount),
+    }
+}
+
+/// Whether `condition` is satisfied as of `current_time`. `Signature` is satisfied only when
+/// `witness` names the same address the condition expects — `apply_plan_witness` is the only
+/// caller that ever passes a `Some(witness)`, and only after that address's own `require_auth`.
+pub fn condition_satisfied(condition: &PlanCondition, current_time: u64, witness: Option<&Address>) -> bool {
+    match condition {
+        PlanCondition::Timestamp(ts) => current_time >= *ts,
+        PlanCondition::Signature(expected) => witness == Some(expected),
+    }
+}
+
+/// The `Pay` leg `plan` resolves to given `current_time`/`witness`, or `None` if nothing has
+/// been satisfied yet.
+pub fn resolve(plan: &PaymentPlan, current_time: u64, witness: Option<&Address>) -> Option<Pay> {
+    match plan {
+        PaymentPlan::Pay(pay) => Some(pay.clone()),
+        PaymentPlan::After(condition, pay) => {
+            if condition_satisfied(condition, current_time, witness) {
+                Some(pay.clone())
+            } else {
+                None
+            }
+        }
+        PaymentPlan::Race((left_condition, left_pay), (right_condition, right_pay)) => {
+            if condition_satisfied(left_condition, current_time, witness) {
+                Some(left_pay.clone())
+            } else if condition_satisfied(right_condition, current_time, witness) {
+                Some(right_pay.clone())
+            } else {
+                None
+            }
+        }
+    }
+}
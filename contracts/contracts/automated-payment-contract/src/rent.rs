@@ -0,0 +1,58 @@
+use crate::alerts;
+use crate::payment_schedule;
+use soroban_sdk::{contracttype, Env};
+
+/// Outcome of a `collect_rent` sweep: how many persistent entries (schedules and alerts
+/// together) had their TTL bumped versus were pruned as stale.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RentReport {
+    pub extended: u32,
+    pub pruned: u32,
+}
+
+/// Schedule ids a single `collect_rent` call will walk. Mirrors the bound every other
+/// full-table scan in this contract (`process_due_payments`, `process_pending_payments`) uses
+/// via `get_schedule_counter`, just capped so one call can't grow unbounded as the contract ages.
+const MAX_SCANNED_SCHEDULES_PER_CALL: u64 = 500;
+
+/// Walks up to `MAX_SCANNED_SCHEDULES_PER_CALL` schedules starting after `offset`, the same
+/// paging convention `process_due_payments` uses to cover a schedule table larger than one
+/// call can scan. For each: prunes the schedule entry and any of its alerts once they've gone
+/// terminal (`Cancelled`/`Completed`/`Abandoned` schedules; resolved alerts) and sat untouched
+/// for longer than `grace_period` seconds, and extends the TTL on every other one. `grace_period` doubles as the TTL
+/// window passed to `extend_ttl` — the same way `price-oracle`'s history archive reuses
+/// `cleanup_window` for both.
+pub fn collect_rent(env: &Env, offset: u32, now: u64, grace_period: u64) -> RentReport {
+    let ttl_extend_to = grace_period.min(u32::MAX as u64) as u32;
+    let ttl_threshold = ttl_extend_to / 2;
+
+    let last_schedule_id = payment_schedule::get_schedule_counter(env);
+    let mut extended = 0u32;
+    let mut pruned = 0u32;
+
+    let mut schedule_id = offset as u64 + 1;
+    let mut scanned = 0u64;
+    while schedule_id <= last_schedule_id && scanned < MAX_SCANNED_SCHEDULES_PER_CALL {
+        let (schedule_extended, schedule_pruned) = payment_schedule::collect_schedule_rent(
+            env,
+            schedule_id,
+            now,
+            grace_period,
+            ttl_threshold,
+            ttl_extend_to,
+        );
+        extended += schedule_extended;
+        pruned += schedule_pruned;
+
+        let (alerts_extended, alerts_pruned) =
+            alerts::collect_schedule_alert_rent(env, schedule_id, now, grace_period, ttl_threshold, ttl_extend_to);
+        extended += alerts_extended;
+        pruned += alerts_pruned;
+
+        schedule_id += 1;
+        scanned += 1;
+    }
+
+    RentReport { extended, pruned }
+}
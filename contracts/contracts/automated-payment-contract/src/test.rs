@@ -2,12 +2,16 @@
 extern crate std;
 
 use crate::{AutomatedPaymentContract, AutomatedPaymentContractClient};
+use crate::payment_schedule;
 use crate::payment_schedule::{PaymentFrequency, ScheduleStatus};
+use crate::plan;
 use crate::error::ContractError;
+use ed25519_dalek::{Signer, SigningKey};
 use soroban_sdk::testutils::Ledger;
+use soroban_sdk::xdr::ToXdr;
 use soroban_sdk::{
     testutils::Address as _,
-    token, Address, Env,
+    token, Address, Bytes, Env,
 };
 use token::Client as TokenClient;
 use token::StellarAssetClient as TokenAdminClient;
@@ -80,14 +84,19 @@ impl<'a> PaymentTest<'a> {
             &PaymentFrequency::Daily,
             &start_time,
             &end_time,
-        )
+            &None,
+        &None,
+            &None,
+        &None,
+            &None,
+            &None)
     }
 
     fn create_daily_schedule_immediate(&self, amount: u128, duration_days: u64) -> u64 {
         // Create schedule that can be executed immediately
         let start_time = self.env.ledger().timestamp();
         let end_time = Some(start_time + (duration_days * 24 * 60 * 60));
-        
+
         let schedule_id = self.contract.create_schedule(
             &self.payer,
             &self.recipient,
@@ -96,8 +105,13 @@ impl<'a> PaymentTest<'a> {
             &PaymentFrequency::Daily,
             &start_time,
             &end_time,
-        );
-        
+            &None,
+        &None,
+            &None,
+        &None,
+            &None,
+            &None);
+
         // The schedule should be immediately executable since start_time equals current time
         schedule_id
     }
@@ -105,7 +119,7 @@ impl<'a> PaymentTest<'a> {
     fn create_weekly_schedule(&self, amount: u128, duration_weeks: u64) -> u64 {
         let start_time = self.env.ledger().timestamp();
         let end_time = Some(start_time + (duration_weeks * 7 * 24 * 60 * 60));
-        
+
         self.contract.create_schedule(
             &self.payer,
             &self.recipient,
@@ -114,13 +128,18 @@ impl<'a> PaymentTest<'a> {
             &PaymentFrequency::Weekly,
             &start_time,
             &end_time,
-        )
+            &None,
+        &None,
+            &None,
+        &None,
+            &None,
+            &None)
     }
 
     fn create_monthly_schedule(&self, amount: u128, duration_months: u64) -> u64 {
         let start_time = self.env.ledger().timestamp();
         let end_time = Some(start_time + (duration_months * 30 * 24 * 60 * 60));
-        
+
         self.contract.create_schedule(
             &self.payer,
             &self.recipient,
@@ -129,7 +148,12 @@ impl<'a> PaymentTest<'a> {
             &PaymentFrequency::Monthly,
             &start_time,
             &end_time,
-        )
+            &None,
+        &None,
+            &None,
+        &None,
+            &None,
+            &None)
     }
 
     fn advance_time(&self, seconds: u64) {
@@ -219,7 +243,12 @@ fn test_create_schedule_zero_amount() {
         &PaymentFrequency::Daily,
         &start_time,
         &end_time,
-    );
+        &None,
+        &None,
+        &None,
+        &None,
+        &None,
+        &None);
     assert!(result.is_err());
     assert_eq!(result.unwrap_err(), Ok(ContractError::InvalidAmount));
 }
@@ -238,7 +267,12 @@ fn test_create_schedule_same_payer_recipient() {
         &PaymentFrequency::Daily,
         &start_time,
         &end_time,
-    );
+        &None,
+        &None,
+        &None,
+        &None,
+        &None,
+        &None);
     assert!(result.is_err());
     assert_eq!(result.unwrap_err(), Ok(ContractError::InvalidInput));
 }
@@ -257,11 +291,73 @@ fn test_create_schedule_invalid_end_time() {
         &PaymentFrequency::Daily,
         &start_time,
         &end_time,
-    );
+        &None,
+        &None,
+        &None,
+        &None,
+        &None,
+        &None);
     assert!(result.is_err());
     assert_eq!(result.unwrap_err(), Ok(ContractError::InvalidEndTime));
 }
 
+#[test]
+fn test_create_schedule_rejects_out_of_bounds_condition_index() {
+    let test = PaymentTest::setup();
+    let start_time = test.env.ledger().timestamp();
+    let end_time = Some(start_time + (7 * 24 * 60 * 60));
+
+    // `And`'s right-hand index points past the end of `nodes`, which would panic
+    // `evaluate_node` at execution time if this were allowed to be stored.
+    let mut nodes = soroban_sdk::Vec::new(&test.env);
+    nodes.push_back(payment_schedule::ConditionKind::AfterTimestamp(start_time));
+    nodes.push_back(payment_schedule::ConditionKind::And(0, 5));
+    let release_condition = payment_schedule::ReleaseCondition { nodes };
+
+    let result = test.contract.try_create_schedule(
+        &test.payer,
+        &test.recipient,
+        &test.token.address,
+        &PAYMENT_AMOUNT,
+        &PaymentFrequency::Daily,
+        &start_time,
+        &end_time,
+        &None,
+        &None,
+        &Some(release_condition),
+        &None,
+        &None,
+        &None);
+    assert!(result.is_err());
+    assert_eq!(result.unwrap_err(), Ok(ContractError::InvalidReleaseCondition));
+}
+
+#[test]
+fn test_create_schedule_rejects_empty_release_condition() {
+    let test = PaymentTest::setup();
+    let start_time = test.env.ledger().timestamp();
+    let end_time = Some(start_time + (7 * 24 * 60 * 60));
+
+    let release_condition = payment_schedule::ReleaseCondition { nodes: soroban_sdk::Vec::new(&test.env) };
+
+    let result = test.contract.try_create_schedule(
+        &test.payer,
+        &test.recipient,
+        &test.token.address,
+        &PAYMENT_AMOUNT,
+        &PaymentFrequency::Daily,
+        &start_time,
+        &end_time,
+        &None,
+        &None,
+        &Some(release_condition),
+        &None,
+        &None,
+        &None);
+    assert!(result.is_err());
+    assert_eq!(result.unwrap_err(), Ok(ContractError::InvalidReleaseCondition));
+}
+
 #[test]
 fn test_create_schedule_insufficient_funds() {
     let test = PaymentTest::setup();
@@ -277,7 +373,12 @@ fn test_create_schedule_insufficient_funds() {
         &PaymentFrequency::Daily,
         &start_time,
         &end_time,
-    );
+        &None,
+        &None,
+        &None,
+        &None,
+        &None,
+        &None);
     assert!(result.is_err());
     assert_eq!(result.unwrap_err(), Ok(ContractError::InsufficientBalance));
 }
@@ -315,7 +416,7 @@ fn test_execute_payment_success() {
     let recipient_balance_before = test.token.balance(&test.recipient);
     let contract_balance_before = test.token.balance(&test.contract.address);
     
-    test.contract.execute_payment(&schedule_id);
+    test.contract.execute_payment(&schedule_id, &None);
     
     // Check payment was made
     assert_eq!(
@@ -349,9 +450,14 @@ fn test_execute_payment_not_due() {
         &PaymentFrequency::Daily,
         &start_time,
         &end_time,
-    );
+        &None,
+        &None,
+        &None,
+        &None,
+        &None,
+        &None);
     
-    let result = test.contract.try_execute_payment(&schedule_id);
+    let result = test.contract.try_execute_payment(&schedule_id, &None);
     assert!(result.is_err());
     assert_eq!(result.unwrap_err(), Ok(ContractError::PaymentNotDue));
 }
@@ -364,7 +470,7 @@ fn test_execute_payment_inactive_schedule() {
     // Deactivate schedule
     test.contract.update_schedule_status(&schedule_id, &test.payer, &false);
     
-    let result = test.contract.try_execute_payment(&schedule_id);
+    let result = test.contract.try_execute_payment(&schedule_id, &None);
     assert!(result.is_err());
     assert_eq!(result.unwrap_err(), Ok(ContractError::ScheduleNotActive));
 }
@@ -372,7 +478,7 @@ fn test_execute_payment_inactive_schedule() {
 #[test]
 fn test_execute_payment_nonexistent_schedule() {
     let test = PaymentTest::setup();
-    let result = test.contract.try_execute_payment(&999);
+    let result = test.contract.try_execute_payment(&999, &None);
     assert!(result.is_err());
     assert_eq!(result.unwrap_err(), Ok(ContractError::ScheduleNotFound));
 }
@@ -385,13 +491,13 @@ fn test_execute_multiple_payments() {
     let recipient_balance_before = test.token.balance(&test.recipient);
     
     // Execute first payment
-    test.contract.execute_payment(&schedule_id);
+    test.contract.execute_payment(&schedule_id, &None);
     
     // Advance time by 1 day
     test.advance_time(24 * 60 * 60);
     
     // Execute second payment
-    test.contract.execute_payment(&schedule_id);
+    test.contract.execute_payment(&schedule_id, &None);
     
     // Check payments were made
     assert_eq!(
@@ -410,13 +516,13 @@ fn test_schedule_completion() {
     let schedule_id = test.create_daily_schedule_immediate(PAYMENT_AMOUNT, 2); // Only 2 days
     
     // Execute first payment
-    test.contract.execute_payment(&schedule_id);
+    test.contract.execute_payment(&schedule_id, &None);
     
     // Advance time to allow for the second payment
     test.advance_time(24 * 60 * 60);
     
     // Execute second (final) payment
-    test.contract.execute_payment(&schedule_id);
+    test.contract.execute_payment(&schedule_id, &None);
     
     // Check schedule status
     let completed_schedule = test.contract.get_schedule(&schedule_id);
@@ -631,4 +737,1432 @@ fn test_get_user_schedules() {
     let new_user = Address::generate(&test.env);
     let empty_schedules = test.get_user_schedules(&new_user, 0, 10);
     assert_eq!(empty_schedules.len(), 0);
-}
\ No newline at end of file
+}
+
+//---
+// Price Guard Tests
+//---
+
+/// Every simulated oracle node in this suite signs with the same fixed keypair, mirroring
+/// price-oracle's own test suite — the signature check exists to reject a bad signature, not
+/// to distinguish which registered node produced it.
+fn oracle_test_signing_key() -> SigningKey {
+    SigningKey::from_bytes(&[7u8; 32])
+}
+
+fn oracle_test_public_key(env: &Env) -> Bytes {
+    Bytes::from_array(env, &oracle_test_signing_key().verifying_key().to_bytes())
+}
+
+/// Mirrors `ValidationEngine::build_signed_message`'s canonical encoding so a price update
+/// signed here carries a signature `submit_price`'s `ed25519_verify` call actually accepts.
+/// Unlike price-oracle's own tests (which call contract functions directly), this suite
+/// invokes `submit_price` through a registered `oracle_contract`, so the message is built
+/// against that contract's address rather than `env.current_contract_address()`.
+fn sign_oracle_price_update(
+    env: &Env,
+    oracle_contract: &Address,
+    asset_symbol: &soroban_sdk::Symbol,
+    price: u64,
+    confidence_interval: u64,
+    timestamp: u64,
+    nonce: u64,
+) -> Bytes {
+    let mut message = Bytes::new(env);
+    message.append(&oracle_contract.to_xdr(env));
+    message.append(&Bytes::from_array(env, &env.ledger().network_id().to_array()));
+    message.append(&asset_symbol.to_xdr(env));
+    message.append(&Bytes::from_array(env, &price.to_be_bytes()));
+    message.append(&Bytes::from_array(env, &confidence_interval.to_be_bytes()));
+    message.append(&Bytes::from_array(env, &timestamp.to_be_bytes()));
+    message.append(&Bytes::from_array(env, &nonce.to_be_bytes()));
+
+    let mut bytes = [0u8; 4096];
+    let len = message.len() as usize;
+    message.copy_into_slice(&mut bytes[..len]);
+    let signature = oracle_test_signing_key().sign(&bytes[..len]);
+    Bytes::from_array(env, &signature.to_bytes())
+}
+
+fn setup_oracle_with_reliable_price(
+    env: &Env,
+    asset: &soroban_sdk::Symbol,
+    price: u64,
+) -> Address {
+    let oracle_admin = Address::generate(env);
+    let oracle_contract = env.register_contract(None, price_oracle::PriceOracle);
+    let oracle = price_oracle::PriceOracleClient::new(env, &oracle_contract);
+    oracle.initialize(&oracle_admin);
+
+    let mut nodes = soroban_sdk::Vec::new(env);
+    for _ in 0..3 {
+        let node = Address::generate(env);
+        let registration = price_oracle::NodeRegistration {
+            node_address: node.clone(),
+            stake_amount: 2000_0000000,
+            metadata: soroban_sdk::Symbol::new(env, "test_oracle_node"),
+            public_key: oracle_test_public_key(env),
+            signature_scheme: price_oracle::SignatureScheme::Ed25519,
+        };
+        oracle.register_oracle_node(&node, &registration);
+        nodes.push_back(node);
+    }
+
+    for (i, node) in nodes.iter().enumerate() {
+        let timestamp = env.ledger().timestamp();
+        let nonce = (i as u64) + 1;
+        let signature = sign_oracle_price_update(env, &oracle_contract, asset, price, price, timestamp, nonce);
+        let update = price_oracle::PriceUpdateRequest {
+            asset_symbol: asset.clone(),
+            price,
+            timestamp,
+            nonce,
+            confidence_interval: price,
+            scheme: price_oracle::SignatureScheme::Ed25519,
+            signature,
+        };
+        oracle.submit_price(&node, &update);
+    }
+
+    oracle_contract
+}
+
+#[test]
+fn test_execute_payment_with_price_guard_matching_view() {
+    let test = PaymentTest::setup();
+    let asset = soroban_sdk::Symbol::new(&test.env, "XLM");
+    let oracle_contract = setup_oracle_with_reliable_price(&test.env, &asset, 1000000);
+
+    let start_time = test.env.ledger().timestamp();
+    let end_time = Some(start_time + (7 * 24 * 60 * 60));
+    let price_guard = payment_schedule::PriceGuard {
+        oracle_contract,
+        asset_symbol: asset,
+        max_age_seconds: 300,
+        max_deviation_bps: 100,
+    };
+
+    let schedule_id = test.contract.create_schedule(
+        &test.payer,
+        &test.recipient,
+        &test.token.address,
+        &PAYMENT_AMOUNT,
+        &PaymentFrequency::Daily,
+        &start_time,
+        &end_time,
+        &Some(price_guard),
+        &None,
+        &None,
+        &None,
+        &None,
+        &None);
+
+    let recipient_balance_before = test.token.balance(&test.recipient);
+    test.contract.execute_payment(&schedule_id, &Some(1000000));
+
+    assert_eq!(
+        test.token.balance(&test.recipient),
+        recipient_balance_before + PAYMENT_AMOUNT as i128
+    );
+}
+
+#[test]
+fn test_execute_payment_with_price_guard_requires_expected_price() {
+    let test = PaymentTest::setup();
+    let asset = soroban_sdk::Symbol::new(&test.env, "XLM");
+    let oracle_contract = setup_oracle_with_reliable_price(&test.env, &asset, 1000000);
+
+    let start_time = test.env.ledger().timestamp();
+    let end_time = Some(start_time + (7 * 24 * 60 * 60));
+    let price_guard = payment_schedule::PriceGuard {
+        oracle_contract,
+        asset_symbol: asset,
+        max_age_seconds: 300,
+        max_deviation_bps: 100,
+    };
+
+    let schedule_id = test.contract.create_schedule(
+        &test.payer,
+        &test.recipient,
+        &test.token.address,
+        &PAYMENT_AMOUNT,
+        &PaymentFrequency::Daily,
+        &start_time,
+        &end_time,
+        &Some(price_guard),
+        &None,
+        &None,
+        &None,
+        &None,
+        &None);
+
+    let result = test.contract.try_execute_payment(&schedule_id, &None);
+    assert!(result.is_err());
+    assert_eq!(result.unwrap_err(), Ok(ContractError::PriceViewRequired));
+}
+
+#[test]
+#[should_panic]
+fn test_execute_payment_with_price_guard_reverts_on_drifted_price() {
+    let test = PaymentTest::setup();
+    let asset = soroban_sdk::Symbol::new(&test.env, "XLM");
+    let oracle_contract = setup_oracle_with_reliable_price(&test.env, &asset, 1000000);
+
+    let start_time = test.env.ledger().timestamp();
+    let end_time = Some(start_time + (7 * 24 * 60 * 60));
+    let price_guard = payment_schedule::PriceGuard {
+        oracle_contract,
+        asset_symbol: asset,
+        max_age_seconds: 300,
+        max_deviation_bps: 100,
+    };
+
+    let schedule_id = test.contract.create_schedule(
+        &test.payer,
+        &test.recipient,
+        &test.token.address,
+        &PAYMENT_AMOUNT,
+        &PaymentFrequency::Daily,
+        &start_time,
+        &end_time,
+        &Some(price_guard),
+        &None,
+        &None,
+        &None,
+        &None,
+        &None);
+
+    // The caller signed against a price far from what the oracle now reports, so the
+    // bundled assertion should revert the whole transaction before any funds move.
+    test.contract.execute_payment(&schedule_id, &Some(2000000));
+}
+
+//---
+// Price Trigger Tests
+//---
+
+#[test]
+fn test_execute_payment_price_trigger_withholds_until_threshold_crossed() {
+    let test = PaymentTest::setup();
+    let asset = soroban_sdk::Symbol::new(&test.env, "XLM");
+    let oracle_contract = setup_oracle_with_reliable_price(&test.env, &asset, 900000);
+
+    let start_time = test.env.ledger().timestamp();
+    let end_time = Some(start_time + (7 * 24 * 60 * 60));
+    let price_trigger = payment_schedule::PriceTrigger {
+        oracle_contract: oracle_contract.clone(),
+        asset_symbol: asset.clone(),
+        comparator: payment_schedule::PriceComparator::Below,
+        threshold: 800000,
+        min_confidence: 70,
+    };
+
+    let schedule_id = test.contract.create_schedule(
+        &test.payer,
+        &test.recipient,
+        &test.token.address,
+        &PAYMENT_AMOUNT,
+        &PaymentFrequency::Daily,
+        &start_time,
+        &end_time,
+        &None,
+        &Some(price_trigger),
+        &None,
+        &None,
+        &None,
+        &None,
+    );
+
+    // The oracle still reports 900000, above the 800000 "buy the dip" threshold.
+    let result = test.contract.try_execute_payment(&schedule_id, &None);
+    assert!(result.is_err());
+    assert_eq!(result.unwrap_err(), Ok(ContractError::PriceTriggerNotMet));
+
+    // A fresh round of reliable submissions (enough to meet `min_submissions` and resolve)
+    // drops the aggregated price below the threshold.
+    let oracle = price_oracle::PriceOracleClient::new(&test.env, &oracle_contract);
+    for _ in 0..3 {
+        let node = Address::generate(&test.env);
+        let registration = price_oracle::NodeRegistration {
+            node_address: node.clone(),
+            stake_amount: 2000_0000000,
+            metadata: soroban_sdk::Symbol::new(&test.env, "test_oracle_node"),
+            public_key: oracle_test_public_key(&test.env),
+            signature_scheme: price_oracle::SignatureScheme::Ed25519,
+        };
+        oracle.register_oracle_node(&node, &registration);
+        let timestamp = test.env.ledger().timestamp();
+        let signature = sign_oracle_price_update(&test.env, &oracle_contract, &asset, 700000, 700000, timestamp, 1);
+        oracle.submit_price(
+            &node,
+            &price_oracle::PriceUpdateRequest {
+                asset_symbol: asset.clone(),
+                price: 700000,
+                timestamp,
+                nonce: 1,
+                confidence_interval: 700000,
+                scheme: price_oracle::SignatureScheme::Ed25519,
+                signature,
+            },
+        );
+    }
+
+    let recipient_balance_before = test.token.balance(&test.recipient);
+    test.contract.execute_payment(&schedule_id, &None);
+    assert_eq!(
+        test.token.balance(&test.recipient),
+        recipient_balance_before + PAYMENT_AMOUNT as i128
+    );
+}
+
+//---
+// Escrow (Witness Condition) Tests
+//---
+
+#[test]
+fn test_execute_payment_after_timestamp_condition_blocks_until_due() {
+    let test = PaymentTest::setup();
+    let start_time = test.env.ledger().timestamp();
+    let end_time = Some(start_time + (7 * 24 * 60 * 60));
+    let release_at = start_time + 3600;
+
+    let mut nodes = soroban_sdk::Vec::new(&test.env);
+    nodes.push_back(payment_schedule::ConditionKind::AfterTimestamp(release_at));
+    let release_condition = payment_schedule::ReleaseCondition { nodes };
+
+    let schedule_id = test.contract.create_schedule(
+        &test.payer,
+        &test.recipient,
+        &test.token.address,
+        &PAYMENT_AMOUNT,
+        &PaymentFrequency::Daily,
+        &start_time,
+        &end_time,
+        &None,
+        &None,
+        &Some(release_condition),
+        &None,
+        &None,
+        &None);
+
+    let result = test.contract.try_execute_payment(&schedule_id, &None);
+    assert!(result.is_err());
+    assert_eq!(result.unwrap_err(), Ok(ContractError::ConditionNotSatisfied));
+
+    test.advance_time(3601);
+
+    let recipient_balance_before = test.token.balance(&test.recipient);
+    test.contract.execute_payment(&schedule_id, &None);
+    assert_eq!(
+        test.token.balance(&test.recipient),
+        recipient_balance_before + PAYMENT_AMOUNT as i128
+    );
+}
+
+#[test]
+fn test_execute_payment_on_approval_condition_requires_arbiter_witness() {
+    let test = PaymentTest::setup();
+    let arbiter = Address::generate(&test.env);
+    let start_time = test.env.ledger().timestamp();
+    let end_time = Some(start_time + (7 * 24 * 60 * 60));
+
+    let mut nodes = soroban_sdk::Vec::new(&test.env);
+    nodes.push_back(payment_schedule::ConditionKind::OnApproval(arbiter.clone()));
+    let release_condition = payment_schedule::ReleaseCondition { nodes };
+
+    let schedule_id = test.contract.create_schedule(
+        &test.payer,
+        &test.recipient,
+        &test.token.address,
+        &PAYMENT_AMOUNT,
+        &PaymentFrequency::Daily,
+        &start_time,
+        &end_time,
+        &None,
+        &None,
+        &Some(release_condition),
+        &None,
+        &None,
+        &None);
+
+    let result = test.contract.try_execute_payment(&schedule_id, &None);
+    assert!(result.is_err());
+    assert_eq!(result.unwrap_err(), Ok(ContractError::ConditionNotSatisfied));
+
+    test.contract.apply_witness(&schedule_id, &payment_schedule::Witness::Approval(arbiter));
+
+    let recipient_balance_before = test.token.balance(&test.recipient);
+    test.contract.execute_payment(&schedule_id, &None);
+    assert_eq!(
+        test.token.balance(&test.recipient),
+        recipient_balance_before + PAYMENT_AMOUNT as i128
+    );
+}
+
+#[test]
+fn test_execute_payment_and_condition_requires_both_branches() {
+    let test = PaymentTest::setup();
+    let arbiter = Address::generate(&test.env);
+    let start_time = test.env.ledger().timestamp();
+    let end_time = Some(start_time + (7 * 24 * 60 * 60));
+    let release_at = start_time + 3600;
+
+    // nodes[0] = And(1, 2), nodes[1] = AfterTimestamp, nodes[2] = OnApproval
+    let mut nodes = soroban_sdk::Vec::new(&test.env);
+    nodes.push_back(payment_schedule::ConditionKind::And(1, 2));
+    nodes.push_back(payment_schedule::ConditionKind::AfterTimestamp(release_at));
+    nodes.push_back(payment_schedule::ConditionKind::OnApproval(arbiter.clone()));
+    let release_condition = payment_schedule::ReleaseCondition { nodes };
+
+    let schedule_id = test.contract.create_schedule(
+        &test.payer,
+        &test.recipient,
+        &test.token.address,
+        &PAYMENT_AMOUNT,
+        &PaymentFrequency::Daily,
+        &start_time,
+        &end_time,
+        &None,
+        &None,
+        &Some(release_condition),
+        &None,
+        &None,
+        &None);
+
+    test.contract.apply_witness(&schedule_id, &payment_schedule::Witness::Approval(arbiter));
+
+    // Approved, but the timestamp branch is still unmet.
+    let result = test.contract.try_execute_payment(&schedule_id, &None);
+    assert!(result.is_err());
+    assert_eq!(result.unwrap_err(), Ok(ContractError::ConditionNotSatisfied));
+
+    test.advance_time(3601);
+
+    let recipient_balance_before = test.token.balance(&test.recipient);
+    test.contract.execute_payment(&schedule_id, &None);
+    assert_eq!(
+        test.token.balance(&test.recipient),
+        recipient_balance_before + PAYMENT_AMOUNT as i128
+    );
+}
+
+#[test]
+fn test_execute_payment_or_condition_satisfied_by_either_branch() {
+    let test = PaymentTest::setup();
+    let arbiter = Address::generate(&test.env);
+    let start_time = test.env.ledger().timestamp();
+    let end_time = Some(start_time + (7 * 24 * 60 * 60));
+    let release_at = start_time + (365 * 24 * 60 * 60); // far in the future
+
+    // nodes[0] = Or(1, 2), nodes[1] = AfterTimestamp (unmet), nodes[2] = OnApproval
+    let mut nodes = soroban_sdk::Vec::new(&test.env);
+    nodes.push_back(payment_schedule::ConditionKind::Or(1, 2));
+    nodes.push_back(payment_schedule::ConditionKind::AfterTimestamp(release_at));
+    nodes.push_back(payment_schedule::ConditionKind::OnApproval(arbiter.clone()));
+    let release_condition = payment_schedule::ReleaseCondition { nodes };
+
+    let schedule_id = test.contract.create_schedule(
+        &test.payer,
+        &test.recipient,
+        &test.token.address,
+        &PAYMENT_AMOUNT,
+        &PaymentFrequency::Daily,
+        &start_time,
+        &end_time,
+        &None,
+        &None,
+        &Some(release_condition),
+        &None,
+        &None,
+        &None);
+
+    test.contract.apply_witness(&schedule_id, &payment_schedule::Witness::Approval(arbiter));
+
+    let recipient_balance_before = test.token.balance(&test.recipient);
+    test.contract.execute_payment(&schedule_id, &None);
+    assert_eq!(
+        test.token.balance(&test.recipient),
+        recipient_balance_before + PAYMENT_AMOUNT as i128
+    );
+}
+
+#[test]
+fn test_execute_payment_multi_witness_condition_requires_quorum() {
+    let test = PaymentTest::setup();
+    let witness_a = Address::generate(&test.env);
+    let witness_b = Address::generate(&test.env);
+    let witness_c = Address::generate(&test.env);
+    let start_time = test.env.ledger().timestamp();
+    let end_time = Some(start_time + (7 * 24 * 60 * 60));
+
+    let mut approvers = soroban_sdk::Vec::new(&test.env);
+    approvers.push_back(witness_a.clone());
+    approvers.push_back(witness_b.clone());
+    approvers.push_back(witness_c.clone());
+
+    let mut nodes = soroban_sdk::Vec::new(&test.env);
+    nodes.push_back(payment_schedule::ConditionKind::MultiWitness {
+        required: 2,
+        approvers,
+    });
+    let release_condition = payment_schedule::ReleaseCondition { nodes };
+
+    let schedule_id = test.contract.create_schedule(
+        &test.payer,
+        &test.recipient,
+        &test.token.address,
+        &PAYMENT_AMOUNT,
+        &PaymentFrequency::Daily,
+        &start_time,
+        &end_time,
+        &None,
+        &None,
+        &Some(release_condition),
+        &None,
+        &None,
+        &None);
+
+    test.contract.apply_witness(&schedule_id, &payment_schedule::Witness::Approval(witness_a));
+
+    // Only one of the two required witnesses has signed off.
+    let result = test.contract.try_execute_payment(&schedule_id, &None);
+    assert!(result.is_err());
+    assert_eq!(result.unwrap_err(), Ok(ContractError::ConditionNotSatisfied));
+
+    test.contract.apply_witness(&schedule_id, &payment_schedule::Witness::Approval(witness_b));
+
+    let recipient_balance_before = test.token.balance(&test.recipient);
+    test.contract.execute_payment(&schedule_id, &None);
+    assert_eq!(
+        test.token.balance(&test.recipient),
+        recipient_balance_before + PAYMENT_AMOUNT as i128
+    );
+}
+
+//---
+// Graded Vesting Tests
+//---
+
+#[test]
+fn test_claim_vesting_respects_cliff_and_unlocks_per_period() {
+    let test = PaymentTest::setup();
+    let start_time = test.env.ledger().timestamp() + 3600; // cliff: vesting starts in 1 hour
+    let vesting = payment_schedule::VestingPlan {
+        period: 100,
+        per_period: 10,
+        period_count: 5,
+    };
+
+    let schedule_id = test.contract.create_schedule(
+        &test.payer,
+        &test.recipient,
+        &test.token.address,
+        &PAYMENT_AMOUNT,
+        &PaymentFrequency::Daily,
+        &start_time,
+        &None,
+        &None,
+        &None,
+        &None,
+        &Some(vesting),
+        &None,
+        &None);
+
+    // Before the cliff, nothing has unlocked.
+    let result = test.contract.try_claim(&schedule_id, &test.recipient);
+    assert!(result.is_err());
+    assert_eq!(result.unwrap_err(), Ok(ContractError::PaymentNotDue));
+
+    test.advance_time(3600 + 250); // 2 full periods past start_time
+
+    let recipient_balance_before = test.token.balance(&test.recipient);
+    test.contract.claim(&schedule_id, &test.recipient);
+    assert_eq!(test.token.balance(&test.recipient), recipient_balance_before + 20);
+
+    let schedule = test.contract.get_schedule(&schedule_id);
+    assert_eq!(schedule.total_paid, 20);
+    assert_eq!(schedule.status, ScheduleStatus::Active);
+
+    // Claiming again immediately yields nothing new.
+    let result = test.contract.try_claim(&schedule_id, &test.recipient);
+    assert!(result.is_err());
+    assert_eq!(result.unwrap_err(), Ok(ContractError::PaymentNotDue));
+}
+
+#[test]
+fn test_claim_vesting_completes_schedule_after_final_period() {
+    let test = PaymentTest::setup();
+    let start_time = test.env.ledger().timestamp();
+    let vesting = payment_schedule::VestingPlan {
+        period: 100,
+        per_period: 10,
+        period_count: 5,
+    };
+
+    let schedule_id = test.contract.create_schedule(
+        &test.payer,
+        &test.recipient,
+        &test.token.address,
+        &PAYMENT_AMOUNT,
+        &PaymentFrequency::Daily,
+        &start_time,
+        &None,
+        &None,
+        &None,
+        &None,
+        &Some(vesting),
+        &None,
+        &None);
+
+    // Far past the last period; elapsed_periods must clamp to period_count, not over-unlock.
+    test.advance_time(100_000);
+
+    let recipient_balance_before = test.token.balance(&test.recipient);
+    test.contract.claim(&schedule_id, &test.recipient);
+    assert_eq!(test.token.balance(&test.recipient), recipient_balance_before + 50);
+
+    let schedule = test.contract.get_schedule(&schedule_id);
+    assert_eq!(schedule.total_paid, 50);
+    assert_eq!(schedule.status, ScheduleStatus::Completed);
+}
+
+#[test]
+fn test_claim_rejects_non_recipient_and_non_vesting_schedule() {
+    let test = PaymentTest::setup();
+    let vesting = payment_schedule::VestingPlan {
+        period: 100,
+        per_period: 10,
+        period_count: 5,
+    };
+    let start_time = test.env.ledger().timestamp();
+
+    let schedule_id = test.contract.create_schedule(
+        &test.payer,
+        &test.recipient,
+        &test.token.address,
+        &PAYMENT_AMOUNT,
+        &PaymentFrequency::Daily,
+        &start_time,
+        &None,
+        &None,
+        &None,
+        &None,
+        &Some(vesting),
+        &None,
+        &None);
+
+    let result = test.contract.try_claim(&schedule_id, &test.payer);
+    assert!(result.is_err());
+    assert_eq!(result.unwrap_err(), Ok(ContractError::RecipientOnly));
+
+    let ordinary_schedule_id = test.create_daily_schedule(PAYMENT_AMOUNT, 7);
+    let result = test.contract.try_claim(&ordinary_schedule_id, &test.recipient);
+    assert!(result.is_err());
+    assert_eq!(result.unwrap_err(), Ok(ContractError::OperationNotAllowed));
+}
+
+//---
+// Oracle-Denominated Payment Tests
+//---
+
+#[test]
+fn test_execute_payment_with_denomination_converts_quote_to_token_at_live_price() {
+    let test = PaymentTest::setup();
+    let asset = soroban_sdk::Symbol::new(&test.env, "XLM");
+    let oracle_contract = setup_oracle_with_reliable_price(&test.env, &asset, 1000000);
+
+    let start_time = test.env.ledger().timestamp();
+    let end_time = Some(start_time + (24 * 60 * 60));
+    let denomination = payment_schedule::Denomination {
+        oracle_contract,
+        asset_symbol: asset,
+        decimals: 7,
+    };
+
+    // quote_amount (PAYMENT_AMOUNT's stand-in here) is 5 "USD"; at a price of 1_000_000 and
+    // 7 decimals that's 5 * 10^7 / 1_000_000 = 50 token units per payment.
+    let schedule_id = test.contract.create_schedule(
+        &test.payer,
+        &test.recipient,
+        &test.token.address,
+        &5,
+        &PaymentFrequency::Daily,
+        &start_time,
+        &end_time,
+        &None,
+        &None,
+        &None,
+        &None,
+        &Some(denomination),
+        &None);
+
+    let recipient_balance_before = test.token.balance(&test.recipient);
+    test.contract.execute_payment(&schedule_id, &None);
+
+    assert_eq!(test.token.balance(&test.recipient), recipient_balance_before + 50);
+
+    let schedule = test.contract.get_schedule(&schedule_id);
+    assert_eq!(schedule.total_paid, 50);
+}
+
+#[test]
+fn test_create_schedule_with_denomination_rejects_unavailable_price() {
+    let test = PaymentTest::setup();
+    let asset = soroban_sdk::Symbol::new(&test.env, "XLM");
+
+    let oracle_admin = Address::generate(&test.env);
+    let oracle_contract = test.env.register_contract(None, price_oracle::PriceOracle);
+    let oracle = price_oracle::PriceOracleClient::new(&test.env, &oracle_contract);
+    oracle.initialize(&oracle_admin);
+
+    let start_time = test.env.ledger().timestamp();
+    let end_time = Some(start_time + (24 * 60 * 60));
+    let denomination = payment_schedule::Denomination {
+        oracle_contract,
+        asset_symbol: asset,
+        decimals: 7,
+    };
+
+    let result = test.contract.try_create_schedule(
+        &test.payer,
+        &test.recipient,
+        &test.token.address,
+        &5,
+        &PaymentFrequency::Daily,
+        &start_time,
+        &end_time,
+        &None,
+        &None,
+        &None,
+        &None,
+        &Some(denomination),
+        &None);
+
+    assert!(result.is_err());
+    assert_eq!(result.unwrap_err(), Ok(ContractError::PriceUnavailable));
+}
+
+//---
+// Batch Keeper Tests
+//---
+
+#[test]
+fn test_process_due_payments_settles_multiple_due_schedules() {
+    let test = PaymentTest::setup();
+    let schedule_id1 = test.create_daily_schedule_immediate(PAYMENT_AMOUNT, 7);
+    let schedule_id2 = test.create_daily_schedule_immediate(PAYMENT_AMOUNT, 7);
+    let schedule_id3 = test.create_daily_schedule_immediate(PAYMENT_AMOUNT, 7);
+
+    let recipient_balance_before = test.token.balance(&test.recipient);
+    let results = test.contract.process_due_payments(&0, &10);
+
+    assert_eq!(results.len(), 3);
+    for (expected_id, result) in [schedule_id1, schedule_id2, schedule_id3].iter().zip(results.iter()) {
+        assert_eq!(result.schedule_id, *expected_id);
+        assert_eq!(result.outcome, payment_schedule::ProcessOutcome::Succeeded);
+    }
+
+    assert_eq!(
+        test.token.balance(&test.recipient),
+        recipient_balance_before + (PAYMENT_AMOUNT as i128) * 3
+    );
+}
+
+#[test]
+fn test_process_due_payments_respects_limit() {
+    let test = PaymentTest::setup();
+    test.create_daily_schedule_immediate(PAYMENT_AMOUNT, 7);
+    test.create_daily_schedule_immediate(PAYMENT_AMOUNT, 7);
+    let schedule_id3 = test.create_daily_schedule_immediate(PAYMENT_AMOUNT, 7);
+
+    let results = test.contract.process_due_payments(&0, &2);
+    assert_eq!(results.len(), 2);
+
+    // The third schedule was never reached by the capped batch.
+    let schedule3 = test.contract.get_schedule(&schedule_id3);
+    assert_eq!(schedule3.payment_count, 0);
+}
+
+#[test]
+fn test_process_due_payments_offset_resumes_after_a_prior_page() {
+    let test = PaymentTest::setup();
+    test.create_daily_schedule_immediate(PAYMENT_AMOUNT, 7);
+    let schedule_id2 = test.create_daily_schedule_immediate(PAYMENT_AMOUNT, 7);
+    let schedule_id3 = test.create_daily_schedule_immediate(PAYMENT_AMOUNT, 7);
+
+    let first_page = test.contract.process_due_payments(&0, &1);
+    assert_eq!(first_page.len(), 1);
+
+    // Resuming with offset = 1 skips the schedule the first page already settled.
+    let second_page = test.contract.process_due_payments(&1, &10);
+    assert_eq!(second_page.len(), 2);
+    assert_eq!(second_page.get(0).unwrap().schedule_id, schedule_id2);
+    assert_eq!(second_page.get(1).unwrap().schedule_id, schedule_id3);
+}
+
+#[test]
+fn test_process_due_payments_skips_price_guarded_schedule() {
+    let test = PaymentTest::setup();
+    let asset = soroban_sdk::Symbol::new(&test.env, "XLM");
+    let oracle_contract = setup_oracle_with_reliable_price(&test.env, &asset, 1000000);
+
+    let start_time = test.env.ledger().timestamp();
+    let end_time = Some(start_time + (7 * 24 * 60 * 60));
+    let price_guard = payment_schedule::PriceGuard {
+        oracle_contract,
+        asset_symbol: asset,
+        max_age_seconds: 300,
+        max_deviation_bps: 100,
+    };
+
+    let schedule_id = test.contract.create_schedule(
+        &test.payer,
+        &test.recipient,
+        &test.token.address,
+        &PAYMENT_AMOUNT,
+        &PaymentFrequency::Daily,
+        &start_time,
+        &end_time,
+        &Some(price_guard),
+        &None,
+        &None,
+        &None,
+        &None,
+        &None);
+
+    let results = test.contract.process_due_payments(&0, &10);
+    assert_eq!(results.len(), 1);
+    assert_eq!(results.get(0).unwrap().outcome, payment_schedule::ProcessOutcome::Skipped);
+
+    let schedule = test.contract.get_schedule(&schedule_id);
+    assert_eq!(schedule.payment_count, 0);
+}
+
+//---
+// Per-user / Global Due-Payment Scan Tests
+//---
+
+#[test]
+fn test_run_due_payment_scan_settles_only_the_given_users_schedules() {
+    let test = PaymentTest::setup();
+    let schedule_id = test.create_daily_schedule_immediate(PAYMENT_AMOUNT, 7);
+
+    let other_payer = Address::generate(&test.env);
+    TokenAdminClient::new(&test.env, &test.token.address).mint(&other_payer, &(INITIAL_BALANCE as i128));
+    let other_recipient = Address::generate(&test.env);
+    let start_time = test.env.ledger().timestamp();
+    let end_time = Some(start_time + (7 * 24 * 60 * 60));
+    let other_schedule_id = test.contract.create_schedule(
+        &other_payer,
+        &other_recipient,
+        &test.token.address,
+        &PAYMENT_AMOUNT,
+        &PaymentFrequency::Daily,
+        &start_time,
+        &end_time,
+        &None,
+        &None,
+        &None,
+        &None,
+        &None,
+        &None);
+
+    let settled = test.contract.run_due_payment_scan(&test.payer, &start_time, &600);
+
+    assert_eq!(settled.len(), 1);
+    assert_eq!(settled.get(0).unwrap(), schedule_id);
+    assert_eq!(test.contract.get_schedule(&schedule_id).payment_count, 1);
+    assert_eq!(test.contract.get_schedule(&other_schedule_id).payment_count, 0);
+}
+
+#[test]
+fn test_run_due_payment_scan_rejects_overlapping_call_within_staleness_window() {
+    let test = PaymentTest::setup();
+    test.create_daily_schedule_immediate(PAYMENT_AMOUNT, 7);
+    let now = test.env.ledger().timestamp();
+
+    payment_schedule::set_scan_in_progress_at(&test.env, now);
+
+    let result = test.contract.try_run_due_payment_scan(&test.payer, &now, &600);
+    assert!(result.is_err());
+    assert_eq!(result.unwrap_err(), Ok(ContractError::ScanInProgress));
+}
+
+#[test]
+fn test_run_due_payment_scan_reclaims_lock_past_staleness_window() {
+    let test = PaymentTest::setup();
+    let schedule_id = test.create_daily_schedule_immediate(PAYMENT_AMOUNT, 7);
+    let now = test.env.ledger().timestamp();
+
+    // Simulate a scan that started long enough ago to count as abandoned.
+    payment_schedule::set_scan_in_progress_at(&test.env, now.saturating_sub(1000));
+
+    let settled = test.contract.run_due_payment_scan(&test.payer, &now, &600);
+    assert_eq!(settled.len(), 1);
+    assert_eq!(settled.get(0).unwrap(), schedule_id);
+}
+
+#[test]
+fn test_run_due_payment_scan_all_users_settles_every_due_schedule() {
+    let test = PaymentTest::setup();
+    let schedule_id1 = test.create_daily_schedule_immediate(PAYMENT_AMOUNT, 7);
+
+    let other_payer = Address::generate(&test.env);
+    TokenAdminClient::new(&test.env, &test.token.address).mint(&other_payer, &(INITIAL_BALANCE as i128));
+    let other_recipient = Address::generate(&test.env);
+    let start_time = test.env.ledger().timestamp();
+    let end_time = Some(start_time + (7 * 24 * 60 * 60));
+    let schedule_id2 = test.contract.create_schedule(
+        &other_payer,
+        &other_recipient,
+        &test.token.address,
+        &PAYMENT_AMOUNT,
+        &PaymentFrequency::Daily,
+        &start_time,
+        &end_time,
+        &None,
+        &None,
+        &None,
+        &None,
+        &None,
+        &None);
+
+    let settled = test.contract.run_due_payment_scan_all_users(&start_time, &600);
+
+    assert_eq!(settled.len(), 2);
+    assert_eq!(test.contract.get_schedule(&schedule_id1).payment_count, 1);
+    assert_eq!(test.contract.get_schedule(&schedule_id2).payment_count, 1);
+}
+
+//---
+// Retry Backoff / Pending-Payment Tests
+//---
+
+#[test]
+fn test_execute_payment_failure_backs_off_then_deactivates_with_alert() {
+    let test = PaymentTest::setup();
+    let schedule_id = test.create_daily_schedule_immediate(PAYMENT_AMOUNT, 7);
+
+    // Drain the contract's token balance so the scheduled transfer fails.
+    TokenAdminClient::new(&test.env, &test.token.address)
+        .clawback(&test.contract.address, &(PAYMENT_AMOUNT as i128 * 7));
+
+    test.contract.try_execute_payment(&schedule_id, &None).ok();
+    let schedule = test.contract.get_schedule(&schedule_id);
+    assert_eq!(schedule.failed_attempts, 1);
+    assert_eq!(schedule.status, ScheduleStatus::Active);
+    assert_eq!(schedule.next_payment_time, test.env.ledger().timestamp() + 3600 * 2);
+
+    test.advance_time(3600 * 2 + 1);
+    test.contract.try_execute_payment(&schedule_id, &None).ok();
+    test.advance_time(3600 * 4 + 1);
+    test.contract.try_execute_payment(&schedule_id, &None).ok();
+
+    let schedule = test.contract.get_schedule(&schedule_id);
+    assert_eq!(schedule.failed_attempts, 3);
+    assert_eq!(schedule.status, ScheduleStatus::Inactive);
+
+    let alerts = test.contract.get_schedule_alerts(&schedule_id, &10);
+    assert_eq!(alerts.len(), 1);
+    assert_eq!(alerts.get(0).unwrap().alert_type, crate::alerts::AlertType::PaymentFailed);
+}
+
+#[test]
+fn test_process_pending_payments_retries_only_previously_failed_schedules() {
+    let test = PaymentTest::setup();
+    let failing_id = test.create_daily_schedule_immediate(PAYMENT_AMOUNT, 7);
+    let healthy_id = test.create_daily_schedule_immediate(PAYMENT_AMOUNT, 7);
+
+    // Drain both schedules' escrow (7 payments each) so `failing_id`'s transfer fails.
+    let token_admin = TokenAdminClient::new(&test.env, &test.token.address);
+    token_admin.clawback(&test.contract.address, &(PAYMENT_AMOUNT as i128 * 14));
+    test.contract.try_execute_payment(&failing_id, &None).ok();
+
+    // Top the contract back up so the retry can actually succeed.
+    token_admin.mint(&test.contract.address, &(PAYMENT_AMOUNT as i128 * 7));
+    test.advance_time(3600 * 2 + 1);
+
+    // The ordinary due-payment scan skips schedules with a prior failure.
+    let due_results = test.contract.process_due_payments(&0, &10);
+    assert_eq!(due_results.len(), 1);
+    assert_eq!(due_results.get(0).unwrap().schedule_id, healthy_id);
+
+    let retry_results = test.contract.process_pending_payments(&10);
+    assert_eq!(retry_results.len(), 1);
+    assert_eq!(retry_results.get(0).unwrap().schedule_id, failing_id);
+    assert_eq!(retry_results.get(0).unwrap().outcome, payment_schedule::ProcessOutcome::Succeeded);
+}
+
+#[test]
+fn test_reset_failed_attempts_reactivates_and_clears_backoff() {
+    let test = PaymentTest::setup();
+    let schedule_id = test.create_daily_schedule_immediate(PAYMENT_AMOUNT, 7);
+
+    let token_admin = TokenAdminClient::new(&test.env, &test.token.address);
+    token_admin.clawback(&test.contract.address, &(PAYMENT_AMOUNT as i128 * 7));
+    test.contract.try_execute_payment(&schedule_id, &None).ok();
+    test.advance_time(3600 * 2 + 1);
+    test.contract.try_execute_payment(&schedule_id, &None).ok();
+    test.advance_time(3600 * 4 + 1);
+    test.contract.try_execute_payment(&schedule_id, &None).ok();
+
+    assert_eq!(test.contract.get_schedule(&schedule_id).status, ScheduleStatus::Inactive);
+
+    token_admin.mint(&test.contract.address, &(PAYMENT_AMOUNT as i128 * 7));
+    test.contract.reset_failed_attempts(&schedule_id, &test.payer);
+
+    let schedule = test.contract.get_schedule(&schedule_id);
+    assert_eq!(schedule.status, ScheduleStatus::Active);
+    assert_eq!(schedule.failed_attempts, 0);
+    assert_eq!(schedule.next_payment_time, test.env.ledger().timestamp());
+}
+
+//---
+// Split Schedule Tests
+//---
+
+#[test]
+fn test_create_split_schedule_divides_payment_by_weight() {
+    let test = PaymentTest::setup();
+    let recipient_two = Address::generate(&test.env);
+    let start_time = test.env.ledger().timestamp();
+    let end_time = Some(start_time + (7 * 24 * 60 * 60));
+
+    let recipients = soroban_sdk::vec![
+        &test.env,
+        (test.recipient.clone(), 3u32),
+        (recipient_two.clone(), 1u32),
+    ];
+
+    let schedule_id = test.contract.create_split_schedule(
+        &test.payer,
+        &recipients,
+        &test.token.address,
+        &PAYMENT_AMOUNT,
+        &PaymentFrequency::Daily,
+        &start_time,
+        &end_time,
+    );
+
+    let recipient_one_before = test.token.balance(&test.recipient);
+    let recipient_two_before = test.token.balance(&recipient_two);
+
+    test.contract.execute_payment(&schedule_id, &None);
+
+    // Weight 3 of 4 and weight 1 of 4 of a 100-unit payment: 75 and 25.
+    assert_eq!(test.token.balance(&test.recipient), recipient_one_before + 75);
+    assert_eq!(test.token.balance(&recipient_two), recipient_two_before + 25);
+}
+
+#[test]
+fn test_create_split_schedule_assigns_division_remainder_to_first_recipient() {
+    let test = PaymentTest::setup();
+    let recipient_two = Address::generate(&test.env);
+    let recipient_three = Address::generate(&test.env);
+    let start_time = test.env.ledger().timestamp();
+    let end_time = Some(start_time + (7 * 24 * 60 * 60));
+
+    let recipients = soroban_sdk::vec![
+        &test.env,
+        (test.recipient.clone(), 1u32),
+        (recipient_two.clone(), 1u32),
+        (recipient_three.clone(), 1u32),
+    ];
+
+    let schedule_id = test.contract.create_split_schedule(
+        &test.payer,
+        &recipients,
+        &test.token.address,
+        &PAYMENT_AMOUNT,
+        &PaymentFrequency::Daily,
+        &start_time,
+        &end_time,
+    );
+
+    let recipient_one_before = test.token.balance(&test.recipient);
+    let recipient_two_before = test.token.balance(&recipient_two);
+    let recipient_three_before = test.token.balance(&recipient_three);
+
+    test.contract.execute_payment(&schedule_id, &None);
+
+    // 100 split three equal ways is 33/33/33 with 1 left over, assigned to the first recipient.
+    assert_eq!(test.token.balance(&test.recipient), recipient_one_before + 34);
+    assert_eq!(test.token.balance(&recipient_two), recipient_two_before + 33);
+    assert_eq!(test.token.balance(&recipient_three), recipient_three_before + 33);
+}
+
+#[test]
+fn test_create_split_schedule_rejects_payer_as_recipient() {
+    let test = PaymentTest::setup();
+    let start_time = test.env.ledger().timestamp();
+    let end_time = Some(start_time + (7 * 24 * 60 * 60));
+
+    let recipients = soroban_sdk::vec![&test.env, (test.payer.clone(), 1u32)];
+
+    let result = test.contract.try_create_split_schedule(
+        &test.payer,
+        &recipients,
+        &test.token.address,
+        &PAYMENT_AMOUNT,
+        &PaymentFrequency::Daily,
+        &start_time,
+        &end_time,
+    );
+
+    assert!(result.is_err());
+    assert_eq!(result.unwrap_err(), Ok(ContractError::InvalidInput));
+}
+
+#[test]
+fn test_create_split_schedule_rejects_zero_total_weight() {
+    let test = PaymentTest::setup();
+    let recipient_two = Address::generate(&test.env);
+    let start_time = test.env.ledger().timestamp();
+    let end_time = Some(start_time + (7 * 24 * 60 * 60));
+
+    let recipients = soroban_sdk::vec![
+        &test.env,
+        (test.recipient.clone(), 0u32),
+        (recipient_two.clone(), 0u32),
+    ];
+
+    let result = test.contract.try_create_split_schedule(
+        &test.payer,
+        &recipients,
+        &test.token.address,
+        &PAYMENT_AMOUNT,
+        &PaymentFrequency::Daily,
+        &start_time,
+        &end_time,
+    );
+
+    assert!(result.is_err());
+    assert_eq!(result.unwrap_err(), Ok(ContractError::InvalidAmount));
+}
+
+//---
+// Payment Frequency Tests
+//---
+
+#[test]
+fn test_monthly_schedule_advances_by_calendar_month_without_drift() {
+    let test = PaymentTest::setup();
+
+    // 2024-01-31T00:00:00Z. 2024 is a leap year, so the Jan 31 -> Feb rollover clamps to
+    // Feb 29 rather than Feb 28, and the schedule must resume from day 31 afterwards rather
+    // than drifting to the 29th permanently.
+    let start_time: u64 = 1_706_659_200;
+    test.env.ledger().with_mut(|ledger_info| {
+        ledger_info.timestamp = start_time;
+    });
+
+    let end_time = Some(start_time + 400 * 24 * 60 * 60);
+    let schedule_id = test.contract.create_schedule(
+        &test.payer,
+        &test.recipient,
+        &test.token.address,
+        &PAYMENT_AMOUNT,
+        &PaymentFrequency::Monthly,
+        &start_time,
+        &end_time,
+        &None,
+        &None,
+        &None,
+        &None,
+        &None,
+        &None);
+
+    // The calendar-correct month-end/day-31 timestamp expected after each of 12 consecutive
+    // Monthly payments starting from Jan 31, 2024.
+    let expected_next_payment_times: [u64; 12] = [
+        1_709_164_800, // 2024-02-29
+        1_711_843_200, // 2024-03-31
+        1_714_435_200, // 2024-04-30
+        1_717_113_600, // 2024-05-31
+        1_719_705_600, // 2024-06-30
+        1_722_384_000, // 2024-07-31
+        1_725_062_400, // 2024-08-31
+        1_727_654_400, // 2024-09-30
+        1_730_332_800, // 2024-10-31
+        1_732_924_800, // 2024-11-30
+        1_735_603_200, // 2024-12-31
+        1_738_281_600, // 2025-01-31
+    ];
+
+    for expected in expected_next_payment_times.iter() {
+        test.contract.execute_payment(&schedule_id, &None);
+        let schedule = test.contract.get_schedule(&schedule_id);
+        assert_eq!(schedule.next_payment_time, *expected);
+
+        test.env.ledger().with_mut(|ledger_info| {
+            ledger_info.timestamp = schedule.next_payment_time;
+        });
+    }
+}
+
+#[test]
+fn test_custom_frequency_advances_by_its_own_interval() {
+    let test = PaymentTest::setup();
+    let start_time = test.env.ledger().timestamp();
+    let end_time = Some(start_time + (7 * 24 * 60 * 60));
+    let twelve_hours = 12 * 60 * 60;
+
+    let schedule_id = test.contract.create_schedule(
+        &test.payer,
+        &test.recipient,
+        &test.token.address,
+        &PAYMENT_AMOUNT,
+        &PaymentFrequency::Custom(twelve_hours),
+        &start_time,
+        &end_time,
+        &None,
+        &None,
+        &None,
+        &None,
+        &None,
+        &None);
+
+    test.contract.execute_payment(&schedule_id, &None);
+    let schedule = test.contract.get_schedule(&schedule_id);
+    assert_eq!(schedule.next_payment_time, start_time + twelve_hours);
+}
+
+//---
+// Rent Collection Tests
+//---
+
+#[test]
+fn test_collect_rent_extends_active_schedule_and_leaves_it_untouched() {
+    let test = PaymentTest::setup();
+    let schedule_id = test.create_daily_schedule_immediate(PAYMENT_AMOUNT, 7);
+
+    let report = test.contract.collect_rent(&0, &test.env.ledger().timestamp(), &86400);
+
+    assert_eq!(report.extended, 1);
+    assert_eq!(report.pruned, 0);
+    assert_eq!(test.contract.get_schedule(&schedule_id).status, ScheduleStatus::Active);
+}
+
+#[test]
+fn test_collect_rent_prunes_cancelled_schedule_past_grace_period() {
+    let test = PaymentTest::setup();
+    let schedule_id = test.create_daily_schedule(PAYMENT_AMOUNT, 7);
+    test.contract.cancel_schedule(&schedule_id, &test.payer);
+
+    test.advance_time(86400 + 1);
+    let report = test.contract.collect_rent(&0, &test.env.ledger().timestamp(), &86400);
+
+    assert_eq!(report.pruned, 1);
+    assert!(test.contract.try_get_schedule(&schedule_id).is_err());
+}
+
+#[test]
+fn test_collect_rent_keeps_cancelled_schedule_within_grace_period() {
+    let test = PaymentTest::setup();
+    let schedule_id = test.create_daily_schedule(PAYMENT_AMOUNT, 7);
+    test.contract.cancel_schedule(&schedule_id, &test.payer);
+
+    let report = test.contract.collect_rent(&0, &test.env.ledger().timestamp(), &86400);
+
+    assert_eq!(report.extended, 1);
+    assert_eq!(report.pruned, 0);
+    assert!(test.contract.try_get_schedule(&schedule_id).is_ok());
+}
+
+#[test]
+fn test_collect_rent_prunes_resolved_alert_but_keeps_unresolved_one() {
+    let test = PaymentTest::setup();
+    let schedule_id = test.create_daily_schedule_immediate(PAYMENT_AMOUNT, 7);
+
+    TokenAdminClient::new(&test.env, &test.token.address)
+        .clawback(&test.contract.address, &(PAYMENT_AMOUNT as i128 * 7));
+    test.contract.try_execute_payment(&schedule_id, &None).ok();
+    test.advance_time(3600 * 2 + 1);
+    test.contract.try_execute_payment(&schedule_id, &None).ok();
+    test.advance_time(3600 * 4 + 1);
+    test.contract.try_execute_payment(&schedule_id, &None).ok();
+    assert_eq!(test.contract.get_schedule_alerts(&schedule_id, &10).len(), 1);
+
+    // Unresolved: stays put even well past the grace period.
+    test.advance_time(86400 + 1);
+    let report = test.contract.collect_rent(&0, &test.env.ledger().timestamp(), &86400);
+    assert_eq!(report.pruned, 0);
+    assert_eq!(test.contract.get_schedule_alerts(&schedule_id, &10).len(), 1);
+
+    // Resolving it (via reset_failed_attempts) makes it eligible for pruning once stale.
+    TokenAdminClient::new(&test.env, &test.token.address)
+        .mint(&test.contract.address, &(PAYMENT_AMOUNT as i128 * 7));
+    test.contract.reset_failed_attempts(&schedule_id, &test.payer);
+    test.advance_time(86400 + 1);
+    let report = test.contract.collect_rent(&0, &test.env.ledger().timestamp(), &86400);
+    assert_eq!(report.pruned, 1);
+    assert_eq!(test.contract.get_schedule_alerts(&schedule_id, &10).len(), 0);
+}
+
+#[test]
+fn test_collect_rent_extends_user_schedules_index_ttl() {
+    let test = PaymentTest::setup();
+    let _schedule_id = test.create_daily_schedule_immediate(PAYMENT_AMOUNT, 7);
+
+    let payer_key = payment_schedule::StorageKey::UserSchedules(test.payer.clone());
+    let recipient_key = payment_schedule::StorageKey::UserSchedules(test.recipient.clone());
+
+    let (payer_ttl_before, recipient_ttl_before) = test.env.as_contract(&test.contract.address, || {
+        (
+            test.env.storage().persistent().get_ttl(&payer_key),
+            test.env.storage().persistent().get_ttl(&recipient_key),
+        )
+    });
+
+    test.advance_time(1);
+    let report = test.contract.collect_rent(&0, &test.env.ledger().timestamp(), &86400);
+    assert_eq!(report.extended, 1);
+
+    let (payer_ttl_after, recipient_ttl_after) = test.env.as_contract(&test.contract.address, || {
+        (
+            test.env.storage().persistent().get_ttl(&payer_key),
+            test.env.storage().persistent().get_ttl(&recipient_key),
+        )
+    });
+
+    assert!(payer_ttl_after > payer_ttl_before);
+    assert!(recipient_ttl_after > recipient_ttl_before);
+}
+
+#[test]
+fn test_collect_rent_extends_alert_index_ttl() {
+    let test = PaymentTest::setup();
+    let schedule_id = test.create_daily_schedule_immediate(PAYMENT_AMOUNT, 7);
+
+    TokenAdminClient::new(&test.env, &test.token.address)
+        .clawback(&test.contract.address, &(PAYMENT_AMOUNT as i128 * 7));
+    test.contract.try_execute_payment(&schedule_id, &None).ok();
+    test.advance_time(3600 * 2 + 1);
+    test.contract.try_execute_payment(&schedule_id, &None).ok();
+    test.advance_time(3600 * 4 + 1);
+    test.contract.try_execute_payment(&schedule_id, &None).ok();
+    assert_eq!(test.contract.get_schedule_alerts(&schedule_id, &10).len(), 1);
+
+    let index_key = (soroban_sdk::symbol_short!("ALRT_IDX"), schedule_id);
+    let ttl_before = test.env.as_contract(&test.contract.address, || {
+        test.env.storage().persistent().get_ttl(&index_key)
+    });
+
+    test.advance_time(1);
+    let report = test.contract.collect_rent(&0, &test.env.ledger().timestamp(), &86400);
+    assert_eq!(report.extended, 2); // the schedule entry and the one unresolved alert
+
+    let ttl_after = test.env.as_contract(&test.contract.address, || {
+        test.env.storage().persistent().get_ttl(&index_key)
+    });
+
+    assert!(ttl_after > ttl_before);
+}
+
+//---
+// Conditional Payment Plan Tests
+//---
+
+#[test]
+fn test_create_conditional_payment_unconditional_pay_settles_immediately() {
+    let test = PaymentTest::setup();
+    let plan = plan::PaymentPlan::Pay(plan::Pay {
+        amount: PAYMENT_AMOUNT,
+        to: test.recipient.clone(),
+    });
+
+    let recipient_balance_before = test.token.balance(&test.recipient);
+    let plan_id = test.contract.create_conditional_payment(&test.payer, &plan, &test.token.address);
+
+    assert_eq!(
+        test.token.balance(&test.recipient),
+        recipient_balance_before + PAYMENT_AMOUNT as i128
+    );
+    assert!(test.contract.get_conditional_payment(&plan_id).executed);
+}
+
+#[test]
+fn test_create_conditional_payment_after_timestamp_waits_then_settles() {
+    let test = PaymentTest::setup();
+    let release_at = test.env.ledger().timestamp() + 3600;
+    let plan = plan::PaymentPlan::After(
+        plan::PlanCondition::Timestamp(release_at),
+        plan::Pay { amount: PAYMENT_AMOUNT, to: test.recipient.clone() },
+    );
+
+    let plan_id = test.contract.create_conditional_payment(&test.payer, &plan, &test.token.address);
+    assert!(!test.contract.get_conditional_payment(&plan_id).executed);
+
+    test.advance_time(3601);
+    test.contract.apply_plan_witness(&plan_id, &None);
+
+    assert!(test.contract.get_conditional_payment(&plan_id).executed);
+}
+
+#[test]
+fn test_create_conditional_payment_after_signature_requires_witness_auth() {
+    let test = PaymentTest::setup();
+    let arbiter = Address::generate(&test.env);
+    let plan = plan::PaymentPlan::After(
+        plan::PlanCondition::Signature(arbiter.clone()),
+        plan::Pay { amount: PAYMENT_AMOUNT, to: test.recipient.clone() },
+    );
+
+    let plan_id = test.contract.create_conditional_payment(&test.payer, &plan, &test.token.address);
+    assert!(!test.contract.get_conditional_payment(&plan_id).executed);
+
+    let recipient_balance_before = test.token.balance(&test.recipient);
+    test.contract.apply_plan_witness(&plan_id, &Some(arbiter));
+
+    assert_eq!(
+        test.token.balance(&test.recipient),
+        recipient_balance_before + PAYMENT_AMOUNT as i128
+    );
+    assert!(test.contract.get_conditional_payment(&plan_id).executed);
+}
+
+#[test]
+fn test_create_conditional_payment_race_pays_recipient_when_timestamp_wins() {
+    let test = PaymentTest::setup();
+    let release_at = test.env.ledger().timestamp() + 3600;
+    let plan = plan::PaymentPlan::Race(
+        (plan::PlanCondition::Timestamp(release_at), plan::Pay { amount: PAYMENT_AMOUNT, to: test.recipient.clone() }),
+        (plan::PlanCondition::Signature(test.payer.clone()), plan::Pay { amount: PAYMENT_AMOUNT, to: test.payer.clone() }),
+    );
+
+    let plan_id = test.contract.create_conditional_payment(&test.payer, &plan, &test.token.address);
+
+    test.advance_time(3601);
+    let recipient_balance_before = test.token.balance(&test.recipient);
+    test.contract.apply_plan_witness(&plan_id, &None);
+
+    assert_eq!(
+        test.token.balance(&test.recipient),
+        recipient_balance_before + PAYMENT_AMOUNT as i128
+    );
+    assert!(test.contract.get_conditional_payment(&plan_id).executed);
+}
+
+#[test]
+fn test_create_conditional_payment_race_refunds_payer_when_cancelled_first() {
+    let test = PaymentTest::setup();
+    let release_at = test.env.ledger().timestamp() + (365 * 24 * 60 * 60); // far in the future
+    let plan = plan::PaymentPlan::Race(
+        (plan::PlanCondition::Timestamp(release_at), plan::Pay { amount: PAYMENT_AMOUNT, to: test.recipient.clone() }),
+        (plan::PlanCondition::Signature(test.payer.clone()), plan::Pay { amount: PAYMENT_AMOUNT, to: test.payer.clone() }),
+    );
+
+    let plan_id = test.contract.create_conditional_payment(&test.payer, &plan, &test.token.address);
+
+    let payer_balance_before = test.token.balance(&test.payer);
+    test.contract.apply_plan_witness(&plan_id, &Some(test.payer.clone()));
+
+    assert_eq!(test.token.balance(&test.payer), payer_balance_before + PAYMENT_AMOUNT as i128);
+    assert!(test.contract.get_conditional_payment(&plan_id).executed);
+
+    // The timestamp branch firing afterwards is a no-op: the plan already retired.
+    test.advance_time(365 * 24 * 60 * 60 + 1);
+    let recipient_balance_before = test.token.balance(&test.recipient);
+    test.contract.apply_plan_witness(&plan_id, &None);
+    assert_eq!(test.token.balance(&test.recipient), recipient_balance_before);
+}
@@ -1,6 +1,11 @@
 use crate::error::ContractError;
-use crate::payment_schedule::{PaymentFrequency, PaymentSchedule};
-use soroban_sdk::{token, Address, Env};
+use crate::payment_schedule::{ConditionKind, Denomination, PaymentFrequency, PaymentSchedule, ReleaseCondition, VestingPlan};
+use soroban_sdk::{token, Address, Env, Vec};
+
+/// Conservative buffer applied to an oracle-denominated schedule's per-payment token
+/// estimate at creation time, in bps, so ordinary price drift between funding and execution
+/// doesn't leave the schedule short.
+const PRICE_DRIFT_BUFFER_BPS: u128 = 2000; // 20%
 
 pub fn validate_schedule_params(
     payer: &Address,
@@ -10,14 +15,26 @@ pub fn validate_schedule_params(
     end_time: Option<u64>,
     current_time: u64,
 ) -> Result<(), ContractError> {
-    if amount == 0 {
-        return Err(ContractError::InvalidAmount);
-    }
-
     if payer == recipient {
         return Err(ContractError::InvalidInput);
     }
 
+    validate_schedule_timing(amount, start_time, end_time, current_time)
+}
+
+/// The amount/start/end checks shared by `create_schedule` and `create_split_schedule`;
+/// the payer-vs-recipient check lives on the single-recipient path only, since a split
+/// schedule checks each of its several recipients instead (`validate_split_recipients`).
+pub fn validate_schedule_timing(
+    amount: u128,
+    start_time: u64,
+    end_time: Option<u64>,
+    current_time: u64,
+) -> Result<(), ContractError> {
+    if amount == 0 {
+        return Err(ContractError::InvalidAmount);
+    }
+
     // Allow start_time to be current_time or future
     if start_time < current_time {
         return Err(ContractError::InvalidStartTime);
@@ -32,6 +49,93 @@ pub fn validate_schedule_params(
     Ok(())
 }
 
+/// Validates a `ReleaseCondition` arena before it's ever stored: rejects an empty `nodes`
+/// (nothing for `evaluate_release_condition`'s root lookup to find) and any `And`/`Or` sibling
+/// index `>= nodes.len()`. Caught here rather than at `evaluate_node` time, since that runs
+/// from the permissionless batch entrypoints and an out-of-bounds `.expect()` there would abort
+/// every other due schedule swept in the same call, not just the malformed one.
+pub fn validate_release_condition(condition: &ReleaseCondition) -> Result<(), ContractError> {
+    if condition.nodes.is_empty() {
+        return Err(ContractError::InvalidReleaseCondition);
+    }
+
+    let len = condition.nodes.len();
+    for node in condition.nodes.iter() {
+        if let ConditionKind::And(left, right) | ConditionKind::Or(left, right) = node {
+            if left >= len || right >= len {
+                return Err(ContractError::InvalidReleaseCondition);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Validates a `create_split_schedule` recipient list: at least one payee, none equal to
+/// `payer`, and a non-zero total weight to divide payments by.
+pub fn validate_split_recipients(
+    payer: &Address,
+    recipients: &Vec<(Address, u32)>,
+) -> Result<(), ContractError> {
+    if recipients.is_empty() {
+        return Err(ContractError::InvalidInput);
+    }
+
+    let mut total_weight: u32 = 0;
+    for (recipient, weight) in recipients.iter() {
+        if recipient == *payer {
+            return Err(ContractError::InvalidInput);
+        }
+        total_weight = total_weight.checked_add(weight).ok_or(ContractError::InvalidAmount)?;
+    }
+
+    if total_weight == 0 {
+        return Err(ContractError::InvalidAmount);
+    }
+
+    Ok(())
+}
+
+/// Divides `total_amount` across `recipients` proportionally to their weight, same order as
+/// given. Integer division drops a remainder of up to `recipients.len() - 1` units; that
+/// remainder is added to the first recipient's share so the shares still sum to exactly
+/// `total_amount`.
+pub fn split_weighted_amount(
+    env: &Env,
+    recipients: &Vec<(Address, u32)>,
+    total_amount: u128,
+) -> Result<Vec<(Address, u128)>, ContractError> {
+    let total_weight: u32 = recipients.iter().try_fold(0u32, |acc, (_, weight)| {
+        acc.checked_add(weight).ok_or(ContractError::InvalidAmount)
+    })?;
+
+    if total_weight == 0 {
+        return Err(ContractError::InvalidAmount);
+    }
+
+    let mut shares = Vec::new(env);
+    let mut distributed: u128 = 0;
+
+    for (recipient, weight) in recipients.iter() {
+        let share = total_amount
+            .checked_mul(weight as u128)
+            .ok_or(ContractError::InvalidAmount)?
+            / total_weight as u128;
+        shares.push_back((recipient, share));
+        distributed = distributed.checked_add(share).ok_or(ContractError::InvalidAmount)?;
+    }
+
+    // Assign the integer-division remainder to the first recipient so the shares sum to
+    // exactly `total_amount`.
+    let remainder = total_amount.checked_sub(distributed).ok_or(ContractError::InvalidAmount)?;
+    if remainder > 0 {
+        let (first_recipient, first_share) = shares.get(0).ok_or(ContractError::InvalidAmount)?;
+        shares.set(0, (first_recipient, first_share + remainder));
+    }
+
+    Ok(shares)
+}
+
 pub fn validate_funds(
     env: &Env,
     token: &Address,
@@ -60,6 +164,7 @@ pub fn calculate_required_funds(
             PaymentFrequency::Daily => duration / 86400,
             PaymentFrequency::Weekly => duration / 604800,
             PaymentFrequency::Monthly => duration / 2592000,
+            PaymentFrequency::Custom(interval_secs) => duration / (*interval_secs).max(1),
         };
         
         // Add 1 to include the first payment, ensure at least 1 payment
@@ -70,8 +175,47 @@ pub fn calculate_required_funds(
     }
 }
 
+pub fn validate_vesting_params(plan: &VestingPlan) -> Result<(), ContractError> {
+    if plan.period == 0 || plan.period_count == 0 || plan.per_period == 0 {
+        return Err(ContractError::InvalidInput);
+    }
+
+    Ok(())
+}
+
+/// Converts an oracle-denominated `quote_amount` (e.g. "100" meaning 100 units of
+/// `denomination.asset_symbol`) into however many `token` units that's worth at `price`.
+pub fn convert_quote_to_token(
+    denomination: &Denomination,
+    quote_amount: u128,
+    price: u64,
+) -> Result<u128, ContractError> {
+    if price == 0 {
+        return Err(ContractError::PriceUnavailable);
+    }
+
+    let scaled = quote_amount
+        .checked_mul(10u128.pow(denomination.decimals))
+        .ok_or(ContractError::InvalidAmount)?;
+
+    Ok(scaled / price as u128)
+}
+
+/// Inflates a denominated schedule's per-payment token estimate by `PRICE_DRIFT_BUFFER_BPS`
+/// so the funds locked at creation still cover the payment if the token price slips before
+/// `execute_payment` re-prices it.
+pub fn apply_price_drift_buffer(per_payment: u128) -> Result<u128, ContractError> {
+    per_payment
+        .checked_mul(10_000 + PRICE_DRIFT_BUFFER_BPS)
+        .map(|buffered| buffered / 10_000)
+        .ok_or(ContractError::InvalidAmount)
+}
+
 pub fn can_execute_payment(schedule: &PaymentSchedule, current_time: u64) -> bool {
-    if schedule.balance < schedule.amount {
+    // For a denominated schedule `amount` is a quote-unit value, not a token quantity, so it
+    // isn't comparable to `balance` here; `execute_payment` checks the live-converted amount
+    // against `balance` itself once it has re-priced the payment.
+    if schedule.denomination.is_none() && schedule.balance < schedule.amount {
         return false;
     }
 
@@ -95,9 +239,35 @@ pub fn can_execute_payment(schedule: &PaymentSchedule, current_time: u64) -> boo
         }
     }
 
+    // A schedule with its own `RetryPolicy` gates retries on `next_retry_at` independent of
+    // `next_payment_time`'s ordinary cadence.
+    if let Some(retry_at) = schedule.next_retry_at {
+        if current_time < retry_at {
+            return false;
+        }
+    }
+
     true
 }
 
 pub fn should_retry_payment(failed_attempts: u32) -> bool {
     failed_attempts < 3
+}
+
+/// Base of the retry delay for a failed recurring payment: a payment that has failed
+/// `failed_attempts` times waits `base * 2^failed_attempts` (so 2 hours after the first
+/// failure), capped at `MAX_RETRY_DELAY_SECONDS` so a schedule
+/// that keeps failing doesn't get pushed out indefinitely.
+const RETRY_BASE_DELAY_SECONDS: u64 = 3600; // 1 hour
+const MAX_RETRY_DELAY_SECONDS: u64 = 604800; // 7 days
+
+/// Backoff `next_payment_time` for a payment that just failed for the `failed_attempts`-th
+/// time, so a retryable schedule waits longer between attempts the more it has failed.
+pub fn calculate_backoff_retry_time(current_time: u64, failed_attempts: u32) -> u64 {
+    let delay = RETRY_BASE_DELAY_SECONDS
+        .checked_shl(failed_attempts)
+        .unwrap_or(MAX_RETRY_DELAY_SECONDS)
+        .min(MAX_RETRY_DELAY_SECONDS);
+
+    current_time.saturating_add(delay)
 }
\ No newline at end of file
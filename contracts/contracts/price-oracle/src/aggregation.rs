@@ -1,29 +1,77 @@
-use soroban_sdk::{Address, Env, Map, Symbol, Vec};
-use crate::price_data::{PriceData, AggregatedPrice, MAX_PRICE_DEVIATION};
+use soroban_sdk::{contracttype, log, Address, Env, Map, Symbol, Vec};
+use crate::price_data::{PriceData, AggregatedPrice, MAX_PRICE_DEVIATION, MIN_CONFIDENCE_LEVEL, PRICE_STALENESS_THRESHOLD};
 use crate::oracle_node::OracleNode;
+use crate::events::{emit_price_aggregated, emit_price_submitted};
+
+// Scales the MAD by 1.4826 (expressed as a ratio) for consistency with a normal distribution.
+const MAD_SCALE_NUMERATOR: u64 = 14826;
+const MAD_SCALE_DENOMINATOR: u64 = 10000;
+pub const MIN_AGGREGATION_SOURCES: u32 = 3;
+const MAX_FALLBACK_STALENESS: u64 = 1800; // 30 minutes
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OutlierConfig {
+    pub k: u32, // Number of scaled-MADs a submission may deviate from the median
+}
+
+impl Default for OutlierConfig {
+    fn default() -> Self {
+        Self { k: 3 }
+    }
+}
+
+/// Per-asset staleness and source-count policy, consolidating what used to be a mix of the
+/// global `ContractConfig::price_staleness_threshold`, a hardcoded 1800s fallback window, and
+/// the `MIN_AGGREGATION_SOURCES` constant. Every path that reads an already-aggregated price —
+/// `get_price`, `get_price_ext`, and `get_fallback_price` — and `aggregate_prices` itself route
+/// through this instead.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OracleConfig {
+    pub max_price_staleness: u64,
+    pub max_fallback_staleness: u64,
+    pub min_eligible_sources: u32,
+}
+
+impl Default for OracleConfig {
+    fn default() -> Self {
+        Self {
+            max_price_staleness: PRICE_STALENESS_THRESHOLD,
+            max_fallback_staleness: MAX_FALLBACK_STALENESS,
+            min_eligible_sources: MIN_AGGREGATION_SOURCES,
+        }
+    }
+}
 
 pub struct PriceAggregator;
 
 impl PriceAggregator {
+    /// Aggregates `price_submissions` into a published price. On success also returns the
+    /// addresses of nodes whose submission survived outlier removal but was then dropped for
+    /// not overlapping the aggregate median — the caller flags these as inaccurate via
+    /// `update_oracle_accuracy` even though they're excluded from the aggregate itself.
     pub fn aggregate_prices(
         env: &Env,
         asset_symbol: Symbol,
         price_submissions: &Vec<PriceData>,
         oracle_nodes: &Map<Address, OracleNode>,
-    ) -> Result<AggregatedPrice, Symbol> {
+        oracle_config: &OracleConfig,
+    ) -> Result<(AggregatedPrice, Vec<Address>), Symbol> {
         if price_submissions.is_empty() {
             return Err(Symbol::new(env, "no_price_data"));
         }
 
-        let valid_submissions = Self::filter_valid_submissions(env, price_submissions, oracle_nodes);
-        
-        if valid_submissions.is_empty() {
-            return Err(Symbol::new(env, "no_valid_submissions"));
+        let (valid_submissions, band_mismatched) = Self::filter_valid_submissions(env, price_submissions, oracle_nodes, oracle_config)?;
+
+        if valid_submissions.len() < oracle_config.min_eligible_sources {
+            return Err(Symbol::new(env, "insufficient_sources"));
         }
 
         let aggregated_price = Self::calculate_weighted_median(env, &valid_submissions, oracle_nodes)?;
         let confidence = Self::calculate_confidence(env, &valid_submissions, oracle_nodes);
-        let deviation = Self::calculate_price_deviation(&valid_submissions);
+        let deviation = Self::calculate_mad_deviation_bps(env, &valid_submissions, aggregated_price);
+        let (min_price, max_price) = Self::price_band(&valid_submissions);
 
         let result = AggregatedPrice::new(
             env,
@@ -32,6 +80,79 @@ impl PriceAggregator {
             valid_submissions.len(),
             confidence,
             deviation,
+            min_price,
+            max_price,
+        );
+
+        if result.is_reliable() {
+            emit_price_aggregated(
+                env,
+                result.asset_symbol.clone(),
+                result.price,
+                result.confidence,
+                result.deviation,
+                result.num_sources,
+            );
+            Ok((result, band_mismatched))
+        } else {
+            Err(Symbol::new(env, "unreliable_price"))
+        }
+    }
+
+    /// A lighter-weight alternative to `aggregate_prices` for callers that already hold a
+    /// trusted `Vec<PriceData>` (e.g. reports relayed from another contract) and have no
+    /// `OracleNode` registry to weight them against. Drops stale or low-confidence reports,
+    /// takes the plain median of what's left (manipulation-resistant against a single bad
+    /// report, unlike a mean), and rejects the whole aggregate if the surviving reports still
+    /// spread too far around it.
+    pub fn aggregate_prices_median(
+        env: &Env,
+        asset_symbol: Symbol,
+        reports: Vec<PriceData>,
+    ) -> Result<AggregatedPrice, Symbol> {
+        let mut survivors: Vec<PriceData> = Vec::new(env);
+        for report in reports.iter() {
+            if report.is_stale(env, PRICE_STALENESS_THRESHOLD) || report.confidence < MIN_CONFIDENCE_LEVEL {
+                continue;
+            }
+            survivors.push_back(report);
+        }
+
+        if survivors.len() < MIN_AGGREGATION_SOURCES {
+            return Err(Symbol::new(env, "insufficient_sources"));
+        }
+
+        let mut prices: Vec<u64> = Vec::new(env);
+        let mut min_confidence = u32::MAX;
+        for report in survivors.iter() {
+            prices.push_back(report.price);
+            min_confidence = min_confidence.min(report.confidence);
+        }
+        prices.sort();
+
+        let median = Self::median_of(&prices);
+        let min_price = prices.get(0).unwrap_or(0);
+        let max_price = prices.get(prices.len() - 1).unwrap_or(0);
+
+        let deviation = if median == 0 {
+            0
+        } else {
+            (((max_price - min_price) as u128 * 10_000) / median as u128).min(u32::MAX as u128) as u32
+        };
+
+        if deviation > MAX_PRICE_DEVIATION {
+            return Err(Symbol::new(env, "price_deviation_too_high"));
+        }
+
+        let result = AggregatedPrice::new(
+            env,
+            asset_symbol,
+            median,
+            survivors.len(),
+            min_confidence,
+            deviation,
+            min_price,
+            max_price,
         );
 
         if result.is_reliable() {
@@ -41,15 +162,20 @@ impl PriceAggregator {
         }
     }
 
+    /// Returns the submissions fit to aggregate, plus the addresses of nodes dropped for
+    /// publishing a confidence band that doesn't overlap the group's median price — a band
+    /// mismatch outlier removal alone wouldn't necessarily catch, since `price` itself can sit
+    /// well within the MAD bound while the claimed band still misses the median entirely.
     fn filter_valid_submissions(
         env: &Env,
         submissions: &Vec<PriceData>,
         oracle_nodes: &Map<Address, OracleNode>,
-    ) -> Vec<PriceData> {
+        oracle_config: &OracleConfig,
+    ) -> Result<(Vec<PriceData>, Vec<Address>), Symbol> {
         let mut valid_submissions = Vec::new(env);
 
         for submission in submissions.iter() {
-            if !submission.is_valid() || submission.is_stale(env) {
+            if !submission.is_valid() || submission.is_stale(env, oracle_config.max_price_staleness) {
                 continue;
             }
 
@@ -60,29 +186,97 @@ impl PriceAggregator {
             }
         }
 
-        // Remove outliers
-        Self::remove_outliers(env, valid_submissions)
+        // Remove outliers with a median-absolute-deviation filter
+        let survivors = Self::remove_outliers_mad(env, valid_submissions);
+
+        let mut prices: Vec<u64> = Vec::new(env);
+        for submission in survivors.iter() {
+            prices.push_back(submission.price);
+        }
+        prices.sort();
+        let median = Self::median_of(&prices);
+
+        let mut kept = Vec::new(env);
+        let mut band_mismatched = Vec::new(env);
+        for submission in survivors.iter() {
+            let (band_low, band_high) = submission.band();
+            if band_low <= median && median <= band_high {
+                kept.push_back(submission);
+            } else {
+                band_mismatched.push_back(submission.oracle_node.clone());
+            }
+        }
+
+        Ok((kept, band_mismatched))
     }
 
-    fn remove_outliers(env: &Env, submissions: Vec<PriceData>) -> Vec<PriceData> {
-        if submissions.len() < 3 {
-            return submissions;
+    /// Returns the (min, max) price across the accepted submissions, the confidence band
+    /// `get_price` uses to refuse a technically-reliable but internally inconsistent quorum.
+    fn price_band(submissions: &Vec<PriceData>) -> (u64, u64) {
+        let mut min_price = u64::MAX;
+        let mut max_price = 0u64;
+
+        for submission in submissions.iter() {
+            min_price = min_price.min(submission.price);
+            max_price = max_price.max(submission.price);
         }
 
-        // Calculate median for outlier detection
-        let mut prices: Vec<u64> = submissions.iter().map(|s| s.price).collect();
-        prices.sort();
-        
-        let median = if prices.len() % 2 == 0 {
+        if submissions.is_empty() {
+            (0, 0)
+        } else {
+            (min_price, max_price)
+        }
+    }
+
+    fn median_of(prices: &Vec<u64>) -> u64 {
+        if prices.is_empty() {
+            return 0;
+        }
+
+        if prices.len() % 2 == 0 {
             let mid = prices.len() / 2;
             (prices.get(mid - 1).unwrap_or(&0) + prices.get(mid).unwrap_or(&0)) / 2
         } else {
             *prices.get(prices.len() / 2).unwrap_or(&0)
-        };
+        }
+    }
 
-        let max_deviation = (median * MAX_PRICE_DEVIATION as u64) / 100;
-        let mut filtered = Vec::new(env);
+    /// Rejects submissions whose price deviates from the median by more than `k` scaled
+    /// median-absolute-deviations, which tolerates a single planted outlier far better than a
+    /// fixed percentage band around the median.
+    fn remove_outliers_mad(env: &Env, submissions: Vec<PriceData>) -> Vec<PriceData> {
+        if submissions.len() < 3 {
+            return submissions;
+        }
 
+        let mut prices: Vec<u64> = Vec::new(env);
+        for submission in submissions.iter() {
+            prices.push_back(submission.price);
+        }
+        prices.sort();
+
+        let median = Self::median_of(&prices);
+
+        let mut abs_deviations: Vec<u64> = Vec::new(env);
+        for price in prices.iter() {
+            let deviation = if price > median { price - median } else { median - price };
+            abs_deviations.push_back(deviation);
+        }
+        abs_deviations.sort();
+
+        let mad = Self::median_of(&abs_deviations);
+
+        // MAD == 0 means the bulk of submissions agree exactly; keep everyone rather than
+        // reject on an unscaled zero-width band.
+        if mad == 0 {
+            return submissions;
+        }
+
+        let scaled_mad = (mad * MAD_SCALE_NUMERATOR) / MAD_SCALE_DENOMINATOR;
+        let k = Self::get_outlier_config(env).k as u64;
+        let max_deviation = scaled_mad.saturating_mul(k);
+
+        let mut filtered = Vec::new(env);
         for submission in submissions.iter() {
             let deviation = if submission.price > median {
                 submission.price - median
@@ -98,6 +292,74 @@ impl PriceAggregator {
         filtered
     }
 
+    /// Reports the scaled MAD as a fraction of the median, in basis points.
+    fn calculate_mad_deviation_bps(env: &Env, submissions: &Vec<PriceData>, median: u64) -> u32 {
+        if median == 0 || submissions.len() < 2 {
+            return 0;
+        }
+
+        let mut abs_deviations: Vec<u64> = Vec::new(env);
+        for submission in submissions.iter() {
+            let deviation = if submission.price > median {
+                submission.price - median
+            } else {
+                median - submission.price
+            };
+            abs_deviations.push_back(deviation);
+        }
+        abs_deviations.sort();
+
+        let mad = Self::median_of(&abs_deviations);
+
+        let scaled_mad = (mad as u128 * MAD_SCALE_NUMERATOR as u128) / MAD_SCALE_DENOMINATOR as u128;
+        ((scaled_mad * 10_000) / median as u128).min(u32::MAX as u128) as u32
+    }
+
+    pub fn get_outlier_config(env: &Env) -> OutlierConfig {
+        env.storage()
+            .instance()
+            .get(&crate::DataKey::OutlierConfig)
+            .unwrap_or_default()
+    }
+
+    pub fn set_outlier_config(env: &Env, config: &OutlierConfig) {
+        env.storage().instance().set(&crate::DataKey::OutlierConfig, config);
+    }
+
+    pub fn get_config(env: &Env, asset_symbol: &Symbol) -> OracleConfig {
+        env.storage()
+            .instance()
+            .get(&crate::DataKey::OracleConfig(asset_symbol.clone()))
+            .unwrap_or_default()
+    }
+
+    pub fn set_config(env: &Env, asset_symbol: &Symbol, config: &OracleConfig) {
+        env.storage()
+            .instance()
+            .set(&crate::DataKey::OracleConfig(asset_symbol.clone()), config);
+    }
+
+    /// Staleness gate for `get_price`, `get_price_ext`'s `Strict` mode and `get_fallback_price`:
+    /// returns the data's age on success, or logs it and returns `stale_price` if it exceeds
+    /// `max_age`. `get_stable_price` and `get_conservative_price` return the stored aggregate
+    /// without any age check, and `get_twap` checks its newest history entry against the
+    /// contract-wide `price_staleness_threshold` instead of going through this gate.
+    pub fn check_staleness(env: &Env, asset_symbol: &Symbol, timestamp: u64, max_age: u64) -> Result<u64, Symbol> {
+        let age = env.ledger().timestamp().saturating_sub(timestamp);
+        if age > max_age {
+            log!(env, "Stale price for {}: age {} exceeds threshold {}", asset_symbol, age, max_age);
+            return Err(Symbol::new(env, "stale_price"));
+        }
+        Ok(age)
+    }
+
+    /// Computes the weighted median without ever expanding a submission into `weight` duplicate
+    /// price entries (the old approach, O(total_weight) in both time and contract memory, and
+    /// unbounded since `calculate_node_weight` can return up to 250). Instead keeps two parallel
+    /// arrays sorted by price as entries are inserted — `Vec<(u64, u64)>::sort` isn't available,
+    /// since soroban_sdk's host-backed `Vec` only sorts element types the host can compare
+    /// directly — then walks the running cumulative weight to find where it first reaches half
+    /// of `total_weight`.
     fn calculate_weighted_median(
         env: &Env,
         submissions: &Vec<PriceData>,
@@ -107,37 +369,70 @@ impl PriceAggregator {
             return Err(Symbol::new(env, "no_submissions"));
         }
 
-        // Create weighted price entries
-        let mut weighted_prices = Vec::new(env);
-        let mut total_weight = 0u64;
+        let mut sorted_prices: Vec<u64> = Vec::new(env);
+        let mut sorted_weights: Vec<u64> = Vec::new(env);
+        let mut total_weight: u64 = 0;
 
         for submission in submissions.iter() {
             if let Some(node) = oracle_nodes.get(&submission.oracle_node) {
                 let weight = Self::calculate_node_weight(&node, &submission);
-                total_weight += weight;
-                
-                for _ in 0..weight {
-                    weighted_prices.push_back(submission.price);
+                if weight == 0 {
+                    continue;
                 }
+                total_weight = total_weight
+                    .checked_add(weight)
+                    .ok_or_else(|| Symbol::new(env, "weight_overflow"))?;
+
+                let mut idx = 0u32;
+                while idx < sorted_prices.len() && sorted_prices.get(idx).unwrap_or(0) <= submission.price {
+                    idx += 1;
+                }
+                sorted_prices.insert(idx, submission.price);
+                sorted_weights.insert(idx, weight);
             }
         }
 
-        if weighted_prices.is_empty() {
+        if total_weight == 0 {
             return Err(Symbol::new(env, "no_weighted_data"));
         }
 
-        // Sort and find median
-        let mut sorted_prices: Vec<u64> = weighted_prices.iter().collect();
-        sorted_prices.sort();
+        // A single node whose weight alone is a strict majority is the median outright, with
+        // no crossing to find.
+        for i in 0..sorted_weights.len() {
+            let weight = sorted_weights.get(i).unwrap_or(0) as u128;
+            if weight * 2 > total_weight as u128 {
+                return Ok(sorted_prices.get(i).unwrap_or(0));
+            }
+        }
 
-        let median = if sorted_prices.len() % 2 == 0 {
-            let mid = sorted_prices.len() / 2;
-            (sorted_prices.get(mid - 1).unwrap_or(&0) + sorted_prices.get(mid).unwrap_or(&0)) / 2
-        } else {
-            *sorted_prices.get(sorted_prices.len() / 2).unwrap_or(&0)
-        };
+        let mut cumulative: u128 = 0;
+        for i in 0..sorted_weights.len() {
+            let weight = sorted_weights.get(i).unwrap_or(0) as u128;
+            let price = sorted_prices.get(i).unwrap_or(0);
+            cumulative = cumulative
+                .checked_add(weight)
+                .ok_or_else(|| Symbol::new(env, "weight_overflow"))?;
 
-        Ok(median)
+            if cumulative * 2 < total_weight as u128 {
+                continue;
+            }
+
+            // Landing exactly on the boundary splits the difference with the next distinct
+            // price rather than arbitrarily favoring this side of the crossing.
+            if cumulative * 2 == total_weight as u128 {
+                let mut next_idx = i + 1;
+                while next_idx < sorted_prices.len() && sorted_prices.get(next_idx).unwrap_or(0) == price {
+                    next_idx += 1;
+                }
+                if let Some(next_price) = sorted_prices.get(next_idx) {
+                    return Ok(((price as u128 + next_price as u128) / 2) as u64);
+                }
+            }
+            return Ok(price);
+        }
+
+        // Unreachable: cumulative weight always reaches total_weight by the last entry.
+        Ok(sorted_prices.get(sorted_prices.len().saturating_sub(1)).unwrap_or(0))
     }
 
     fn calculate_node_weight(node: &OracleNode, submission: &PriceData) -> u64 {
@@ -164,7 +459,16 @@ impl PriceAggregator {
         for submission in submissions.iter() {
             if let Some(node) = oracle_nodes.get(&submission.oracle_node) {
                 let weight = Self::calculate_node_weight(&node, &submission);
-                total_confidence += (submission.confidence as u64) * weight;
+
+                // A submission's band width relative to its own price discounts how much its
+                // confidence counts — a node claiming a wide ± spread is less useful even if
+                // it reports a high confidence number.
+                let band_width_bps = ((submission.confidence_interval as u128 * 10_000)
+                    / submission.price.max(1) as u128)
+                    .min(10_000) as u64;
+                let effective_confidence = (submission.confidence as u64 * (10_000 - band_width_bps)) / 10_000;
+
+                total_confidence += effective_confidence * weight;
                 total_weight += weight;
             }
         }
@@ -186,81 +490,69 @@ impl PriceAggregator {
         (weighted_confidence as u32 + source_bonus).min(100)
     }
 
-    fn calculate_price_deviation(submissions: &Vec<PriceData>) -> u32 {
-        if submissions.len() < 2 {
-            return 0;
-        }
-
-        let prices: Vec<u64> = submissions.iter().map(|s| s.price).collect();
-        let avg_price: u64 = prices.iter().sum::<u64>() / prices.len() as u64;
-
-        if avg_price == 0 {
-            return 100; // Maximum deviation
-        }
-
-        let mut max_deviation = 0u32;
-        
-        for price in prices.iter() {
-            let deviation = if *price > avg_price {
-                *price - avg_price
-            } else {
-                avg_price - *price
-            };
-            
-            let deviation_percentage = ((deviation * 100) / avg_price) as u32;
-            max_deviation = max_deviation.max(deviation_percentage);
-        }
-
-        max_deviation.min(100)
-    }
-
     pub fn get_fallback_price(
         env: &Env,
         asset_symbol: Symbol,
         price_history: &Map<Symbol, Vec<AggregatedPrice>>,
+        oracle_config: &OracleConfig,
     ) -> Result<u64, Symbol> {
         if let Some(history) = price_history.get(&asset_symbol) {
             if !history.is_empty() {
-                // Return the most recent reliable price
+                // Return the most recent reliable price within the fallback's own (larger)
+                // staleness bound.
                 for price_entry in history.iter().rev() {
-                    if price_entry.is_reliable() && !Self::is_price_stale(env, &price_entry) {
+                    if price_entry.is_reliable()
+                        && Self::check_staleness(env, &asset_symbol, price_entry.timestamp, oracle_config.max_fallback_staleness).is_ok()
+                    {
                         return Ok(price_entry.price);
                     }
                 }
             }
         }
-        
-        Err(Symbol::new(env, "no_fallback_available"))
-    }
 
-    fn is_price_stale(env: &Env, price: &AggregatedPrice) -> bool {
-        let current_time = env.ledger().timestamp();
-        let staleness_threshold = 1800; // 30 minutes for fallback prices
-        current_time.saturating_sub(price.timestamp) > staleness_threshold
+        Err(Symbol::new(env, "no_fallback_available"))
     }
 
+    /// `force_inaccurate` lets a caller override the deviation-based accuracy check — used for
+    /// submissions `filter_valid_submissions` already dropped for a confidence-band mismatch,
+    /// which should count against the node regardless of how close `submission.price` itself
+    /// landed to `aggregated_price`.
     pub fn update_oracle_accuracy(
+        env: &Env,
         oracle_nodes: &mut Map<Address, OracleNode>,
         submission: &PriceData,
         aggregated_price: u64,
+        force_inaccurate: bool,
     ) {
         if let Some(mut node) = oracle_nodes.get(&submission.oracle_node) {
-            let price_diff = if submission.price > aggregated_price {
-                submission.price - aggregated_price
+            let was_accurate = if force_inaccurate {
+                false
             } else {
-                aggregated_price - submission.price
+                let price_diff = if submission.price > aggregated_price {
+                    submission.price - aggregated_price
+                } else {
+                    aggregated_price - submission.price
+                };
+
+                let deviation_percentage = if aggregated_price > 0 {
+                    (((price_diff as u128) * 100) / aggregated_price as u128).min(u32::MAX as u128) as u32
+                } else {
+                    100
+                };
+
+                deviation_percentage <= 5 // Within 5% is considered accurate
             };
-
-            let deviation_percentage = if aggregated_price > 0 {
-                ((price_diff * 100) / aggregated_price) as u32
-            } else {
-                100
-            };
-
-            let was_accurate = deviation_percentage <= 5; // Within 5% is considered accurate
             node.update_reputation(was_accurate);
-            
+
             oracle_nodes.set(submission.oracle_node.clone(), node);
         }
+
+        emit_price_submitted(
+            env,
+            submission.oracle_node.clone(),
+            submission.asset_symbol.clone(),
+            submission.price,
+            submission.confidence,
+        );
     }
 }
\ No newline at end of file
@@ -0,0 +1,153 @@
+use soroban_sdk::{contracttype, Address, Env, Symbol};
+
+/// Tags where a price returned to a caller actually came from, so integrators can apply
+/// different risk tolerances to an oracle-quorum price versus an AMM-derived one.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum PriceSource {
+    Oracle,
+    AmmPool,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PricedQuote {
+    pub price: u64,
+    pub source: PriceSource,
+    pub timestamp: u64,
+}
+
+/// A registered AMM pool used as a fallback price source when the oracle quorum is
+/// unavailable or stale. `price_cumulative` is a Uniswap-v2-style cumulative-price
+/// accumulator: each touch adds `last_spot_price * elapsed_seconds`, so a TWAP over any
+/// window can be derived from two cumulative snapshots taken at its endpoints.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AmmPool {
+    pub pool_address: Address,
+    pub token_a: Address,
+    pub token_b: Address,
+    pub price_cumulative: u128,
+    pub last_spot_price: u64,
+    pub last_update_ts: u64,
+    pub anchor_cumulative: u128,
+    pub anchor_ts: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AmmConfig {
+    pub twap_window: u64, // Seconds spanned by the rolling TWAP anchor
+}
+
+impl Default for AmmConfig {
+    fn default() -> Self {
+        Self { twap_window: 1800 } // 30 minutes
+    }
+}
+
+pub struct AmmFallbackManager;
+
+impl AmmFallbackManager {
+    pub fn get_pool(env: &Env, asset_symbol: &Symbol) -> Option<AmmPool> {
+        env.storage()
+            .instance()
+            .get(&crate::DataKey::AmmPool(asset_symbol.clone()))
+    }
+
+    fn set_pool(env: &Env, asset_symbol: &Symbol, pool: &AmmPool) {
+        env.storage()
+            .instance()
+            .set(&crate::DataKey::AmmPool(asset_symbol.clone()), pool);
+    }
+
+    pub fn register_pool(
+        env: &Env,
+        asset_symbol: &Symbol,
+        pool_address: Address,
+        token_a: Address,
+        token_b: Address,
+        initial_spot_price: u64,
+    ) {
+        let now = env.ledger().timestamp();
+        let pool = AmmPool {
+            pool_address,
+            token_a,
+            token_b,
+            price_cumulative: 0,
+            last_spot_price: initial_spot_price,
+            last_update_ts: now,
+            anchor_cumulative: 0,
+            anchor_ts: now,
+        };
+        Self::set_pool(env, asset_symbol, &pool);
+    }
+
+    pub fn remove_pool(env: &Env, asset_symbol: &Symbol) {
+        env.storage()
+            .instance()
+            .remove(&crate::DataKey::AmmPool(asset_symbol.clone()));
+    }
+
+    /// Advances the cumulative-price accumulator to the current ledger time using the
+    /// pool's previous spot price, then records `spot_price` as the new reading. Rolls the
+    /// TWAP anchor forward once it falls outside the configured window.
+    pub fn update_twap(
+        env: &Env,
+        asset_symbol: &Symbol,
+        spot_price: u64,
+    ) -> Result<(), Symbol> {
+        let mut pool = Self::get_pool(env, asset_symbol)
+            .ok_or_else(|| Symbol::new(env, "amm_pool_not_registered"))?;
+
+        let now = env.ledger().timestamp();
+        let elapsed = now.saturating_sub(pool.last_update_ts);
+        pool.price_cumulative = pool
+            .price_cumulative
+            .saturating_add((pool.last_spot_price as u128).saturating_mul(elapsed as u128));
+
+        let config = Self::get_config(env);
+        if now.saturating_sub(pool.anchor_ts) >= config.twap_window {
+            pool.anchor_cumulative = pool.price_cumulative;
+            pool.anchor_ts = now;
+        }
+
+        pool.last_spot_price = spot_price;
+        pool.last_update_ts = now;
+
+        Self::set_pool(env, asset_symbol, &pool);
+        Ok(())
+    }
+
+    /// Returns `(cum_now - cum_then) / (t_now - t_then)` over the rolling anchor window. A
+    /// window that has only partly elapsed is averaged over the time elapsed so far; the last
+    /// observed spot price is returned only when no time has passed since the anchor.
+    pub fn get_twap(env: &Env, asset_symbol: &Symbol) -> Result<u64, Symbol> {
+        let pool = Self::get_pool(env, asset_symbol)
+            .ok_or_else(|| Symbol::new(env, "amm_pool_not_registered"))?;
+
+        let now = env.ledger().timestamp();
+        let elapsed_since_touch = now.saturating_sub(pool.last_update_ts);
+        let cum_now = pool
+            .price_cumulative
+            .saturating_add((pool.last_spot_price as u128).saturating_mul(elapsed_since_touch as u128));
+
+        let window_elapsed = now.saturating_sub(pool.anchor_ts);
+        if window_elapsed == 0 {
+            return Ok(pool.last_spot_price);
+        }
+
+        Ok(((cum_now - pool.anchor_cumulative) / window_elapsed as u128) as u64)
+    }
+
+    pub fn get_config(env: &Env) -> AmmConfig {
+        env.storage()
+            .instance()
+            .get(&crate::DataKey::AmmConfig)
+            .unwrap_or_default()
+    }
+
+    pub fn set_config(env: &Env, config: &AmmConfig) {
+        env.storage().instance().set(&crate::DataKey::AmmConfig, config);
+    }
+}
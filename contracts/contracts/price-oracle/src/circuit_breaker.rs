@@ -0,0 +1,89 @@
+use soroban_sdk::{contracttype, Env, Symbol};
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CircuitBreakerConfig {
+    pub max_deviation_bps: u32, // Max move from the last live price before tripping
+    pub cooldown: u64, // Seconds the asset stays halted once tripped
+    pub max_spread_bps: u32, // Max (max_price - min_price) a quorum may show, as bps of the median
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        Self {
+            max_deviation_bps: 2000, // 20%
+            cooldown: 900,           // 15 minutes
+            max_spread_bps: 1500,    // 15%
+        }
+    }
+}
+
+pub struct CircuitBreaker;
+
+impl CircuitBreaker {
+    pub fn get_config(env: &Env, asset_symbol: &Symbol) -> CircuitBreakerConfig {
+        env.storage()
+            .instance()
+            .get(&crate::DataKey::CircuitBreakerConfig(asset_symbol.clone()))
+            .unwrap_or_default()
+    }
+
+    pub fn set_config(env: &Env, asset_symbol: &Symbol, config: &CircuitBreakerConfig) {
+        env.storage()
+            .instance()
+            .set(&crate::DataKey::CircuitBreakerConfig(asset_symbol.clone()), config);
+    }
+
+    /// Returns the timestamp the asset's trip expires at, or `None` if it isn't halted.
+    pub fn halted_until(env: &Env, asset_symbol: &Symbol) -> Option<u64> {
+        let halted_until: u64 = env
+            .storage()
+            .instance()
+            .get(&crate::DataKey::CircuitBreakerHalt(asset_symbol.clone()))
+            .unwrap_or(0);
+
+        if halted_until > env.ledger().timestamp() {
+            Some(halted_until)
+        } else {
+            None
+        }
+    }
+
+    /// Halts `asset_symbol` for `config.cooldown` seconds from now.
+    pub fn trip(env: &Env, asset_symbol: &Symbol, config: &CircuitBreakerConfig) {
+        let halted_until = env.ledger().timestamp() + config.cooldown;
+        env.storage()
+            .instance()
+            .set(&crate::DataKey::CircuitBreakerHalt(asset_symbol.clone()), &halted_until);
+    }
+
+    /// Returns `true` when `new_price` deviates from `old_price` by more than
+    /// `config.max_deviation_bps`.
+    pub fn deviation_exceeds(old_price: u64, new_price: u64, config: &CircuitBreakerConfig) -> bool {
+        if old_price == 0 {
+            return false;
+        }
+
+        let diff = if new_price > old_price {
+            new_price - old_price
+        } else {
+            old_price - new_price
+        };
+
+        let deviation_bps = ((diff as u128 * 10_000) / old_price as u128) as u32;
+        deviation_bps > config.max_deviation_bps
+    }
+
+    /// Returns `true` when the accepted submissions' `[min_price, max_price]` band is wider
+    /// than `config.max_spread_bps` of the aggregated price, signaling a quorum too internally
+    /// inconsistent to trust even though it cleared outlier filtering.
+    pub fn spread_exceeds(min_price: u64, max_price: u64, price: u64, config: &CircuitBreakerConfig) -> bool {
+        if price == 0 {
+            return false;
+        }
+
+        let spread = max_price.saturating_sub(min_price);
+        let spread_bps = ((spread as u128 * 10_000) / price as u128) as u32;
+        spread_bps > config.max_spread_bps
+    }
+}
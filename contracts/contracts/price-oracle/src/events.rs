@@ -0,0 +1,105 @@
+use soroban_sdk::{contracttype, Address, Env, Symbol};
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct NodeRegisteredEvent {
+    pub node_address: Address,
+    pub stake_amount: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct NodeDeactivatedEvent {
+    pub node_address: Address,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PriceSubmittedEvent {
+    pub node_address: Address,
+    pub asset_symbol: Symbol,
+    pub price: u64,
+    pub confidence: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PriceAggregatedEvent {
+    pub asset_symbol: Symbol,
+    pub price: u64,
+    pub confidence: u32,
+    pub deviation: u32,
+    pub source_count: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct NodeSlashedEvent {
+    pub node_address: Address,
+    pub slashed_amount: u64,
+    pub remaining_stake: u64,
+    pub still_active: bool,
+}
+
+pub fn emit_node_registered(env: &Env, node_address: Address, stake_amount: u64) {
+    let event = NodeRegisteredEvent {
+        node_address,
+        stake_amount,
+    };
+    env.events().publish(("node_registered",), event);
+}
+
+pub fn emit_node_deactivated(env: &Env, node_address: Address) {
+    let event = NodeDeactivatedEvent { node_address };
+    env.events().publish(("node_deactivated",), event);
+}
+
+pub fn emit_price_submitted(
+    env: &Env,
+    node_address: Address,
+    asset_symbol: Symbol,
+    price: u64,
+    confidence: u32,
+) {
+    let event = PriceSubmittedEvent {
+        node_address,
+        asset_symbol,
+        price,
+        confidence,
+    };
+    env.events().publish(("price_submitted",), event);
+}
+
+pub fn emit_price_aggregated(
+    env: &Env,
+    asset_symbol: Symbol,
+    price: u64,
+    confidence: u32,
+    deviation: u32,
+    source_count: u32,
+) {
+    let event = PriceAggregatedEvent {
+        asset_symbol,
+        price,
+        confidence,
+        deviation,
+        source_count,
+    };
+    env.events().publish(("price_aggregated",), event);
+}
+
+pub fn emit_node_slashed(
+    env: &Env,
+    node_address: Address,
+    slashed_amount: u64,
+    remaining_stake: u64,
+    still_active: bool,
+) {
+    let event = NodeSlashedEvent {
+        node_address,
+        slashed_amount,
+        remaining_stake,
+        still_active,
+    };
+    env.events().publish(("node_slashed",), event);
+}
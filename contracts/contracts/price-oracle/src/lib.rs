@@ -8,22 +8,53 @@ mod price_data;
 mod oracle_node;
 mod aggregation;
 mod validation;
+mod slashing;
+mod amm;
+mod round;
+mod circuit_breaker;
+mod rewards;
+mod events;
 
 pub use price_data::*;
 pub use oracle_node::*;
 pub use aggregation::*;
 pub use validation::*;
+pub use slashing::*;
+pub use amm::*;
+pub use round::*;
+pub use circuit_breaker::*;
+pub use rewards::*;
+pub use events::*;
 
 #[contracttype]
 pub enum DataKey {
     OracleNodes,
     RateLimits,
-    PriceData(Symbol), // Asset symbol -> Vec<PriceData>
     AggregatedPrices(Symbol), // Asset symbol -> AggregatedPrice
-    PriceHistory(Symbol), // Asset symbol -> Vec<AggregatedPrice>
+    PriceHistory(Symbol), // Asset symbol -> Vec<AggregatedPrice>, persistent storage
     Admin,
     EmergencyStop,
     SupportedAssets,
+    SlashingConfig,
+    OracleStatus(Address), // Oracle node address -> OracleStatus
+    SlashFlags(Address, u64), // (offender, round_id) -> flagging node addresses
+    StablePriceConfig,
+    OutlierConfig,
+    AmmPool(Symbol), // Asset symbol -> registered AMM fallback pool
+    AmmConfig,
+    CurrentRound(Symbol), // Asset symbol -> open/last-resolved Round, temporary storage
+    AggregatorConfig(Symbol), // Asset symbol -> AggregatorConfig
+    OracleConfig(Symbol), // Asset symbol -> OracleConfig (staleness and source-count policy)
+    PriceTwapHistory(Symbol), // Asset symbol -> PriceHistory ring buffer backing `get_twap`
+    CircuitBreakerConfig(Symbol), // Asset symbol -> CircuitBreakerConfig
+    CircuitBreakerHalt(Symbol), // Asset symbol -> halted-until timestamp
+    RewardConfig,
+    RewardBalance(Address), // Oracle node address -> accumulated unclaimed reward points
+    MissedRounds(Address, Symbol), // (oracle node, asset symbol) -> consecutive missed rounds
+    ReplayWindow(Address), // Oracle node address -> ReplayWindow of recently consumed submissions
+    MisbehaviorConfig,
+    MisbehaviorStatus(Address), // Oracle node address -> accumulated anomaly score and ban state
+    StablePriceModel(Symbol), // Asset symbol -> StablePriceModel delayed-price ring buffer
 }
 
 #[contracttype]
@@ -33,6 +64,25 @@ pub struct ContractConfig {
     pub emergency_stop: bool,
     pub min_oracle_nodes: u32,
     pub price_update_interval: u64,
+    pub price_staleness_threshold: u64,
+    pub max_history_entries: u32,
+    pub cleanup_window: u64,
+}
+
+impl ContractConfig {
+    /// Rejects settings that would leave the oracle in a nonsensical state: no nodes ever
+    /// required, a zero update cadence, or a cleanup window shorter than the staleness
+    /// threshold it's meant to outlive.
+    pub fn validate(&self, env: &Env) -> Result<(), Symbol> {
+        if self.min_oracle_nodes == 0
+            || self.price_update_interval == 0
+            || self.cleanup_window < self.price_staleness_threshold
+        {
+            return Err(Symbol::new(env, "invalid_config"));
+        }
+
+        Ok(())
+    }
 }
 
 #[contract]
@@ -50,6 +100,9 @@ impl PriceOracle {
             emergency_stop: false,
             min_oracle_nodes: 3,
             price_update_interval: 60, // 1 minute
+            price_staleness_threshold: PRICE_STALENESS_THRESHOLD,
+            max_history_entries: MAX_HISTORY_ENTRIES,
+            cleanup_window: 86400, // 24 hours
         };
 
         env.storage().instance().set(&DataKey::Admin, &config);
@@ -90,6 +143,10 @@ impl PriceOracle {
         Ok(())
     }
 
+    /// Records `caller`'s submission into the current round for `price_update.asset_symbol`,
+    /// opening a fresh round if none is open or the previous one timed out. Each oracle may
+    /// submit at most once per round; once `min_submissions` is reached the round is
+    /// aggregated and resolved deterministically, like a flux-aggregator round.
     pub fn submit_price(
         env: Env,
         caller: Address,
@@ -98,6 +155,8 @@ impl PriceOracle {
         caller.require_auth();
         Self::check_emergency_stop(&env)?;
 
+        let config = Self::get_config(&env)?;
+
         let nodes: Map<Address, OracleNode> = env
             .storage()
             .instance()
@@ -110,13 +169,19 @@ impl PriceOracle {
             .get(&DataKey::RateLimits)
             .unwrap_or_else(|| Map::new(&env));
 
+        let mut replay_window = ReplayWindow::load(&env, &caller);
+        let current_time = env.ledger().timestamp();
+        replay_window.prune(&env, current_time, config.price_staleness_threshold);
+
         // Validate the price update
         ValidationEngine::validate_price_update(
             &env,
             &price_update,
             &nodes,
             &rate_limits,
+            &replay_window,
             &caller,
+            config.price_staleness_threshold,
         )?;
 
         // Update rate limiting
@@ -127,7 +192,39 @@ impl PriceOracle {
             rate_limits.set(caller.clone(), rate_limit);
         }
 
-        // Store the price data
+        // Consume this submission so it can't be replayed
+        let sig_hash = ReplayWindow::hash_signature(&env, &price_update.signature);
+        replay_window.record(sig_hash, price_update.nonce, current_time);
+        replay_window.save(&env, &caller);
+
+        let round_config = RoundManager::get_config(&env, &price_update.asset_symbol);
+
+        // A round that ran out its max duration without reaching quorum still gets one shot at
+        // aggregating whatever it collected, rather than having those submissions silently
+        // discarded the moment `open_round` below starts a fresh round in its place.
+        if let Some(mut stale_round) = RoundManager::take_timed_out_round(&env, &price_update.asset_symbol, &round_config) {
+            let _ = Self::resolve_round(&env, &price_update.asset_symbol, &nodes, &mut stale_round, &config);
+            stale_round.resolved = true;
+            RoundManager::persist(&env, &price_update.asset_symbol, &stale_round, config.cleanup_window);
+        }
+
+        let mut round = RoundManager::open_round(
+            &env,
+            &price_update.asset_symbol,
+            &round_config,
+            config.cleanup_window,
+        );
+
+        if round.submissions.len() >= round_config.max_submissions {
+            return Err(Symbol::new(&env, "round_full"));
+        }
+
+        let mut historical: Map<Symbol, Vec<PriceData>> = Map::new(&env);
+        historical.set(
+            price_update.asset_symbol.clone(),
+            RoundManager::submissions_as_vec(&env, &round),
+        );
+
         let price_data = PriceData::new(
             &env,
             price_update.asset_symbol.clone(),
@@ -137,33 +234,27 @@ impl PriceOracle {
                 &env,
                 price_update.price,
                 &price_update.asset_symbol,
-                &env.storage().instance()
-                    .get(&DataKey::PriceData(price_update.asset_symbol.clone()))
-                    .unwrap_or_else(|| Vec::new(&env))
+                &historical,
             ).unwrap_or(50),
+            price_update.confidence_interval,
         );
 
-        let mut price_submissions: Vec<PriceData> = env
-            .storage()
-            .instance()
-            .get(&DataKey::PriceData(price_update.asset_symbol.clone()))
-            .unwrap_or_else(|| Vec::new(&env));
+        RoundManager::record_submission(&env, &mut round, &caller, price_data)?;
 
-        price_submissions.push_back(price_data.clone());
-
-        // Keep only recent submissions (last 24 hours)
-        Self::cleanup_old_price_data(&env, &mut price_submissions);
+        let round_submissions = RoundManager::submissions_as_vec(&env, &round);
+        for anomaly in ValidationEngine::detect_anomalous_patterns(&env, &round_submissions, &caller).iter() {
+            MisbehaviorTracker::report(&env, &caller, &anomaly);
+        }
 
-        env.storage().instance().set(
-            &DataKey::PriceData(price_update.asset_symbol.clone()),
-            &price_submissions,
-        );
         env.storage().instance().set(&DataKey::RateLimits, &rate_limits);
 
-        // Try to aggregate prices if we have enough data
-        Self::try_aggregate_prices(&env, &price_update.asset_symbol)?;
+        if round.submissions.len() >= round_config.min_submissions {
+            Self::resolve_round(&env, &price_update.asset_symbol, &nodes, &mut round, &config)?;
+        }
+
+        RoundManager::persist(&env, &price_update.asset_symbol, &round, config.cleanup_window);
 
-        log!(&env, "Price submitted for asset: {} by node: {}", 
+        log!(&env, "Price submitted for asset: {} by node: {}",
              price_update.asset_symbol, caller);
         Ok(())
     }
@@ -171,6 +262,10 @@ impl PriceOracle {
     pub fn get_price(env: Env, asset_symbol: Symbol) -> Result<AggregatedPrice, Symbol> {
         Self::check_emergency_stop(&env)?;
 
+        if CircuitBreaker::halted_until(&env, &asset_symbol).is_some() {
+            return Err(Symbol::new(&env, "circuit_breaker_tripped"));
+        }
+
         if let Some(aggregated_price) = env
             .storage()
             .instance()
@@ -179,11 +274,18 @@ impl PriceOracle {
             if !aggregated_price.is_reliable() {
                 return Err(Symbol::new(&env, "unreliable_price"));
             }
-            
-            // Check if price is stale
-            let current_time = env.ledger().timestamp();
-            if current_time.saturating_sub(aggregated_price.timestamp) > PRICE_STALENESS_THRESHOLD {
-                return Err(Symbol::new(&env, "stale_price"));
+
+            let oracle_config = PriceAggregator::get_config(&env, &asset_symbol);
+            PriceAggregator::check_staleness(&env, &asset_symbol, aggregated_price.timestamp, oracle_config.max_price_staleness)?;
+
+            let breaker_config = CircuitBreaker::get_config(&env, &asset_symbol);
+            if CircuitBreaker::spread_exceeds(
+                aggregated_price.min_price,
+                aggregated_price.max_price,
+                aggregated_price.price,
+                &breaker_config,
+            ) {
+                return Err(Symbol::new(&env, "confidence_spread_too_wide"));
             }
 
             return Ok(aggregated_price);
@@ -192,6 +294,175 @@ impl PriceOracle {
         Err(Symbol::new(&env, "price_not_available"))
     }
 
+    /// Like `get_price`, but lets the caller pick how tolerant it is of a stale reading via
+    /// `staleness_mode`, returning a `PriceView` carrying the age and confidence so the
+    /// caller can make its own risk decision instead of being blanket-denied.
+    pub fn get_price_ext(
+        env: Env,
+        asset_symbol: Symbol,
+        staleness_mode: StalenessMode,
+    ) -> Result<PriceView, Symbol> {
+        Self::check_emergency_stop(&env)?;
+
+        let aggregated_price: AggregatedPrice = env
+            .storage()
+            .instance()
+            .get(&DataKey::AggregatedPrices(asset_symbol.clone()))
+            .ok_or_else(|| Symbol::new(&env, "price_not_available"))?;
+
+        if !aggregated_price.is_reliable() {
+            return Err(Symbol::new(&env, "unreliable_price"));
+        }
+
+        let oracle_config = PriceAggregator::get_config(&env, &asset_symbol);
+        let current_time = env.ledger().timestamp();
+        let age_seconds = current_time.saturating_sub(aggregated_price.timestamp);
+        let is_stale = age_seconds > oracle_config.max_price_staleness;
+
+        match staleness_mode {
+            StalenessMode::Strict => {
+                if is_stale {
+                    PriceAggregator::check_staleness(&env, &asset_symbol, aggregated_price.timestamp, oracle_config.max_price_staleness)?;
+                }
+            }
+            StalenessMode::AllowStale => {}
+            StalenessMode::ConfidenceGated(confidence_floor) => {
+                if aggregated_price.confidence < confidence_floor {
+                    return Err(Symbol::new(&env, "confidence_below_floor"));
+                }
+            }
+        }
+
+        Ok(PriceView {
+            price: aggregated_price.price,
+            age_seconds,
+            is_stale,
+            confidence: aggregated_price.confidence,
+        })
+    }
+
+    /// Reverts unless `asset_symbol`'s current aggregated price is fresher than
+    /// `max_age_seconds` and within `max_deviation_bps` of `expected_price`. Meant to be
+    /// bundled alongside a scheduled execution so a transaction built against one view of
+    /// the oracle state can't land after the price has moved out from under it.
+    pub fn assert_price_view(
+        env: Env,
+        asset_symbol: Symbol,
+        expected_price: u64,
+        max_age_seconds: u64,
+        max_deviation_bps: u32,
+    ) -> Result<(), Symbol> {
+        Self::check_emergency_stop(&env)?;
+
+        let aggregated_price: AggregatedPrice = env
+            .storage()
+            .instance()
+            .get(&DataKey::AggregatedPrices(asset_symbol))
+            .ok_or_else(|| Symbol::new(&env, "price_not_available"))?;
+
+        if !aggregated_price.is_reliable() {
+            return Err(Symbol::new(&env, "unreliable_price"));
+        }
+
+        let current_time = env.ledger().timestamp();
+        let age_seconds = current_time.saturating_sub(aggregated_price.timestamp);
+        if age_seconds > max_age_seconds {
+            return Err(Symbol::new(&env, "price_view_stale"));
+        }
+
+        let diff = if aggregated_price.price > expected_price {
+            aggregated_price.price - expected_price
+        } else {
+            expected_price - aggregated_price.price
+        };
+        let deviation_bps = if expected_price > 0 {
+            ((diff as u128 * 10_000) / expected_price as u128) as u32
+        } else {
+            u32::MAX
+        };
+
+        if deviation_bps > max_deviation_bps {
+            return Err(Symbol::new(&env, "price_view_mismatch"));
+        }
+
+        Ok(())
+    }
+
+    /// Returns the damped stable price for `asset_symbol`, which moves toward the live
+    /// oracle price but cannot jump within a single ledger.
+    pub fn get_stable_price(env: Env, asset_symbol: Symbol) -> Result<u64, Symbol> {
+        Self::check_emergency_stop(&env)?;
+
+        let aggregated_price: AggregatedPrice = env
+            .storage()
+            .instance()
+            .get(&DataKey::AggregatedPrices(asset_symbol))
+            .ok_or_else(|| Symbol::new(&env, "price_not_available"))?;
+
+        Ok(aggregated_price.stable_price)
+    }
+
+    /// Returns `min(stable, oracle)` for `PriceSide::Collateral` reads or `max(stable, oracle)`
+    /// for `PriceSide::Debt` reads, so downstream logic always gets the conservative side.
+    pub fn get_conservative_price(env: Env, asset_symbol: Symbol, side: PriceSide) -> Result<u64, Symbol> {
+        Self::check_emergency_stop(&env)?;
+
+        let aggregated_price: AggregatedPrice = env
+            .storage()
+            .instance()
+            .get(&DataKey::AggregatedPrices(asset_symbol))
+            .ok_or_else(|| Symbol::new(&env, "price_not_available"))?;
+
+        Ok(aggregated_price.get_conservative_price(&side))
+    }
+
+    pub fn update_stable_price_config(
+        env: Env,
+        caller: Address,
+        tau: u64,
+        max_move_bps_per_sec: u64,
+        delay_interval_seconds: u64,
+    ) -> Result<(), Symbol> {
+        caller.require_auth();
+        Self::check_admin(&env, &caller)?;
+
+        if tau == 0 || delay_interval_seconds == 0 {
+            return Err(Symbol::new(&env, "invalid_config"));
+        }
+
+        let config = StablePriceConfig { tau, max_move_bps_per_sec, delay_interval_seconds };
+        env.storage().instance().set(&DataKey::StablePriceConfig, &config);
+        Ok(())
+    }
+
+    fn get_stable_price_config(env: &Env) -> StablePriceConfig {
+        env.storage()
+            .instance()
+            .get(&DataKey::StablePriceConfig)
+            .unwrap_or_default()
+    }
+
+    /// Loads the asset's `StablePriceModel`, seeding a fresh one (primed with `seed_price`,
+    /// e.g. the first-ever aggregated price) on the asset's first resolved round.
+    fn get_stable_price_model(
+        env: &Env,
+        asset_symbol: &Symbol,
+        config: &StablePriceConfig,
+        now: u64,
+        seed_price: u64,
+    ) -> StablePriceModel {
+        env.storage()
+            .instance()
+            .get(&DataKey::StablePriceModel(asset_symbol.clone()))
+            .unwrap_or_else(|| StablePriceModel::new(env, config.delay_interval_seconds, now, seed_price))
+    }
+
+    fn set_stable_price_model(env: &Env, asset_symbol: &Symbol, model: &StablePriceModel) {
+        env.storage()
+            .instance()
+            .set(&DataKey::StablePriceModel(asset_symbol.clone()), model);
+    }
+
     pub fn get_fallback_price(env: Env, asset_symbol: Symbol) -> Result<u64, Symbol> {
         Self::check_emergency_stop(&env)?;
 
@@ -201,7 +472,117 @@ impl PriceOracle {
             .get(&DataKey::PriceHistory(asset_symbol.clone()))
             .unwrap_or_else(|| Map::new(&env));
 
-        PriceAggregator::get_fallback_price(&env, asset_symbol, &price_history)
+        let oracle_config = PriceAggregator::get_config(&env, &asset_symbol);
+        PriceAggregator::get_fallback_price(&env, asset_symbol, &price_history, &oracle_config)
+    }
+
+    /// Aggregates `reports` directly via the plain-median pipeline rather than the node-weighted
+    /// one `submit_price` feeds — for a trusted relayer (e.g. another contract) that already
+    /// holds its own vetted `PriceData` and has no `OracleNode` registry to weight against.
+    /// Gated to the admin, since an unweighted median trusts its inputs outright.
+    pub fn submit_relayed_prices(
+        env: Env,
+        caller: Address,
+        asset_symbol: Symbol,
+        reports: Vec<PriceData>,
+    ) -> Result<AggregatedPrice, Symbol> {
+        caller.require_auth();
+        Self::check_admin(&env, &caller)?;
+        Self::check_emergency_stop(&env)?;
+
+        let config = Self::get_config(&env)?;
+        let result = PriceAggregator::aggregate_prices_median(&env, asset_symbol.clone(), reports)?;
+
+        env.storage().instance().set(&DataKey::AggregatedPrices(asset_symbol.clone()), &result);
+        Self::update_price_history(&env, &asset_symbol, &result, &config);
+
+        log!(&env, "Relayed prices aggregated for asset: {}", asset_symbol);
+        Ok(result)
+    }
+
+    /// Registers an AMM/DEX pool as an independent fallback price source for `asset_symbol`,
+    /// used when the oracle quorum can't produce a fresh, reliable price.
+    pub fn register_amm_fallback(
+        env: Env,
+        caller: Address,
+        asset_symbol: Symbol,
+        pool_address: Address,
+        token_a: Address,
+        token_b: Address,
+        initial_spot_price: u64,
+    ) -> Result<(), Symbol> {
+        caller.require_auth();
+        Self::check_admin(&env, &caller)?;
+
+        AmmFallbackManager::register_pool(&env, &asset_symbol, pool_address, token_a, token_b, initial_spot_price);
+
+        log!(&env, "AMM fallback registered for asset: {}", asset_symbol);
+        Ok(())
+    }
+
+    pub fn remove_amm_fallback(env: Env, caller: Address, asset_symbol: Symbol) -> Result<(), Symbol> {
+        caller.require_auth();
+        Self::check_admin(&env, &caller)?;
+
+        AmmFallbackManager::remove_pool(&env, &asset_symbol);
+
+        log!(&env, "AMM fallback removed for asset: {}", asset_symbol);
+        Ok(())
+    }
+
+    pub fn set_amm_twap_window(env: Env, caller: Address, twap_window: u64) -> Result<(), Symbol> {
+        caller.require_auth();
+        Self::check_admin(&env, &caller)?;
+
+        if twap_window == 0 {
+            return Err(Symbol::new(&env, "invalid_config"));
+        }
+
+        AmmFallbackManager::set_config(&env, &AmmConfig { twap_window });
+        Ok(())
+    }
+
+    /// Advances the registered pool's cumulative-price accumulator with a freshly observed
+    /// spot price. Intended to be called alongside normal pool activity (e.g. by a keeper),
+    /// analogous to how `submit_price` feeds the oracle quorum.
+    pub fn update_amm_twap(
+        env: Env,
+        caller: Address,
+        asset_symbol: Symbol,
+        spot_price: u64,
+    ) -> Result<(), Symbol> {
+        caller.require_auth();
+        Self::check_admin(&env, &caller)?;
+        Self::check_emergency_stop(&env)?;
+
+        AmmFallbackManager::update_twap(&env, &asset_symbol, spot_price)
+    }
+
+    pub fn get_amm_twap(env: Env, asset_symbol: Symbol) -> Result<u64, Symbol> {
+        AmmFallbackManager::get_twap(&env, &asset_symbol)
+    }
+
+    /// Returns the oracle-quorum price when it's available and reliable; otherwise falls
+    /// back to the registered AMM pool's TWAP, tagging the result with its `PriceSource` so
+    /// callers can apply their own risk tolerance to an AMM-derived read.
+    pub fn get_price_with_amm_fallback(env: Env, asset_symbol: Symbol) -> Result<PricedQuote, Symbol> {
+        match Self::get_price(env.clone(), asset_symbol.clone()) {
+            Ok(aggregated_price) => Ok(PricedQuote {
+                price: aggregated_price.price,
+                source: PriceSource::Oracle,
+                timestamp: aggregated_price.timestamp,
+            }),
+            Err(oracle_error) => {
+                match AmmFallbackManager::get_twap(&env, &asset_symbol) {
+                    Ok(twap_price) => Ok(PricedQuote {
+                        price: twap_price,
+                        source: PriceSource::AmmPool,
+                        timestamp: env.ledger().timestamp(),
+                    }),
+                    Err(_) => Err(oracle_error),
+                }
+            }
+        }
     }
 
     pub fn add_supported_asset(
@@ -256,11 +637,7 @@ impl PriceOracle {
         caller.require_auth();
         Self::check_admin(&env, &caller)?;
 
-        let mut config: ContractConfig = env
-            .storage()
-            .instance()
-            .get(&DataKey::Admin)
-            .ok_or_else(|| Symbol::new(&env, "not_initialized"))?;
+        let mut config = Self::get_config(&env)?;
 
         config.emergency_stop = emergency_stop;
         env.storage().instance().set(&DataKey::Admin, &config);
@@ -269,6 +646,20 @@ impl PriceOracle {
         Ok(())
     }
 
+    /// Replaces the whole `ContractConfig` in one call, validating it before committing so a
+    /// single governance action can reconfigure `min_oracle_nodes`, `price_update_interval`,
+    /// and the staleness/history/cleanup tuning that used to be hardcoded constants.
+    pub fn update_config(env: Env, caller: Address, new_config: ContractConfig) -> Result<(), Symbol> {
+        caller.require_auth();
+        Self::check_admin(&env, &caller)?;
+
+        new_config.validate(&env)?;
+        env.storage().instance().set(&DataKey::Admin, &new_config);
+
+        log!(&env, "Contract config updated by admin: {}", caller);
+        Ok(())
+    }
+
     pub fn get_oracle_node_info(env: Env, node_address: Address) -> Option<OracleNode> {
         let nodes: Map<Address, OracleNode> = env
             .storage()
@@ -286,14 +677,228 @@ impl PriceOracle {
             .unwrap_or_else(|| Vec::new(&env))
     }
 
+    /// Flag an offending node for a bad submission in a given round (identified by the
+    /// aggregated price timestamp). Once enough distinct flags accumulate, the node's
+    /// stake is slashed.
+    pub fn flag_submission(
+        env: Env,
+        caller: Address,
+        offender: Address,
+        round_id: u64,
+    ) -> Result<(), Symbol> {
+        caller.require_auth();
+        Self::check_emergency_stop(&env)?;
+
+        let mut nodes: Map<Address, OracleNode> = env
+            .storage()
+            .instance()
+            .get(&DataKey::OracleNodes)
+            .unwrap_or_else(|| Map::new(&env));
+
+        SlashingManager::flag_submission(&env, &mut nodes, &caller, &offender, round_id)?;
+
+        env.storage().instance().set(&DataKey::OracleNodes, &nodes);
+        Ok(())
+    }
+
+    pub fn get_oracle_status(env: Env, node: Address) -> OracleStatus {
+        SlashingManager::get_status(&env, &node)
+    }
+
+    pub fn get_reward_balance(env: Env, node: Address) -> u64 {
+        RewardManager::get_balance(&env, &node)
+    }
+
+    /// Reports `node`'s accumulated anomaly demerit score and ban state, as tracked by
+    /// `MisbehaviorTracker` off `ValidationEngine::detect_anomalous_patterns` flags.
+    pub fn get_misbehavior_status(env: Env, node: Address) -> MisbehaviorStatus {
+        MisbehaviorTracker::get_status(&env, &node)
+    }
+
+    pub fn set_misbehavior_ban_threshold(env: Env, caller: Address, ban_threshold: u32) -> Result<(), Symbol> {
+        caller.require_auth();
+        Self::check_admin(&env, &caller)?;
+
+        let mut config = MisbehaviorTracker::get_config(&env);
+        config.ban_threshold = ban_threshold;
+        MisbehaviorTracker::set_config(&env, &config);
+        Ok(())
+    }
+
+    pub fn set_slash_threshold_bps(env: Env, caller: Address, slash_threshold_bps: u32) -> Result<(), Symbol> {
+        caller.require_auth();
+        Self::check_admin(&env, &caller)?;
+
+        let mut config = SlashingManager::get_config(&env);
+        config.slash_threshold_bps = slash_threshold_bps;
+        SlashingManager::set_config(&env, &config);
+        Ok(())
+    }
+
+    pub fn set_slash_quorum(env: Env, caller: Address, slash_quorum: u32) -> Result<(), Symbol> {
+        caller.require_auth();
+        Self::check_admin(&env, &caller)?;
+
+        let mut config = SlashingManager::get_config(&env);
+        config.slash_quorum = slash_quorum;
+        SlashingManager::set_config(&env, &config);
+        Ok(())
+    }
+
+    pub fn set_slash_amount(env: Env, caller: Address, slash_amount: u64) -> Result<(), Symbol> {
+        caller.require_auth();
+        Self::check_admin(&env, &caller)?;
+
+        let mut config = SlashingManager::get_config(&env);
+        config.slash_amount = slash_amount;
+        SlashingManager::set_config(&env, &config);
+        Ok(())
+    }
+
+    /// Sets `k`, the number of scaled median-absolute-deviations a submission may deviate
+    /// from the median before it is excluded as an outlier.
+    pub fn set_outlier_k(env: Env, caller: Address, k: u32) -> Result<(), Symbol> {
+        caller.require_auth();
+        Self::check_admin(&env, &caller)?;
+
+        if k == 0 {
+            return Err(Symbol::new(&env, "invalid_config"));
+        }
+
+        let mut config = PriceAggregator::get_outlier_config(&env);
+        config.k = k;
+        PriceAggregator::set_outlier_config(&env, &config);
+        Ok(())
+    }
+
+    /// Sets the round lifecycle parameters for `asset_symbol`: how many submissions a round
+    /// needs before it resolves, how many it accepts before it's full, and how long it stays
+    /// open before being abandoned for a fresh one.
+    pub fn set_aggregator_config(
+        env: Env,
+        caller: Address,
+        asset_symbol: Symbol,
+        min_submissions: u32,
+        max_submissions: u32,
+        round_timeout: u64,
+    ) -> Result<(), Symbol> {
+        caller.require_auth();
+        Self::check_admin(&env, &caller)?;
+
+        if min_submissions == 0 || max_submissions < min_submissions || round_timeout == 0 {
+            return Err(Symbol::new(&env, "invalid_config"));
+        }
+
+        RoundManager::set_config(&env, &asset_symbol, &AggregatorConfig {
+            min_submissions,
+            max_submissions,
+            round_timeout,
+        });
+        Ok(())
+    }
+
+    /// Sets `asset_symbol`'s staleness and source-count policy: how old a live aggregate or a
+    /// fallback price may be before a read is refused, and how many eligible submissions a
+    /// round needs before it can aggregate at all.
+    pub fn set_oracle_config(
+        env: Env,
+        caller: Address,
+        asset_symbol: Symbol,
+        max_price_staleness: u64,
+        max_fallback_staleness: u64,
+        min_eligible_sources: u32,
+    ) -> Result<(), Symbol> {
+        caller.require_auth();
+        Self::check_admin(&env, &caller)?;
+
+        if max_price_staleness == 0 || max_fallback_staleness < max_price_staleness || min_eligible_sources == 0 {
+            return Err(Symbol::new(&env, "invalid_config"));
+        }
+
+        PriceAggregator::set_config(&env, &asset_symbol, &OracleConfig {
+            max_price_staleness,
+            max_fallback_staleness,
+            min_eligible_sources,
+        });
+        Ok(())
+    }
+
+    /// Sets the per-asset circuit breaker: how far a new aggregate may move from the live
+    /// price before it's rejected and the asset halted, how long that halt lasts, and how
+    /// wide the accepted submissions' price band may be before `get_price` refuses to serve it.
+    pub fn set_circuit_breaker_config(
+        env: Env,
+        caller: Address,
+        asset_symbol: Symbol,
+        max_deviation_bps: u32,
+        cooldown: u64,
+        max_spread_bps: u32,
+    ) -> Result<(), Symbol> {
+        caller.require_auth();
+        Self::check_admin(&env, &caller)?;
+
+        if max_deviation_bps == 0 || cooldown == 0 || max_spread_bps == 0 {
+            return Err(Symbol::new(&env, "invalid_config"));
+        }
+
+        CircuitBreaker::set_config(&env, &asset_symbol, &CircuitBreakerConfig {
+            max_deviation_bps,
+            cooldown,
+            max_spread_bps,
+        });
+        Ok(())
+    }
+
+    pub fn set_reward_config(
+        env: Env,
+        caller: Address,
+        reward_deviation_bps: u32,
+        min_accuracy_floor: u32,
+        max_missed_rounds: u32,
+    ) -> Result<(), Symbol> {
+        caller.require_auth();
+        Self::check_admin(&env, &caller)?;
+
+        if reward_deviation_bps == 0 || max_missed_rounds == 0 || min_accuracy_floor > 100 {
+            return Err(Symbol::new(&env, "invalid_config"));
+        }
+
+        RewardManager::set_config(&env, &RewardConfig {
+            reward_deviation_bps,
+            min_accuracy_floor,
+            max_missed_rounds,
+        });
+        Ok(())
+    }
+
+    /// Withdraws `caller`'s accumulated reward points earned for tracking the aggregated
+    /// price within `RewardConfig::reward_deviation_bps`. Returns the claimed amount so an
+    /// external payout contract (or the caller itself) can act on it; the balance resets to
+    /// zero once claimed.
+    pub fn claim_rewards(env: Env, caller: Address) -> Result<u64, Symbol> {
+        caller.require_auth();
+
+        let amount = RewardManager::claim(&env, &caller);
+        if amount == 0 {
+            return Err(Symbol::new(&env, "no_rewards_to_claim"));
+        }
+
+        log!(&env, "Oracle node {} claimed {} reward points", caller, amount);
+        Ok(amount)
+    }
+
     pub fn get_price_history(env: Env, asset_symbol: Symbol, limit: u32) -> Vec<AggregatedPrice> {
+        let max_history_entries = Self::get_config(&env)
+            .map(|c| c.max_history_entries)
+            .unwrap_or(MAX_HISTORY_ENTRIES);
+
         let history: Vec<AggregatedPrice> = env
             .storage()
-            .instance()
+            .persistent()
             .get(&DataKey::PriceHistory(asset_symbol))
             .unwrap_or_else(|| Vec::new(&env));
 
-        let limit = limit.min(MAX_HISTORY_ENTRIES);
+        let limit = limit.min(max_history_entries);
         let start_index = if history.len() > limit {
             history.len() - limit
         } else {
@@ -310,105 +915,170 @@ impl PriceOracle {
         result
     }
 
-    // Internal helper functions
-    fn try_aggregate_prices(env: &Env, asset_symbol: &Symbol) -> Result<(), Symbol> {
-        let price_submissions: Vec<PriceData> = env
-            .storage()
-            .instance()
-            .get(&DataKey::PriceData(asset_symbol.clone()))
-            .unwrap_or_else(|| Vec::new(env));
+    /// Computes a time-weighted average of `asset_symbol`'s aggregated-price history over the
+    /// trailing `window_seconds`, far costlier to manipulate in one round than the spot
+    /// aggregate. Walks history entries within `[now - window_seconds, now]` in timestamp
+    /// order, weighting each price by the seconds until the next entry (the final entry is
+    /// weighted up to `now`, and the first covered interval is clamped to the window start),
+    /// then divides the weighted sum by the total covered duration.
+    pub fn get_twap(env: Env, asset_symbol: Symbol, window_seconds: u64) -> Result<u64, Symbol> {
+        Self::check_emergency_stop(&env)?;
 
-        let nodes: Map<Address, OracleNode> = env
-            .storage()
-            .instance()
-            .get(&DataKey::OracleNodes)
-            .unwrap_or_else(|| Map::new(env));
+        let config = Self::get_config(&env)?;
+        let now = env.ledger().timestamp();
 
-        // Only aggregate if we have sufficient recent data
-        let recent_submissions = Self::filter_recent_submissions(env, &price_submissions);
-        
-        let config: ContractConfig = env
+        let history: PriceHistory = env
             .storage()
-            .instance()
-            .get(&DataKey::Admin)
-            .ok_or_else(|| Symbol::new(env, "not_initialized"))?;
+            .persistent()
+            .get(&DataKey::PriceTwapHistory(asset_symbol.clone()))
+            .unwrap_or_else(|| PriceHistory::new(&env, asset_symbol, config.max_history_entries));
 
-        if recent_submissions.len() < config.min_oracle_nodes {
-            return Ok(()); // Not enough data to aggregate
+        if let Some(last_ts) = history.timestamps.get(history.timestamps.len().saturating_sub(1)) {
+            if now.saturating_sub(last_ts) > config.price_staleness_threshold {
+                return Err(Symbol::new(&env, "stale_price"));
+            }
         }
 
-        match PriceAggregator::aggregate_prices(env, asset_symbol.clone(), &recent_submissions, &nodes) {
-            Ok(aggregated_price) => {
+        history
+            .twap(window_seconds, now)
+            .ok_or_else(|| Symbol::new(&env, "insufficient_history"))
+    }
+
+    // Internal helper functions
+
+    /// Aggregates `round`'s submissions into a new `AggregatedPrice` and marks it resolved.
+    /// Called once `submit_price` has collected `min_submissions` for the round; an
+    /// aggregation failure (e.g. too few submissions survive outlier filtering) is
+    /// propagated so the round is left unresolved for the next submission to retry.
+    fn resolve_round(
+        env: &Env,
+        asset_symbol: &Symbol,
+        nodes: &Map<Address, OracleNode>,
+        round: &mut Round,
+        config: &ContractConfig,
+    ) -> Result<(), Symbol> {
+        let submissions = RoundManager::submissions_as_vec(env, round);
+        let oracle_config = PriceAggregator::get_config(env, asset_symbol);
+
+        match PriceAggregator::aggregate_prices(
+            env,
+            asset_symbol.clone(),
+            &submissions,
+            nodes,
+            &oracle_config,
+        ) {
+            Ok((mut aggregated_price, band_mismatched)) => {
+                let previous: Option<AggregatedPrice> = env
+                    .storage()
+                    .instance()
+                    .get(&DataKey::AggregatedPrices(asset_symbol.clone()));
+
+                // A newly aggregated price that jumps too far from the last live one trips
+                // the breaker instead of being published, so one bad round can't move the
+                // price consumers see.
+                let breaker_config = CircuitBreaker::get_config(env, asset_symbol);
+                if let Some(prev) = &previous {
+                    if CircuitBreaker::deviation_exceeds(prev.price, aggregated_price.price, &breaker_config) {
+                        CircuitBreaker::trip(env, asset_symbol, &breaker_config);
+                        round.resolved = true;
+                        round.result = None;
+                        log!(env, "Circuit breaker tripped for asset: {}, round: {}",
+                             asset_symbol, round.round_id);
+                        return Ok(());
+                    }
+                }
+
+                let (prev_stable, prev_update_ts) = match &previous {
+                    Some(p) => (p.stable_price, p.last_update_ts),
+                    None => (aggregated_price.price, aggregated_price.timestamp),
+                };
+                let stable_config = Self::get_stable_price_config(env);
+
+                let mut stable_model = Self::get_stable_price_model(env, asset_symbol, &stable_config, aggregated_price.timestamp, aggregated_price.price);
+                let target = stable_model.advance(aggregated_price.timestamp, aggregated_price.price, prev_stable);
+                Self::set_stable_price_model(env, asset_symbol, &stable_model);
+
+                aggregated_price.update_stable_price(prev_stable, prev_update_ts, target, &stable_config);
+
                 // Store the aggregated price
                 env.storage().instance().set(
                     &DataKey::AggregatedPrices(asset_symbol.clone()),
                     &aggregated_price,
                 );
 
-                // Update price history
-                Self::update_price_history(env, asset_symbol, &aggregated_price);
+                // Archive to price history
+                Self::update_price_history(env, asset_symbol, &aggregated_price, config);
+                Self::record_twap_history(env, asset_symbol, &aggregated_price, config);
 
                 // Update oracle node accuracies
-                let mut updated_nodes = nodes;
-                for submission in recent_submissions.iter() {
+                let mut updated_nodes = nodes.clone();
+                for submission in submissions.iter() {
+                    let band_mismatch = band_mismatched.iter().any(|a| a == submission.oracle_node);
                     PriceAggregator::update_oracle_accuracy(
+                        env,
                         &mut updated_nodes,
                         &submission,
                         aggregated_price.price,
+                        band_mismatch,
+                    );
+
+                    SlashingManager::record_deviation(
+                        env,
+                        &mut updated_nodes,
+                        &submission.oracle_node,
+                        submission.price,
+                        aggregated_price.price,
+                        round.round_id,
                     );
                 }
+
+                RewardManager::settle_round(env, &mut updated_nodes, asset_symbol, &submissions, aggregated_price.price);
+
                 env.storage().instance().set(&DataKey::OracleNodes, &updated_nodes);
 
-                log!(env, "Price aggregated for asset: {}, price: {}", 
-                     asset_symbol, aggregated_price.price);
+                round.resolved = true;
+                round.result = Some(aggregated_price.clone());
+
+                log!(env, "Round {} resolved for asset: {}, price: {}",
+                     round.round_id, asset_symbol, aggregated_price.price);
                 Ok(())
             }
             Err(e) => {
-                log!(env, "Failed to aggregate prices for asset: {}, error: {}", asset_symbol, e);
+                log!(env, "Round {} failed to aggregate for asset: {}, error: {}",
+                     round.round_id, asset_symbol, e);
                 Err(e)
             }
         }
     }
 
-    fn filter_recent_submissions(env: &Env, submissions: &Vec<PriceData>) -> Vec<PriceData> {
-        let mut recent = Vec::new(env);
-        let current_time = env.ledger().timestamp();
-        
-        for submission in submissions.iter() {
-            if current_time.saturating_sub(submission.timestamp) <= PRICE_STALENESS_THRESHOLD {
-                recent.push_back(submission);
-            }
-        }
-        
-        recent
-    }
-
-    fn cleanup_old_price_data(env: &Env, submissions: &mut Vec<PriceData>) {
-        let current_time = env.ledger().timestamp();
-        const CLEANUP_THRESHOLD: u64 = 86400; // 24 hours
-
-        let mut cleaned = Vec::new(env);
-        for submission in submissions.iter() {
-            if current_time.saturating_sub(submission.timestamp) <= CLEANUP_THRESHOLD {
-                cleaned.push_back(submission);
-            }
-        }
-
-        *submissions = cleaned;
-    }
-
-    fn update_price_history(env: &Env, asset_symbol: &Symbol, aggregated_price: &AggregatedPrice) {
+    fn update_price_history(
+        env: &Env,
+        asset_symbol: &Symbol,
+        aggregated_price: &AggregatedPrice,
+        config: &ContractConfig,
+    ) {
         let mut history: Vec<AggregatedPrice> = env
             .storage()
-            .instance()
+            .persistent()
             .get(&DataKey::PriceHistory(asset_symbol.clone()))
             .unwrap_or_else(|| Vec::new(env));
 
         history.push_back(aggregated_price.clone());
 
-        // Keep only the last MAX_HISTORY_ENTRIES
-        if history.len() > MAX_HISTORY_ENTRIES {
-            let start_index = history.len() - MAX_HISTORY_ENTRIES;
+        // Drop entries older than `cleanup_window` before enforcing the count cap, so a
+        // deployment that shortens the window doesn't have to wait for the array to fill up.
+        let current_time = env.ledger().timestamp();
+        let mut fresh = Vec::new(env);
+        for entry in history.iter() {
+            if current_time.saturating_sub(entry.timestamp) <= config.cleanup_window {
+                fresh.push_back(entry);
+            }
+        }
+        history = fresh;
+
+        // Keep only the last `max_history_entries`
+        if history.len() > config.max_history_entries {
+            let start_index = history.len() - config.max_history_entries;
             let mut trimmed = Vec::new(env);
             for i in start_index..history.len() {
                 if let Some(entry) = history.get(i) {
@@ -418,15 +1088,45 @@ impl PriceOracle {
             history = trimmed;
         }
 
-        env.storage().instance().set(&DataKey::PriceHistory(asset_symbol.clone()), &history);
+        // Bump the archive's TTL on every write so an actively-updated asset's history stays
+        // live, while one nobody submits to is left to expire on its own.
+        let history_key = DataKey::PriceHistory(asset_symbol.clone());
+        env.storage().persistent().set(&history_key, &history);
+        let ttl = config.cleanup_window as u32;
+        env.storage().persistent().extend_ttl(&history_key, ttl / 2, ttl);
     }
 
-    fn check_admin(env: &Env, caller: &Address) -> Result<(), Symbol> {
-        let config: ContractConfig = env
+    /// Records the round's published price into the `PriceHistory` ring buffer `get_twap`
+    /// reads, bumping its TTL the same way `update_price_history` does for the archive.
+    fn record_twap_history(
+        env: &Env,
+        asset_symbol: &Symbol,
+        aggregated_price: &AggregatedPrice,
+        config: &ContractConfig,
+    ) {
+        let key = DataKey::PriceTwapHistory(asset_symbol.clone());
+        let mut twap_history: PriceHistory = env
             .storage()
+            .persistent()
+            .get(&key)
+            .unwrap_or_else(|| PriceHistory::new(env, asset_symbol.clone(), config.max_history_entries));
+
+        twap_history.record(env, aggregated_price.price);
+
+        env.storage().persistent().set(&key, &twap_history);
+        let ttl = config.cleanup_window as u32;
+        env.storage().persistent().extend_ttl(&key, ttl / 2, ttl);
+    }
+
+    fn get_config(env: &Env) -> Result<ContractConfig, Symbol> {
+        env.storage()
             .instance()
             .get(&DataKey::Admin)
-            .ok_or_else(|| Symbol::new(env, "not_initialized"))?;
+            .ok_or_else(|| Symbol::new(env, "not_initialized"))
+    }
+
+    fn check_admin(env: &Env, caller: &Address) -> Result<(), Symbol> {
+        let config = Self::get_config(env)?;
 
         if caller != &config.admin {
             return Err(Symbol::new(env, "unauthorized"));
@@ -436,11 +1136,7 @@ impl PriceOracle {
     }
 
     fn check_emergency_stop(env: &Env) -> Result<(), Symbol> {
-        let config: ContractConfig = env
-            .storage()
-            .instance()
-            .get(&DataKey::Admin)
-            .ok_or_else(|| Symbol::new(env, "not_initialized"))?;
+        let config = Self::get_config(env)?;
 
         if config.emergency_stop {
             return Err(Symbol::new(env, "emergency_stop_active"));
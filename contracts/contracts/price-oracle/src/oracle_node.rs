@@ -1,4 +1,33 @@
-use soroban_sdk::{contracttype, Address, Env, Map, Symbol, Vec};
+use soroban_sdk::{contracttype, Address, Bytes, BytesN, Env, Map, Symbol, Vec};
+use crate::events::{emit_node_deactivated, emit_node_registered};
+
+/// Selects which `env.crypto()` verifier `ValidationEngine::validate_signature` dispatches to,
+/// and therefore how `OracleNode::public_key` / `PriceUpdateRequest::signature` are laid out —
+/// each scheme has its own key and signature length rather than the contract hardcoding
+/// Ed25519's 32/64 bytes everywhere. Adding a scheme means adding a variant here plus a match
+/// arm in `validate_signature`; existing submissions are unaffected.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SignatureScheme {
+    Ed25519,
+    Secp256k1Recoverable,
+}
+
+impl SignatureScheme {
+    pub fn public_key_len(&self) -> u32 {
+        match self {
+            SignatureScheme::Ed25519 => 32,
+            SignatureScheme::Secp256k1Recoverable => 65, // uncompressed, 0x04-prefixed
+        }
+    }
+
+    pub fn signature_len(&self) -> u32 {
+        match self {
+            SignatureScheme::Ed25519 => 64,
+            SignatureScheme::Secp256k1Recoverable => 65, // 64-byte signature + 1-byte recovery id
+        }
+    }
+}
 
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -11,6 +40,10 @@ pub struct OracleNode {
     pub is_active: bool,
     pub stake_amount: u64,
     pub registered_time: u64,
+    /// Public key the node signs its `PriceUpdateRequest`s with, in the encoding
+    /// `signature_scheme` expects; checked by `ValidationEngine::validate_signature`.
+    pub public_key: Bytes,
+    pub signature_scheme: SignatureScheme,
 }
 
 #[contracttype]
@@ -19,6 +52,8 @@ pub struct NodeRegistration {
     pub node_address: Address,
     pub stake_amount: u64,
     pub metadata: Symbol, // JSON-like metadata as Symbol
+    pub public_key: Bytes,
+    pub signature_scheme: SignatureScheme,
 }
 
 #[contracttype]
@@ -38,7 +73,13 @@ pub const REPUTATION_DECAY_TIME: u64 = 86400 * 7; // 7 days
 pub const MIN_SUBMISSIONS_FOR_REPUTATION: u32 = 10;
 
 impl OracleNode {
-    pub fn new(env: &Env, address: Address, stake_amount: u64) -> Self {
+    pub fn new(
+        env: &Env,
+        address: Address,
+        stake_amount: u64,
+        public_key: Bytes,
+        signature_scheme: SignatureScheme,
+    ) -> Self {
         Self {
             address,
             reputation_score: 100, // Start with perfect reputation
@@ -48,6 +89,8 @@ impl OracleNode {
             is_active: true,
             stake_amount,
             registered_time: env.ledger().timestamp(),
+            public_key,
+            signature_scheme,
         }
     }
 
@@ -73,6 +116,7 @@ impl OracleNode {
             && self.stake_amount >= MIN_STAKE_AMOUNT
             && self.reputation_score >= MIN_REPUTATION_SCORE
             && !self.is_reputation_stale(env)
+            && !MisbehaviorTracker::is_banned(env, &self.address)
     }
 
     pub fn is_reputation_stale(&self, env: &Env) -> bool {
@@ -129,6 +173,200 @@ impl RateLimitInfo {
     }
 }
 
+/// Per-node sliding dedup window guarding against a captured, still-valid-looking
+/// `PriceUpdateRequest` being replayed: `ValidationEngine` rejects a submission whose `nonce`
+/// doesn't strictly increase, or whose signature hash is still inside `seen`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReplayWindow {
+    pub last_nonce: u64,
+    /// (signature hash, submission timestamp) pairs not yet pruned as stale.
+    pub seen: Vec<(BytesN<32>, u64)>,
+}
+
+impl ReplayWindow {
+    pub fn new(env: &Env) -> Self {
+        Self {
+            last_nonce: 0,
+            seen: Vec::new(env),
+        }
+    }
+
+    pub fn load(env: &Env, node: &Address) -> Self {
+        env.storage()
+            .instance()
+            .get(&crate::DataKey::ReplayWindow(node.clone()))
+            .unwrap_or_else(|| Self::new(env))
+    }
+
+    pub fn save(&self, env: &Env, node: &Address) {
+        env.storage()
+            .instance()
+            .set(&crate::DataKey::ReplayWindow(node.clone()), self);
+    }
+
+    /// Drops entries older than `staleness_threshold` relative to `now`, so the window's
+    /// storage footprint stays bounded by the staleness horizon instead of growing forever.
+    pub fn prune(&mut self, env: &Env, now: u64, staleness_threshold: u64) {
+        let mut kept = Vec::new(env);
+        for (hash, ts) in self.seen.iter() {
+            if now.saturating_sub(ts) <= staleness_threshold {
+                kept.push_back((hash, ts));
+            }
+        }
+        self.seen = kept;
+    }
+
+    pub fn contains(&self, hash: &BytesN<32>) -> bool {
+        self.seen.iter().any(|(seen_hash, _)| &seen_hash == hash)
+    }
+
+    pub fn record(&mut self, hash: BytesN<32>, nonce: u64, now: u64) {
+        self.last_nonce = nonce;
+        self.seen.push_back((hash, now));
+    }
+
+    /// Hashes a submission's signature down to a fixed-size key for `seen`, so the window
+    /// doesn't have to store the full (scheme-dependent length) signature per entry.
+    pub fn hash_signature(env: &Env, signature: &Bytes) -> BytesN<32> {
+        env.crypto().sha256(signature).into()
+    }
+}
+
+/// Per-node accumulated demerit score from `ValidationEngine::detect_anomalous_patterns`,
+/// backing a ban rather than just the advisory flags the detector used to return on its own.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MisbehaviorStatus {
+    pub score: u32,
+    pub banned_until_ledger: u32,
+    pub last_decay_ledger: u32,
+}
+
+impl MisbehaviorStatus {
+    fn new(env: &Env) -> Self {
+        Self {
+            score: 0,
+            banned_until_ledger: 0,
+            last_decay_ledger: env.ledger().sequence(),
+        }
+    }
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MisbehaviorConfig {
+    pub rapid_submissions_demerit: u32,
+    pub consistent_outliers_demerit: u32,
+    pub suspicious_patterns_demerit: u32,
+    pub ban_threshold: u32,
+    pub ban_duration_ledgers: u32,
+    pub decay_per_epoch: u32,
+    pub decay_epoch_ledgers: u32,
+}
+
+impl Default for MisbehaviorConfig {
+    fn default() -> Self {
+        Self {
+            rapid_submissions_demerit: 10,
+            consistent_outliers_demerit: 20,
+            suspicious_patterns_demerit: 15,
+            ban_threshold: 50,
+            ban_duration_ledgers: 17280, // ~1 day at 5s ledgers
+            decay_per_epoch: 5,
+            decay_epoch_ledgers: 17280,
+        }
+    }
+}
+
+pub struct MisbehaviorTracker;
+
+impl MisbehaviorTracker {
+    pub fn get_config(env: &Env) -> MisbehaviorConfig {
+        env.storage()
+            .instance()
+            .get(&crate::DataKey::MisbehaviorConfig)
+            .unwrap_or_default()
+    }
+
+    pub fn set_config(env: &Env, config: &MisbehaviorConfig) {
+        env.storage().instance().set(&crate::DataKey::MisbehaviorConfig, config);
+    }
+
+    pub fn get_status(env: &Env, node: &Address) -> MisbehaviorStatus {
+        env.storage()
+            .instance()
+            .get(&crate::DataKey::MisbehaviorStatus(node.clone()))
+            .unwrap_or_else(|| MisbehaviorStatus::new(env))
+    }
+
+    fn set_status(env: &Env, node: &Address, status: &MisbehaviorStatus) {
+        env.storage()
+            .instance()
+            .set(&crate::DataKey::MisbehaviorStatus(node.clone()), status);
+    }
+
+    fn demerit_for(config: &MisbehaviorConfig, anomaly: &Symbol, env: &Env) -> u32 {
+        if anomaly == &Symbol::new(env, "rapid_submissions") {
+            config.rapid_submissions_demerit
+        } else if anomaly == &Symbol::new(env, "consistent_outliers") {
+            config.consistent_outliers_demerit
+        } else if anomaly == &Symbol::new(env, "suspicious_patterns") {
+            config.suspicious_patterns_demerit
+        } else {
+            0
+        }
+    }
+
+    /// Decays `status.score` toward zero by `decay_per_epoch` for every whole
+    /// `decay_epoch_ledgers` elapsed since the last decay, so a node that stops misbehaving
+    /// works its way back to good standing instead of carrying a permanent mark forever.
+    fn decay(status: &mut MisbehaviorStatus, config: &MisbehaviorConfig, current_ledger: u32) {
+        if config.decay_epoch_ledgers == 0 {
+            return;
+        }
+        let elapsed = current_ledger.saturating_sub(status.last_decay_ledger);
+        let epochs = elapsed / config.decay_epoch_ledgers;
+        if epochs == 0 {
+            return;
+        }
+        status.score = status.score.saturating_sub(epochs * config.decay_per_epoch);
+        status.last_decay_ledger += epochs * config.decay_epoch_ledgers;
+    }
+
+    /// Decays `node`'s existing score, adds the weighted demerit for `anomaly`, and bans the
+    /// node for `ban_duration_ledgers` once the score crosses `ban_threshold`. Emits a
+    /// `misbehavior_reported` event carrying the node, anomaly type and resulting score.
+    pub fn report(env: &Env, node: &Address, anomaly: &Symbol) -> u32 {
+        let config = Self::get_config(env);
+        let current_ledger = env.ledger().sequence();
+
+        let mut status = Self::get_status(env, node);
+        Self::decay(&mut status, &config, current_ledger);
+
+        let demerit = Self::demerit_for(&config, anomaly, env);
+        status.score = status.score.saturating_add(demerit);
+
+        if status.score >= config.ban_threshold {
+            status.banned_until_ledger = current_ledger + config.ban_duration_ledgers;
+        }
+
+        Self::set_status(env, node, &status);
+
+        env.events().publish(
+            ("misbehavior_reported", node.clone(), anomaly.clone()),
+            status.score,
+        );
+
+        status.score
+    }
+
+    pub fn is_banned(env: &Env, node: &Address) -> bool {
+        let status = Self::get_status(env, node);
+        env.ledger().sequence() < status.banned_until_ledger
+    }
+}
+
 pub struct NodeManager;
 
 impl NodeManager {
@@ -146,11 +384,24 @@ impl NodeManager {
             return Err(Symbol::new(env, "insufficient_stake"));
         }
 
-        let node = OracleNode::new(env, registration.node_address.clone(), registration.stake_amount);
+        if registration.public_key.len() != registration.signature_scheme.public_key_len() {
+            return Err(Symbol::new(env, "invalid_public_key_length"));
+        }
+
+        let node = OracleNode::new(
+            env,
+            registration.node_address.clone(),
+            registration.stake_amount,
+            registration.public_key.clone(),
+            registration.signature_scheme.clone(),
+        );
         let rate_limit = RateLimitInfo::new(env, registration.node_address.clone());
 
         nodes.set(registration.node_address.clone(), node);
-        rate_limits.set(registration.node_address, rate_limit);
+        let stake_amount = registration.stake_amount;
+        rate_limits.set(registration.node_address.clone(), rate_limit);
+
+        emit_node_registered(env, registration.node_address, stake_amount);
 
         Ok(())
     }
@@ -162,6 +413,7 @@ impl NodeManager {
         if let Some(mut node) = nodes.get(node_address) {
             node.is_active = false;
             nodes.set(node_address.clone(), node);
+            emit_node_deactivated(&nodes.env(), node_address.clone());
             Ok(())
         } else {
             Err(Symbol::new(&nodes.env(), "node_not_found"))
@@ -1,4 +1,5 @@
-use soroban_sdk::{contracttype, Address, Env, Symbol};
+use soroban_sdk::{contracttype, Address, Bytes, Env, Symbol};
+use crate::oracle_node::SignatureScheme;
 
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -8,6 +9,11 @@ pub struct PriceData {
     pub timestamp: u64,
     pub oracle_node: Address,
     pub confidence: u32, // Confidence level (0-100)
+    /// Absolute ± spread the submitting node claims around `price`. A submission whose band
+    /// `[price - confidence_interval, price + confidence_interval]` doesn't overlap the
+    /// aggregate median is dropped by `PriceAggregator::filter_valid_submissions` even if
+    /// `price` itself wasn't a MAD outlier.
+    pub confidence_interval: u64,
 }
 
 #[contracttype]
@@ -19,6 +25,112 @@ pub struct AggregatedPrice {
     pub num_sources: u32,
     pub confidence: u32,
     pub deviation: u32, // Price deviation as percentage
+    pub stable_price: u64,
+    pub last_update_ts: u64,
+    pub min_price: u64, // Lowest accepted submission this round
+    pub max_price: u64, // Highest accepted submission this round
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StablePriceConfig {
+    pub tau: u64, // Smoothing time constant in seconds
+    pub max_move_bps_per_sec: u64, // Maximum relative move per second, in bps
+    /// How often `StablePriceModel` shifts a fresh sample into its delayed-price ring buffer.
+    pub delay_interval_seconds: u64,
+}
+
+impl Default for StablePriceConfig {
+    fn default() -> Self {
+        Self {
+            tau: 900, // 15 minutes
+            max_move_bps_per_sec: 2, // 0.02% per second cap
+            delay_interval_seconds: 3600, // 1 hour per ring slot, STABLE_PRICE_RING_SIZE hours of history
+        }
+    }
+}
+
+/// Number of delayed samples `StablePriceModel` retains — with the default hourly
+/// `delay_interval_seconds`, a full day of history.
+pub const STABLE_PRICE_RING_SIZE: u32 = 24;
+
+/// Tracks a slow-moving window of historical prices for an asset, independent of the
+/// instantaneous `AggregatedPrice::price`. `advance` is the manipulation-resistance core: it
+/// only admits a new sample into the ring once per `delay_interval_seconds`, then hands back
+/// whichever retained sample sits furthest from the current stable price. Chasing that
+/// "most extreme delayed price" — rather than chasing the live price directly — means a
+/// price spike can't move `stable_price` until it has aged into the buffer, so a single round
+/// (or a burst of colluding submissions within one `delay_interval_seconds`) can't just walk
+/// `stable_price` wherever it wants.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StablePriceModel {
+    pub delay_prices: soroban_sdk::Vec<u64>, // Ring buffer, oldest first, capped at STABLE_PRICE_RING_SIZE
+    pub delay_interval_seconds: u64,
+    pub last_delay_update: u64,
+}
+
+impl StablePriceModel {
+    pub fn new(env: &Env, delay_interval_seconds: u64, now: u64, initial_price: u64) -> Self {
+        let mut delay_prices = soroban_sdk::Vec::new(env);
+        delay_prices.push_back(initial_price);
+        Self {
+            delay_prices,
+            delay_interval_seconds,
+            last_delay_update: now,
+        }
+    }
+
+    /// Admits `live_price` into the ring if `delay_interval_seconds` has elapsed since the
+    /// last admission (evicting the oldest sample past `STABLE_PRICE_RING_SIZE`), then returns
+    /// the retained sample furthest from `stable_price` for the caller to chase toward.
+    pub fn advance(&mut self, now: u64, live_price: u64, stable_price: u64) -> u64 {
+        if now.saturating_sub(self.last_delay_update) >= self.delay_interval_seconds {
+            self.delay_prices.push_back(live_price);
+            if self.delay_prices.len() > STABLE_PRICE_RING_SIZE {
+                self.delay_prices.pop_front();
+            }
+            self.last_delay_update = now;
+        }
+
+        let mut most_extreme = stable_price;
+        let mut max_diff: u64 = 0;
+        for price in self.delay_prices.iter() {
+            let diff = price.max(stable_price) - price.min(stable_price);
+            if diff >= max_diff {
+                max_diff = diff;
+                most_extreme = price;
+            }
+        }
+        most_extreme
+    }
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum PriceSide {
+    Collateral, // Value assets conservatively: the lower of stable/oracle
+    Debt, // Value liabilities conservatively: the higher of stable/oracle
+}
+
+/// Controls how `get_price_ext` treats an aggregated price's age, letting each caller pick
+/// the risk tolerance appropriate to their own operation rather than being blanket-denied
+/// by a single hardcoded staleness threshold.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum StalenessMode {
+    Strict, // Reject prices older than the configured staleness threshold, matching `get_price`
+    AllowStale, // Always return the last value, flagging its age instead of rejecting it
+    ConfidenceGated(u32), // Accept any age as long as confidence is at least this floor
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PriceView {
+    pub price: u64,
+    pub age_seconds: u64,
+    pub is_stale: bool,
+    pub confidence: u32,
 }
 
 #[contracttype]
@@ -27,7 +139,21 @@ pub struct PriceUpdateRequest {
     pub asset_symbol: Symbol,
     pub price: u64,
     pub timestamp: u64,
-    pub signature: Symbol, // Simplified signature representation
+    /// Caller-chosen value folded into the signed message alongside `price`/`timestamp`, so a
+    /// signature is only ever valid for the exact fields it was produced for. Must strictly
+    /// increase per submitter — `ReplayWindow` rejects a submission whose `nonce` doesn't,
+    /// guarding against a captured `(request, signature)` pair being resubmitted.
+    pub nonce: u64,
+    /// Absolute ± spread the submitting node claims around `price`, signed alongside it so a
+    /// node can't claim a tighter band after the fact than what it actually submitted.
+    pub confidence_interval: u64,
+    /// Which `env.crypto()` verifier `signature` should be checked with; must match the
+    /// submitting node's registered `OracleNode::signature_scheme`.
+    pub scheme: SignatureScheme,
+    /// Signature over `ValidationEngine`'s canonical encoding of the fields above, in the
+    /// length/format `scheme` expects, checked against the submitting node's
+    /// `OracleNode::public_key`.
+    pub signature: Bytes,
 }
 
 #[contracttype]
@@ -40,7 +166,7 @@ pub struct PriceHistory {
 }
 
 pub const PRICE_STALENESS_THRESHOLD: u64 = 300; // 5 minutes in seconds
-pub const MAX_PRICE_DEVIATION: u32 = 10; // 10% maximum deviation
+pub const MAX_PRICE_DEVIATION: u32 = 1000; // Max scaled-MAD spread, in bps (10%)
 pub const MIN_CONFIDENCE_LEVEL: u32 = 70; // Minimum 70% confidence
 pub const MAX_HISTORY_ENTRIES: u32 = 100;
 
@@ -51,6 +177,7 @@ impl PriceData {
         price: u64,
         oracle_node: Address,
         confidence: u32,
+        confidence_interval: u64,
     ) -> Self {
         Self {
             asset_symbol,
@@ -58,17 +185,27 @@ impl PriceData {
             timestamp: env.ledger().timestamp(),
             oracle_node,
             confidence,
+            confidence_interval,
         }
     }
 
-    pub fn is_stale(&self, env: &Env) -> bool {
+    pub fn is_stale(&self, env: &Env, staleness_threshold: u64) -> bool {
         let current_time = env.ledger().timestamp();
-        current_time.saturating_sub(self.timestamp) > PRICE_STALENESS_THRESHOLD
+        current_time.saturating_sub(self.timestamp) > staleness_threshold
     }
 
     pub fn is_valid(&self) -> bool {
         self.price > 0 && self.confidence >= MIN_CONFIDENCE_LEVEL
     }
+
+    /// The price band this submission claims: `[price - confidence_interval, price +
+    /// confidence_interval]`.
+    pub fn band(&self) -> (u64, u64) {
+        (
+            self.price.saturating_sub(self.confidence_interval),
+            self.price.saturating_add(self.confidence_interval),
+        )
+    }
 }
 
 impl AggregatedPrice {
@@ -79,20 +216,145 @@ impl AggregatedPrice {
         num_sources: u32,
         confidence: u32,
         deviation: u32,
+        min_price: u64,
+        max_price: u64,
     ) -> Self {
+        let now = env.ledger().timestamp();
         Self {
             asset_symbol,
             price,
-            timestamp: env.ledger().timestamp(),
+            timestamp: now,
             num_sources,
             confidence,
             deviation,
+            stable_price: price,
+            last_update_ts: now,
+            min_price,
+            max_price,
         }
     }
 
     pub fn is_reliable(&self) -> bool {
-        self.num_sources >= 3 
-            && self.confidence >= MIN_CONFIDENCE_LEVEL 
+        self.num_sources >= 3
+            && self.confidence >= MIN_CONFIDENCE_LEVEL
             && self.deviation <= MAX_PRICE_DEVIATION
     }
-}
\ No newline at end of file
+
+    /// Advances the damped stable-price channel toward `target` — the most manipulation-
+    /// resistant reference a caller has for this update, e.g. `StablePriceModel::advance`'s
+    /// most-extreme delayed sample rather than the instantaneous `self.price` directly.
+    pub fn update_stable_price(&mut self, prev_stable: u64, prev_update_ts: u64, target: u64, config: &StablePriceConfig) {
+        let now = self.timestamp;
+        let dt = now.saturating_sub(prev_update_ts);
+
+        if dt == 0 {
+            self.stable_price = prev_stable;
+            self.last_update_ts = prev_update_ts;
+            return;
+        }
+
+        let tau = config.tau.max(1);
+        // Rational approximation of `1 - exp(-dt/tau)`, exact in the limits dt=0 and dt->inf.
+        let alpha_bps = ((dt as u128) * 10_000) / ((dt as u128) + (tau as u128));
+
+        let p = target as i128;
+        let stable = prev_stable as i128;
+        let diff = p - stable;
+        let smoothed_move = (diff * alpha_bps as i128) / 10_000;
+
+        let max_move = ((stable.unsigned_abs() as u128)
+            .saturating_mul(config.max_move_bps_per_sec as u128)
+            .saturating_mul(dt as u128)
+            / 10_000) as i128;
+
+        let clamped_move = smoothed_move.clamp(-max_move, max_move);
+        let new_stable = (stable + clamped_move).max(0);
+
+        self.stable_price = new_stable as u64;
+        self.last_update_ts = now;
+    }
+
+    /// Returns the conservative read for the given side: the lower of stable/oracle when
+    /// valuing collateral, the higher when valuing debt, so a single-block spike can only
+    /// ever hurt the side that benefits from the unsmoothed price.
+    pub fn get_conservative_price(&self, side: &PriceSide) -> u64 {
+        match side {
+            PriceSide::Collateral => self.price.min(self.stable_price),
+            PriceSide::Debt => self.price.max(self.stable_price),
+        }
+    }
+}
+impl PriceHistory {
+    pub fn new(env: &Env, asset_symbol: Symbol, max_entries: u32) -> Self {
+        Self {
+            asset_symbol,
+            prices: soroban_sdk::Vec::new(env),
+            timestamps: soroban_sdk::Vec::new(env),
+            max_entries: max_entries.min(MAX_HISTORY_ENTRIES),
+        }
+    }
+
+    /// Appends `price` at the current ledger time, evicting the oldest sample once
+    /// `max_entries` is exceeded.
+    pub fn record(&mut self, env: &Env, price: u64) {
+        self.prices.push_back(price);
+        self.timestamps.push_back(env.ledger().timestamp());
+
+        if self.prices.len() > self.max_entries {
+            self.prices.pop_front();
+            self.timestamps.pop_front();
+        }
+    }
+
+    /// Time-weighted average over the retained samples whose timestamp falls within
+    /// `[now - window_seconds, now]`. Walks those samples in order, weighting each price by
+    /// the duration until the next sample (the last interval is clamped to `now`), sums
+    /// `price * duration`, and divides by the total covered duration. Returns `None` if
+    /// fewer than two samples fall within the window.
+    pub fn twap(&self, window_seconds: u64, now: u64) -> Option<u64> {
+        let window_start = now.saturating_sub(window_seconds);
+        let len = self.timestamps.len();
+
+        let mut first_idx: Option<u32> = None;
+        let mut count: u32 = 0;
+        for i in 0..len {
+            let ts = self.timestamps.get(i)?;
+            if ts >= window_start && ts <= now {
+                if first_idx.is_none() {
+                    first_idx = Some(i);
+                }
+                count += 1;
+            }
+        }
+
+        let first_idx = first_idx?;
+        if count < 2 {
+            return None;
+        }
+
+        let mut weighted_sum: u128 = 0;
+        let mut total_duration: u128 = 0;
+
+        for offset in 0..count {
+            let i = first_idx + offset;
+            let price = self.prices.get(i)?;
+            let ts = self.timestamps.get(i)?;
+            let interval_start = ts.max(window_start);
+            let interval_end = if offset + 1 < count {
+                self.timestamps.get(i + 1)?
+            } else {
+                now
+            };
+
+            let duration = interval_end.saturating_sub(interval_start);
+            weighted_sum = weighted_sum.saturating_add((price as u128).saturating_mul(duration as u128));
+            total_duration = total_duration.saturating_add(duration as u128);
+        }
+
+        if total_duration == 0 {
+            return None;
+        }
+
+        Some((weighted_sum / total_duration) as u64)
+    }
+}
@@ -0,0 +1,137 @@
+use soroban_sdk::{contracttype, Address, Env, Map, Symbol, Vec};
+use crate::oracle_node::{NodeManager, OracleNode};
+use crate::price_data::PriceData;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RewardConfig {
+    pub reward_deviation_bps: u32, // Max deviation from the final price that still earns a point
+    pub min_accuracy_floor: u32, // Rolling reputation below which a node is auto-deactivated
+    pub max_missed_rounds: u32, // Consecutive resolved rounds a node can skip before deactivation
+}
+
+impl Default for RewardConfig {
+    fn default() -> Self {
+        Self {
+            reward_deviation_bps: 100, // Within 1% of the final price
+            min_accuracy_floor: 50,
+            max_missed_rounds: 5,
+        }
+    }
+}
+
+pub struct RewardManager;
+
+impl RewardManager {
+    pub fn get_config(env: &Env) -> RewardConfig {
+        env.storage()
+            .instance()
+            .get(&crate::DataKey::RewardConfig)
+            .unwrap_or_default()
+    }
+
+    pub fn set_config(env: &Env, config: &RewardConfig) {
+        env.storage().instance().set(&crate::DataKey::RewardConfig, config);
+    }
+
+    pub fn get_balance(env: &Env, node: &Address) -> u64 {
+        env.storage()
+            .instance()
+            .get(&crate::DataKey::RewardBalance(node.clone()))
+            .unwrap_or(0)
+    }
+
+    fn add_reward(env: &Env, node: &Address, points: u64) {
+        let balance = Self::get_balance(env, node).saturating_add(points);
+        env.storage().instance().set(&crate::DataKey::RewardBalance(node.clone()), &balance);
+    }
+
+    /// Zeroes `node`'s accumulated reward points and returns the amount claimed, for
+    /// `claim_rewards` to hand off to the caller (or an external payout contract).
+    pub fn claim(env: &Env, node: &Address) -> u64 {
+        let balance = Self::get_balance(env, node);
+        if balance > 0 {
+            env.storage().instance().set(&crate::DataKey::RewardBalance(node.clone()), &0u64);
+        }
+        balance
+    }
+
+    fn get_missed_rounds(env: &Env, node: &Address, asset_symbol: &Symbol) -> u32 {
+        env.storage()
+            .instance()
+            .get(&crate::DataKey::MissedRounds(node.clone(), asset_symbol.clone()))
+            .unwrap_or(0)
+    }
+
+    fn set_missed_rounds(env: &Env, node: &Address, asset_symbol: &Symbol, count: u32) {
+        env.storage()
+            .instance()
+            .set(&crate::DataKey::MissedRounds(node.clone(), asset_symbol.clone()), &count);
+    }
+
+    /// Closes the loop between accuracy tracking and real consequences once a round resolves:
+    /// a submission within `reward_deviation_bps` of the final price earns a reward point, an
+    /// active node that never submitted this round racks up a miss, and a node is
+    /// auto-deactivated the moment its rolling reputation drops below `min_accuracy_floor` or
+    /// it misses `max_missed_rounds` consecutive rounds for this asset.
+    pub fn settle_round(
+        env: &Env,
+        nodes: &mut Map<Address, OracleNode>,
+        asset_symbol: &Symbol,
+        submissions: &Vec<PriceData>,
+        aggregated_price: u64,
+    ) {
+        let config = Self::get_config(env);
+
+        let mut submitted: Vec<Address> = Vec::new(env);
+        for submission in submissions.iter() {
+            submitted.push_back(submission.oracle_node.clone());
+            Self::set_missed_rounds(env, &submission.oracle_node, asset_symbol, 0);
+
+            if aggregated_price == 0 {
+                continue;
+            }
+
+            let diff = if submission.price > aggregated_price {
+                submission.price - aggregated_price
+            } else {
+                aggregated_price - submission.price
+            };
+            let deviation_bps = ((diff as u128 * 10_000) / aggregated_price as u128) as u32;
+
+            if deviation_bps <= config.reward_deviation_bps {
+                Self::add_reward(env, &submission.oracle_node, 1);
+            }
+        }
+
+        // Collect active node addresses first so deactivating one below doesn't mutate `nodes`
+        // while this pass is still reading it.
+        let mut active_addresses: Vec<Address> = Vec::new(env);
+        for (address, node) in nodes.iter() {
+            if node.is_active {
+                active_addresses.push_back(address);
+            }
+        }
+
+        for address in active_addresses.iter() {
+            if submitted.iter().any(|a| a == address) {
+                continue;
+            }
+
+            let missed = Self::get_missed_rounds(env, &address, asset_symbol) + 1;
+            Self::set_missed_rounds(env, &address, asset_symbol, missed);
+
+            if missed >= config.max_missed_rounds {
+                let _ = NodeManager::deactivate_node(nodes, &address);
+            }
+        }
+
+        for submission in submissions.iter() {
+            if let Some(node) = nodes.get(&submission.oracle_node) {
+                if node.is_active && node.reputation_score < config.min_accuracy_floor {
+                    let _ = NodeManager::deactivate_node(nodes, &submission.oracle_node);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,149 @@
+use soroban_sdk::{contracttype, Address, Env, Map, Symbol, Vec};
+use crate::price_data::{AggregatedPrice, PriceData};
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Round {
+    pub round_id: u64,
+    pub asset_symbol: Symbol,
+    pub started_at: u64,
+    pub submissions: Map<Address, PriceData>,
+    pub resolved: bool,
+    /// The round's published aggregate, once resolution actually produced one. `resolved` can
+    /// be `true` with this still `None` — e.g. a circuit-breaker trip, or a timed-out round
+    /// finalized without ever reaching quorum.
+    pub result: Option<AggregatedPrice>,
+}
+
+impl Round {
+    fn new(env: &Env, round_id: u64, asset_symbol: Symbol) -> Self {
+        Self {
+            round_id,
+            asset_symbol,
+            started_at: env.ledger().timestamp(),
+            submissions: Map::new(env),
+            resolved: false,
+            result: None,
+        }
+    }
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AggregatorConfig {
+    pub min_submissions: u32,
+    pub max_submissions: u32,
+    pub round_timeout: u64,
+}
+
+impl Default for AggregatorConfig {
+    fn default() -> Self {
+        Self {
+            min_submissions: 3,
+            max_submissions: 10,
+            round_timeout: 300, // 5 minutes
+        }
+    }
+}
+
+pub struct RoundManager;
+
+impl RoundManager {
+    pub fn get_config(env: &Env, asset_symbol: &Symbol) -> AggregatorConfig {
+        env.storage()
+            .instance()
+            .get(&crate::DataKey::AggregatorConfig(asset_symbol.clone()))
+            .unwrap_or_default()
+    }
+
+    pub fn set_config(env: &Env, asset_symbol: &Symbol, config: &AggregatorConfig) {
+        env.storage()
+            .instance()
+            .set(&crate::DataKey::AggregatorConfig(asset_symbol.clone()), config);
+    }
+
+    fn get_round(env: &Env, asset_symbol: &Symbol) -> Option<Round> {
+        env.storage()
+            .temporary()
+            .get(&crate::DataKey::CurrentRound(asset_symbol.clone()))
+    }
+
+    /// Writes the round to temporary storage and bumps its TTL, so an asset with active
+    /// submissions stays live while one nobody submits to simply expires instead of needing
+    /// a manual cleanup scan.
+    fn set_round(env: &Env, asset_symbol: &Symbol, round: &Round, ttl_seconds: u64) {
+        let key = crate::DataKey::CurrentRound(asset_symbol.clone());
+        env.storage().temporary().set(&key, round);
+        let ttl = ttl_seconds as u32;
+        env.storage().temporary().extend_ttl(&key, ttl / 2, ttl);
+    }
+
+    /// Returns the round `submit_price` should record into for `asset_symbol`: the existing
+    /// round if it's still open and within `round_timeout`, or a fresh one otherwise. A round
+    /// that timed out without reaching `min_submissions` is abandoned rather than resolved.
+    /// `round_ttl` matches the round's entry in temporary storage to the caller's cleanup
+    /// window, so an abandoned round expires on its own rather than lingering forever.
+    pub fn open_round(env: &Env, asset_symbol: &Symbol, config: &AggregatorConfig, round_ttl: u64) -> Round {
+        let now = env.ledger().timestamp();
+
+        if let Some(round) = Self::get_round(env, asset_symbol) {
+            let timed_out = now.saturating_sub(round.started_at) > config.round_timeout;
+            if !round.resolved && !timed_out {
+                return round;
+            }
+        }
+
+        let next_round_id = Self::get_round(env, asset_symbol)
+            .map(|r| r.round_id + 1)
+            .unwrap_or(1);
+
+        let fresh = Round::new(env, next_round_id, asset_symbol.clone());
+        Self::set_round(env, asset_symbol, &fresh, round_ttl);
+        fresh
+    }
+
+    /// Returns the round currently open for `asset_symbol` if it has run past
+    /// `round_timeout` without ever being resolved, so `submit_price` can give it one last
+    /// finalization attempt over whatever submissions it collected before `open_round`
+    /// discards it in favor of a fresh round. Rounds that never received a submission are not
+    /// worth finalizing, so those are skipped.
+    pub fn take_timed_out_round(env: &Env, asset_symbol: &Symbol, config: &AggregatorConfig) -> Option<Round> {
+        let round = Self::get_round(env, asset_symbol)?;
+        let now = env.ledger().timestamp();
+        let timed_out = now.saturating_sub(round.started_at) > config.round_timeout;
+
+        if !round.resolved && timed_out && !round.submissions.is_empty() {
+            Some(round)
+        } else {
+            None
+        }
+    }
+
+    /// Records `submission` into `round` for `oracle_node`, rejecting a second submission
+    /// from the same node within the same round.
+    pub fn record_submission(
+        env: &Env,
+        round: &mut Round,
+        oracle_node: &Address,
+        submission: PriceData,
+    ) -> Result<(), Symbol> {
+        if round.submissions.get(oracle_node).is_some() {
+            return Err(Symbol::new(env, "dup_submission"));
+        }
+
+        round.submissions.set(oracle_node.clone(), submission);
+        Ok(())
+    }
+
+    pub fn persist(env: &Env, asset_symbol: &Symbol, round: &Round, round_ttl: u64) {
+        Self::set_round(env, asset_symbol, round, round_ttl);
+    }
+
+    pub fn submissions_as_vec(env: &Env, round: &Round) -> Vec<PriceData> {
+        let mut result = Vec::new(env);
+        for (_, submission) in round.submissions.iter() {
+            result.push_back(submission);
+        }
+        result
+    }
+}
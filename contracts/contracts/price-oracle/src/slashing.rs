@@ -0,0 +1,212 @@
+use soroban_sdk::{contracttype, Address, Env, Map, Symbol, Vec};
+use crate::oracle_node::OracleNode;
+use crate::events::emit_node_slashed;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OracleStatus {
+    pub accuracy_score: u32,
+    pub strikes: u32,
+    pub slashed_total: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SlashingConfig {
+    pub slash_threshold_bps: u32,
+    pub slash_quorum: u32,
+    pub slash_amount: u64,
+}
+
+impl Default for SlashingConfig {
+    fn default() -> Self {
+        Self {
+            slash_threshold_bps: 500, // 5%
+            slash_quorum: 2,
+            slash_amount: 100_0000000, // 100 XLM
+        }
+    }
+}
+
+impl OracleStatus {
+    pub fn new() -> Self {
+        Self {
+            accuracy_score: 100,
+            strikes: 0,
+            slashed_total: 0,
+        }
+    }
+}
+
+pub struct SlashingManager;
+
+impl SlashingManager {
+    pub fn get_status(env: &Env, node: &Address) -> OracleStatus {
+        env.storage()
+            .instance()
+            .get(&crate::DataKey::OracleStatus(node.clone()))
+            .unwrap_or_else(OracleStatus::new)
+    }
+
+    fn set_status(env: &Env, node: &Address, status: &OracleStatus) {
+        env.storage()
+            .instance()
+            .set(&crate::DataKey::OracleStatus(node.clone()), status);
+    }
+
+    fn get_flags(env: &Env, offender: &Address, round_id: u64) -> Vec<Address> {
+        env.storage()
+            .instance()
+            .get(&crate::DataKey::SlashFlags(offender.clone(), round_id))
+            .unwrap_or_else(|| Vec::new(env))
+    }
+
+    fn set_flags(env: &Env, offender: &Address, round_id: u64, flags: &Vec<Address>) {
+        env.storage()
+            .instance()
+            .set(&crate::DataKey::SlashFlags(offender.clone(), round_id), flags);
+    }
+
+    fn clear_flags(env: &Env, offender: &Address, round_id: u64) {
+        env.storage()
+            .instance()
+            .remove(&crate::DataKey::SlashFlags(offender.clone(), round_id));
+    }
+
+    /// Checks whether a submission deviated beyond `slash_threshold_bps` from the round's
+    /// aggregated median, and if so records a strike plus an automatic (system) flag toward
+    /// quorum. Called from the aggregation path, where the deviation is already confirmed.
+    pub fn record_deviation(
+        env: &Env,
+        nodes: &mut Map<Address, OracleNode>,
+        offender: &Address,
+        submitted_price: u64,
+        aggregated_price: u64,
+        round_id: u64,
+    ) {
+        let config = Self::get_config(env);
+
+        if aggregated_price == 0 {
+            return;
+        }
+
+        let diff = if submitted_price > aggregated_price {
+            submitted_price - aggregated_price
+        } else {
+            aggregated_price - submitted_price
+        };
+
+        let deviation_bps = ((diff as u128 * 10_000) / aggregated_price as u128) as u32;
+
+        if deviation_bps <= config.slash_threshold_bps {
+            return;
+        }
+
+        let mut status = Self::get_status(env, offender);
+        status.strikes += 1;
+        Self::set_status(env, offender, &status);
+
+        // The aggregation round itself confirms the deviation, so it counts as one flag.
+        Self::add_flag(env, nodes, offender, round_id, None, &config);
+    }
+
+    /// Lets another active oracle node flag `offender` for a bad submission in `round_id`.
+    pub fn flag_submission(
+        env: &Env,
+        nodes: &mut Map<Address, OracleNode>,
+        flagger: &Address,
+        offender: &Address,
+        round_id: u64,
+    ) -> Result<(), Symbol> {
+        if flagger == offender {
+            return Err(Symbol::new(env, "cannot_flag_self"));
+        }
+
+        let flagger_node = nodes
+            .get(flagger)
+            .ok_or_else(|| Symbol::new(env, "unregistered_node"))?;
+
+        if !flagger_node.is_eligible(env) {
+            return Err(Symbol::new(env, "node_not_eligible"));
+        }
+
+        if nodes.get(offender).is_none() {
+            return Err(Symbol::new(env, "offender_not_found"));
+        }
+
+        let config = Self::get_config(env);
+        Self::add_flag(env, nodes, offender, round_id, Some(flagger.clone()), &config);
+
+        Ok(())
+    }
+
+    fn add_flag(
+        env: &Env,
+        nodes: &mut Map<Address, OracleNode>,
+        offender: &Address,
+        round_id: u64,
+        flagger: Option<Address>,
+        config: &SlashingConfig,
+    ) {
+        let mut flags = Self::get_flags(env, offender, round_id);
+
+        if let Some(flagger) = flagger {
+            if flags.iter().any(|f| f == flagger) {
+                return;
+            }
+            flags.push_back(flagger);
+        } else {
+            // Synthetic system flag representing aggregation-confirmed deviation, stored as the
+            // offender's own address (which can never flag itself). Only add it once per round.
+            if flags.iter().any(|f| f == *offender) {
+                return;
+            }
+            flags.push_back(offender.clone());
+        }
+
+        if flags.len() >= config.slash_quorum {
+            Self::finalize_slash(env, nodes, offender, config);
+            Self::clear_flags(env, offender, round_id);
+        } else {
+            Self::set_flags(env, offender, round_id, &flags);
+        }
+    }
+
+    fn finalize_slash(
+        env: &Env,
+        nodes: &mut Map<Address, OracleNode>,
+        offender: &Address,
+        config: &SlashingConfig,
+    ) {
+        if let Some(mut node) = nodes.get(offender) {
+            node.slash_stake(config.slash_amount);
+            let remaining_stake = node.stake_amount;
+            let still_active = node.is_active;
+            nodes.set(offender.clone(), node);
+
+            emit_node_slashed(
+                env,
+                offender.clone(),
+                config.slash_amount,
+                remaining_stake,
+                still_active,
+            );
+        }
+
+        let mut status = Self::get_status(env, offender);
+        status.slashed_total = status.slashed_total.saturating_add(config.slash_amount);
+        status.accuracy_score = status.accuracy_score.saturating_sub(10);
+        Self::set_status(env, offender, &status);
+    }
+
+    pub fn get_config(env: &Env) -> SlashingConfig {
+        env.storage()
+            .instance()
+            .get(&crate::DataKey::SlashingConfig)
+            .unwrap_or_default()
+    }
+
+    pub fn set_config(env: &Env, config: &SlashingConfig) {
+        env.storage().instance().set(&crate::DataKey::SlashingConfig, config);
+    }
+}
@@ -1,7 +1,57 @@
 #![cfg(test)]
 
 use super::*;
-use soroban_sdk::{testutils::Address as _, Address, Env, Symbol};
+use core::sync::atomic::{AtomicU64, Ordering};
+use ed25519_dalek::{Signer, SigningKey};
+use soroban_sdk::xdr::ToXdr;
+use soroban_sdk::{testutils::Address as _, Address, Bytes, Env, Symbol};
+
+/// Shared across every test in this binary, so nonces handed out by `create_test_price_update`
+/// are always strictly increasing regardless of how many submissions a single test's ledger
+/// timestamp covers — `ReplayWindow` rejects a repeated or non-increasing nonce outright.
+static NEXT_NONCE: AtomicU64 = AtomicU64::new(1);
+
+fn next_nonce() -> u64 {
+    NEXT_NONCE.fetch_add(1, Ordering::Relaxed)
+}
+
+/// Every simulated oracle node in this suite signs with the same fixed keypair — the
+/// signature checks in `submit_price` exist to reject a bad signature, not to distinguish
+/// which registered node produced it, so a single shared key keeps the many call sites below
+/// unchanged.
+fn test_signing_key() -> SigningKey {
+    SigningKey::from_bytes(&[7u8; 32])
+}
+
+fn test_public_key(env: &Env) -> Bytes {
+    Bytes::from_array(env, &test_signing_key().verifying_key().to_bytes())
+}
+
+/// Mirrors `ValidationEngine::build_signed_message`'s canonical encoding so a test price
+/// update carries a signature `submit_price`'s `ed25519_verify` call actually accepts.
+fn sign_test_price_update(
+    env: &Env,
+    asset_symbol: &Symbol,
+    price: u64,
+    confidence_interval: u64,
+    timestamp: u64,
+    nonce: u64,
+) -> Bytes {
+    let mut message = Bytes::new(env);
+    message.append(&env.current_contract_address().to_xdr(env));
+    message.append(&Bytes::from_array(env, &env.ledger().network_id().to_array()));
+    message.append(&asset_symbol.to_xdr(env));
+    message.append(&Bytes::from_array(env, &price.to_be_bytes()));
+    message.append(&Bytes::from_array(env, &confidence_interval.to_be_bytes()));
+    message.append(&Bytes::from_array(env, &timestamp.to_be_bytes()));
+    message.append(&Bytes::from_array(env, &nonce.to_be_bytes()));
+
+    let mut bytes = [0u8; 4096];
+    let len = message.len() as usize;
+    message.copy_into_slice(&mut bytes[..len]);
+    let signature = test_signing_key().sign(&bytes[..len]);
+    Bytes::from_array(env, &signature.to_bytes())
+}
 
 fn create_test_env() -> (Env, Address, Address, Address) {
     let env = Env::default();
@@ -20,15 +70,38 @@ fn create_test_registration(env: &Env, node_address: &Address) -> NodeRegistrati
         node_address: node_address.clone(),
         stake_amount: 2000_0000000, // 2000 XLM
         metadata: Symbol::new(env, "test_oracle_node"),
+        public_key: test_public_key(env),
+        signature_scheme: SignatureScheme::Ed25519,
     }
 }
 
+/// Uses a permissive `± price` confidence interval, wide enough that the band-overlap check
+/// in `PriceAggregator::filter_valid_submissions` never rejects a submission built by this
+/// helper — tests that want to exercise that check use
+/// `create_test_price_update_with_interval` directly with a tighter value.
 fn create_test_price_update(env: &Env, asset: &str, price: u64) -> PriceUpdateRequest {
+    create_test_price_update_with_interval(env, asset, price, price)
+}
+
+fn create_test_price_update_with_interval(
+    env: &Env,
+    asset: &str,
+    price: u64,
+    confidence_interval: u64,
+) -> PriceUpdateRequest {
+    let asset_symbol = Symbol::new(env, asset);
+    let timestamp = env.ledger().timestamp();
+    let nonce = next_nonce();
+    let signature = sign_test_price_update(env, &asset_symbol, price, confidence_interval, timestamp, nonce);
+
     PriceUpdateRequest {
-        asset_symbol: Symbol::new(env, asset),
+        asset_symbol,
         price,
-        timestamp: env.ledger().timestamp(),
-        signature: Symbol::new(env, "test_signature_64_chars_long_placeholder_for_real_signature"),
+        timestamp,
+        nonce,
+        confidence_interval,
+        scheme: SignatureScheme::Ed25519,
+        signature,
     }
 }
 
@@ -317,13 +390,14 @@ fn test_price_data_validation() {
         &env,
         Symbol::new(&env, "XLM"),
         1000000,
-        node_address,
+        node_address.clone(),
         80,
+        5000,
     );
-    
+
     assert!(price_data.is_valid());
-    assert!(!price_data.is_stale(&env));
-    
+    assert!(!price_data.is_stale(&env, 300));
+
     // Test invalid price data
     let invalid_price_data = PriceData {
         asset_symbol: Symbol::new(&env, "XLM"),
@@ -331,6 +405,7 @@ fn test_price_data_validation() {
         timestamp: env.ledger().timestamp(),
         oracle_node: node_address,
         confidence: 50, // Below minimum confidence
+        confidence_interval: 5000,
     };
     
     assert!(!invalid_price_data.is_valid());
@@ -348,6 +423,8 @@ fn test_aggregated_price_reliability() {
         5, // 5 sources
         85, // 85% confidence
         3,  // 3% deviation
+        990000,
+        1010000,
     );
     
     assert!(reliable_price.is_reliable());
@@ -360,6 +437,8 @@ fn test_aggregated_price_reliability() {
         2, // Only 2 sources
         60, // Low confidence
         15, // High deviation
+        950000,
+        1050000,
     );
     
     assert!(!unreliable_price.is_reliable());
@@ -371,20 +450,56 @@ fn test_oracle_node_eligibility() {
     let node_address = Address::generate(&env);
     
     // Test eligible node
-    let eligible_node = OracleNode::new(&env, node_address.clone(), 2000_0000000);
+    let eligible_node = OracleNode::new(&env, node_address.clone(), 2000_0000000, test_public_key(&env), SignatureScheme::Ed25519);
     assert!(eligible_node.is_eligible(&env));
-    
+
     // Test ineligible node (insufficient stake)
-    let mut ineligible_node = OracleNode::new(&env, node_address.clone(), 500_0000000);
+    let mut ineligible_node = OracleNode::new(&env, node_address.clone(), 500_0000000, test_public_key(&env), SignatureScheme::Ed25519);
     ineligible_node.stake_amount = 500_0000000; // Below minimum
     assert!(!ineligible_node.is_eligible(&env));
-    
+
     // Test inactive node
-    let mut inactive_node = OracleNode::new(&env, node_address, 2000_0000000);
+    let mut inactive_node = OracleNode::new(&env, node_address, 2000_0000000, test_public_key(&env), SignatureScheme::Ed25519);
     inactive_node.is_active = false;
     assert!(!inactive_node.is_eligible(&env));
 }
 
+#[test]
+fn test_misbehavior_reports_accumulate_and_ban() {
+    let env = Env::default();
+    let node_address = Address::generate(&env);
+    let node = OracleNode::new(&env, node_address.clone(), 2000_0000000, test_public_key(&env), SignatureScheme::Ed25519);
+
+    MisbehaviorTracker::report(&env, &node_address, &Symbol::new(&env, "rapid_submissions"));
+    assert!(node.is_eligible(&env));
+
+    // Default config bans at a score of 50; two more "consistent_outliers" reports (20 each)
+    // push the node over the threshold.
+    MisbehaviorTracker::report(&env, &node_address, &Symbol::new(&env, "consistent_outliers"));
+    let status = MisbehaviorTracker::report(&env, &node_address, &Symbol::new(&env, "consistent_outliers"));
+
+    assert!(status.score >= 50);
+    assert!(MisbehaviorTracker::is_banned(&env, &node_address));
+    assert!(!node.is_eligible(&env));
+}
+
+#[test]
+fn test_misbehavior_score_decays_after_ban_expires() {
+    let env = Env::default();
+    let node_address = Address::generate(&env);
+
+    MisbehaviorTracker::report(&env, &node_address, &Symbol::new(&env, "rapid_submissions"));
+    MisbehaviorTracker::report(&env, &node_address, &Symbol::new(&env, "consistent_outliers"));
+    MisbehaviorTracker::report(&env, &node_address, &Symbol::new(&env, "consistent_outliers"));
+    assert!(MisbehaviorTracker::is_banned(&env, &node_address));
+
+    env.ledger().with_mut(|li| li.sequence_number += 17280 * 3 + 1);
+    assert!(!MisbehaviorTracker::is_banned(&env, &node_address));
+
+    let status = MisbehaviorTracker::get_status(&env, &node_address);
+    assert!(status.score < 40);
+}
+
 #[test]
 fn test_fallback_price() {
     let (env, admin, oracle1, oracle2) = create_test_env();
@@ -421,4 +536,1044 @@ fn test_fallback_price() {
     // But fallback price should still work
     let fallback_result = PriceOracle::get_fallback_price(env.clone(), Symbol::new(&env, "XLM"));
     assert!(fallback_result.is_ok());
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_mad_filter_excludes_planted_outlier() {
+    let (env, admin, oracle1, oracle2) = create_test_env();
+    init_contract(&env, &admin).unwrap();
+
+    let oracle3 = Address::generate(&env);
+    let oracle4 = Address::generate(&env);
+    for oracle in [&oracle1, &oracle2, &oracle3, &oracle4] {
+        let registration = create_test_registration(&env, oracle);
+        PriceOracle::register_oracle_node(env.clone(), oracle.clone(), registration).unwrap();
+    }
+
+    // Three oracles agree closely; one plants a wildly off price.
+    PriceOracle::submit_price(env.clone(), oracle1.clone(), create_test_price_update(&env, "XLM", 1000000)).unwrap();
+    PriceOracle::submit_price(env.clone(), oracle2.clone(), create_test_price_update(&env, "XLM", 1001000)).unwrap();
+    PriceOracle::submit_price(env.clone(), oracle3.clone(), create_test_price_update(&env, "XLM", 999000)).unwrap();
+    PriceOracle::submit_price(env.clone(), oracle4.clone(), create_test_price_update(&env, "XLM", 5000000)).unwrap();
+
+    let result = PriceOracle::get_price(env.clone(), Symbol::new(&env, "XLM")).unwrap();
+    // The outlier's price should not have dragged the aggregate toward it.
+    assert!(result.price < 1500000);
+    assert_eq!(result.num_sources, 3);
+}
+
+#[test]
+fn test_aggregation_rejects_insufficient_sources() {
+    let (env, admin, oracle1, oracle2) = create_test_env();
+    init_contract(&env, &admin).unwrap();
+
+    let registration1 = create_test_registration(&env, &oracle1);
+    let registration2 = create_test_registration(&env, &oracle2);
+    PriceOracle::register_oracle_node(env.clone(), oracle1.clone(), registration1).unwrap();
+    PriceOracle::register_oracle_node(env.clone(), oracle2.clone(), registration2).unwrap();
+
+    // Only two oracles registered, so aggregation never fires and no price is available.
+    PriceOracle::submit_price(env.clone(), oracle1.clone(), create_test_price_update(&env, "XLM", 1000000)).unwrap();
+    PriceOracle::submit_price(env.clone(), oracle2.clone(), create_test_price_update(&env, "XLM", 1000000)).unwrap();
+
+    let result = PriceOracle::get_price(env.clone(), Symbol::new(&env, "XLM"));
+    assert_eq!(result, Err(Symbol::new(&env, "price_not_available")));
+}
+
+#[test]
+fn test_weighted_median_skews_toward_higher_stake_node_without_expansion() {
+    let (env, admin, oracle1, oracle2) = create_test_env();
+    init_contract(&env, &admin).unwrap();
+
+    let oracle3 = Address::generate(&env);
+    // oracle3 stakes far more than the default, pushing its weight well past a simple
+    // one-submission-one-vote median without ever materializing `weight` duplicate entries.
+    let mut registration1 = create_test_registration(&env, &oracle1);
+    registration1.stake_amount = 1000_0000000; // 1000 XLM, the minimum: lowest weight
+    let mut registration2 = create_test_registration(&env, &oracle2);
+    registration2.stake_amount = 1000_0000000;
+    let mut registration3 = create_test_registration(&env, &oracle3);
+    registration3.stake_amount = 50000_0000000; // 50,000 XLM: clamped to the max stake multiplier
+
+    PriceOracle::register_oracle_node(env.clone(), oracle1.clone(), registration1).unwrap();
+    PriceOracle::register_oracle_node(env.clone(), oracle2.clone(), registration2).unwrap();
+    PriceOracle::register_oracle_node(env.clone(), oracle3.clone(), registration3).unwrap();
+
+    PriceOracle::submit_price(env.clone(), oracle1.clone(), create_test_price_update(&env, "XLM", 1000000)).unwrap();
+    PriceOracle::submit_price(env.clone(), oracle2.clone(), create_test_price_update(&env, "XLM", 1010000)).unwrap();
+    PriceOracle::submit_price(env.clone(), oracle3.clone(), create_test_price_update(&env, "XLM", 2000000)).unwrap();
+
+    let result = PriceOracle::get_price(env.clone(), Symbol::new(&env, "XLM")).unwrap();
+    // oracle3's far larger stake gives it a crossing weight majority, so the median lands on
+    // its price rather than the plain, unweighted middle value (1010000).
+    assert_eq!(result.price, 2000000);
+}
+
+#[test]
+fn test_stable_price_tracks_and_damps_spikes() {
+    let (env, admin, oracle1, oracle2) = create_test_env();
+    init_contract(&env, &admin).unwrap();
+
+    let oracle3 = Address::generate(&env);
+    for oracle in [&oracle1, &oracle2, &oracle3] {
+        let registration = create_test_registration(&env, oracle);
+        PriceOracle::register_oracle_node(env.clone(), oracle.clone(), registration).unwrap();
+    }
+
+    // First aggregation seeds stable_price == oracle price and primes the delayed-price ring
+    // with that same price.
+    PriceOracle::submit_price(env.clone(), oracle1.clone(), create_test_price_update(&env, "XLM", 1000000)).unwrap();
+    PriceOracle::submit_price(env.clone(), oracle2.clone(), create_test_price_update(&env, "XLM", 1000000)).unwrap();
+    PriceOracle::submit_price(env.clone(), oracle3.clone(), create_test_price_update(&env, "XLM", 1000000)).unwrap();
+
+    let stable = PriceOracle::get_stable_price(env.clone(), Symbol::new(&env, "XLM")).unwrap();
+    assert_eq!(stable, 1000000);
+
+    // A spike that hasn't aged past `delay_interval_seconds` never reaches the delayed-price
+    // ring, so the stable price doesn't move at all yet — not even partially.
+    env.ledger().with_mut(|li| li.timestamp += 1);
+    PriceOracle::submit_price(env.clone(), oracle1.clone(), create_test_price_update(&env, "XLM", 1050000)).unwrap();
+    PriceOracle::submit_price(env.clone(), oracle2.clone(), create_test_price_update(&env, "XLM", 1050000)).unwrap();
+    PriceOracle::submit_price(env.clone(), oracle3.clone(), create_test_price_update(&env, "XLM", 1050000)).unwrap();
+
+    let stable_immediately_after_spike = PriceOracle::get_stable_price(env.clone(), Symbol::new(&env, "XLM")).unwrap();
+    assert_eq!(stable_immediately_after_spike, 1000000);
+
+    // Once the spike has aged past the default hour-long `delay_interval_seconds`, it enters
+    // the ring as the most extreme delayed sample, and the stable price damps partway toward it.
+    env.ledger().with_mut(|li| li.timestamp += 3600);
+    PriceOracle::submit_price(env.clone(), oracle1.clone(), create_test_price_update(&env, "XLM", 1050000)).unwrap();
+    PriceOracle::submit_price(env.clone(), oracle2.clone(), create_test_price_update(&env, "XLM", 1050000)).unwrap();
+    PriceOracle::submit_price(env.clone(), oracle3.clone(), create_test_price_update(&env, "XLM", 1050000)).unwrap();
+
+    let stable_after_spike = PriceOracle::get_stable_price(env.clone(), Symbol::new(&env, "XLM")).unwrap();
+    assert!(stable_after_spike > 1000000 && stable_after_spike < 1050000);
+
+    let conservative_collateral = PriceOracle::get_conservative_price(
+        env.clone(),
+        Symbol::new(&env, "XLM"),
+        PriceSide::Collateral,
+    ).unwrap();
+    assert_eq!(conservative_collateral, stable_after_spike.min(1050000));
+
+    let conservative_debt = PriceOracle::get_conservative_price(
+        env.clone(),
+        Symbol::new(&env, "XLM"),
+        PriceSide::Debt,
+    ).unwrap();
+    assert_eq!(conservative_debt, stable_after_spike.max(1050000));
+}
+
+#[test]
+fn test_stable_price_ring_resists_sustained_single_interval_manipulation() {
+    let (env, admin, oracle1, oracle2) = create_test_env();
+    init_contract(&env, &admin).unwrap();
+
+    let oracle3 = Address::generate(&env);
+    for oracle in [&oracle1, &oracle2, &oracle3] {
+        let registration = create_test_registration(&env, oracle);
+        PriceOracle::register_oracle_node(env.clone(), oracle.clone(), registration).unwrap();
+    }
+
+    PriceOracle::submit_price(env.clone(), oracle1.clone(), create_test_price_update(&env, "XLM", 1000000)).unwrap();
+    PriceOracle::submit_price(env.clone(), oracle2.clone(), create_test_price_update(&env, "XLM", 1000000)).unwrap();
+    PriceOracle::submit_price(env.clone(), oracle3.clone(), create_test_price_update(&env, "XLM", 1000000)).unwrap();
+
+    // Repeated colluding submissions of an extreme price, all within the same
+    // `delay_interval_seconds` window, never age into the delayed-price ring — so however many
+    // rounds they span, the stable price stays put until real time has actually passed.
+    for _ in 0..5 {
+        env.ledger().with_mut(|li| li.timestamp += 10);
+        PriceOracle::submit_price(env.clone(), oracle1.clone(), create_test_price_update(&env, "XLM", 5000000)).unwrap();
+        PriceOracle::submit_price(env.clone(), oracle2.clone(), create_test_price_update(&env, "XLM", 5000000)).unwrap();
+        PriceOracle::submit_price(env.clone(), oracle3.clone(), create_test_price_update(&env, "XLM", 5000000)).unwrap();
+    }
+
+    let stable = PriceOracle::get_stable_price(env.clone(), Symbol::new(&env, "XLM")).unwrap();
+    assert_eq!(stable, 1000000);
+}
+
+#[test]
+fn test_slashing_quorum_forms_and_slashes() {
+    let (env, admin, oracle1, oracle2) = create_test_env();
+    init_contract(&env, &admin).unwrap();
+
+    let oracle3 = Address::generate(&env);
+    for oracle in [&oracle1, &oracle2, &oracle3] {
+        let registration = create_test_registration(&env, oracle);
+        PriceOracle::register_oracle_node(env.clone(), oracle.clone(), registration).unwrap();
+    }
+    PriceOracle::set_slash_quorum(env.clone(), admin.clone(), 2).unwrap();
+
+    // Two other nodes flag oracle1 for a bad submission in round 1.
+    PriceOracle::flag_submission(env.clone(), oracle2.clone(), oracle1.clone(), 1).unwrap();
+    let status = PriceOracle::get_oracle_status(env.clone(), oracle1.clone());
+    assert_eq!(status.slashed_total, 0);
+
+    PriceOracle::flag_submission(env.clone(), oracle3.clone(), oracle1.clone(), 1).unwrap();
+    let status = PriceOracle::get_oracle_status(env.clone(), oracle1.clone());
+    assert!(status.slashed_total > 0);
+}
+
+#[test]
+fn test_slashing_depletes_stake_and_deactivates() {
+    let (env, admin, oracle1, oracle2) = create_test_env();
+    init_contract(&env, &admin).unwrap();
+
+    let oracle3 = Address::generate(&env);
+    for oracle in [&oracle1, &oracle2, &oracle3] {
+        let registration = create_test_registration(&env, oracle);
+        PriceOracle::register_oracle_node(env.clone(), oracle.clone(), registration).unwrap();
+    }
+
+    PriceOracle::set_slash_quorum(env.clone(), admin.clone(), 2).unwrap();
+    // 2000 XLM staked, minimum is 1000 XLM - slash 1500 XLM to push below minimum.
+    PriceOracle::set_slash_amount(env.clone(), admin.clone(), 1500_0000000).unwrap();
+
+    PriceOracle::flag_submission(env.clone(), oracle2.clone(), oracle1.clone(), 1).unwrap();
+    PriceOracle::flag_submission(env.clone(), oracle3.clone(), oracle1.clone(), 1).unwrap();
+
+    let node_info = PriceOracle::get_oracle_node_info(env.clone(), oracle1.clone()).unwrap();
+    assert!(!node_info.is_active);
+    assert!(node_info.stake_amount < MIN_STAKE_AMOUNT);
+}
+
+#[test]
+fn test_flag_submission_rejects_self_flag() {
+    let (env, admin, oracle1, _) = create_test_env();
+    init_contract(&env, &admin).unwrap();
+
+    let registration = create_test_registration(&env, &oracle1);
+    PriceOracle::register_oracle_node(env.clone(), oracle1.clone(), registration).unwrap();
+
+    let result = PriceOracle::flag_submission(env.clone(), oracle1.clone(), oracle1.clone(), 1);
+    assert_eq!(result, Err(Symbol::new(&env, "cannot_flag_self")));
+}
+
+#[test]
+fn test_amm_fallback_used_when_oracle_price_is_stale() {
+    let (env, admin, oracle1, oracle2) = create_test_env();
+    init_contract(&env, &admin).unwrap();
+
+    let oracle3 = Address::generate(&env);
+    for oracle in [&oracle1, &oracle2, &oracle3] {
+        let registration = create_test_registration(&env, oracle);
+        PriceOracle::register_oracle_node(env.clone(), oracle.clone(), registration).unwrap();
+    }
+
+    PriceOracle::submit_price(env.clone(), oracle1.clone(), create_test_price_update(&env, "XLM", 1000000)).unwrap();
+    PriceOracle::submit_price(env.clone(), oracle2.clone(), create_test_price_update(&env, "XLM", 1001000)).unwrap();
+    PriceOracle::submit_price(env.clone(), oracle3.clone(), create_test_price_update(&env, "XLM", 999000)).unwrap();
+
+    // The oracle quorum produced a reliable price before the fallback is even registered.
+    let quote = PriceOracle::get_price_with_amm_fallback(env.clone(), Symbol::new(&env, "XLM")).unwrap();
+    assert_eq!(quote.source, PriceSource::Oracle);
+
+    let pool_address = Address::generate(&env);
+    let token_a = Address::generate(&env);
+    let token_b = Address::generate(&env);
+    PriceOracle::register_amm_fallback(
+        env.clone(),
+        admin.clone(),
+        Symbol::new(&env, "XLM"),
+        pool_address,
+        token_a,
+        token_b,
+        1002000,
+    ).unwrap();
+
+    // Advance past the oracle staleness threshold so `get_price` would hard-fail.
+    env.ledger().with_mut(|li| {
+        li.timestamp += PRICE_STALENESS_THRESHOLD + 1;
+    });
+
+    let stale_result = PriceOracle::get_price(env.clone(), Symbol::new(&env, "XLM"));
+    assert_eq!(stale_result, Err(Symbol::new(&env, "stale_price")));
+
+    let quote = PriceOracle::get_price_with_amm_fallback(env.clone(), Symbol::new(&env, "XLM")).unwrap();
+    assert_eq!(quote.source, PriceSource::AmmPool);
+    assert_eq!(quote.price, 1002000);
+}
+
+#[test]
+fn test_amm_twap_accumulates_over_registered_window() {
+    let (env, admin, _, _) = create_test_env();
+    init_contract(&env, &admin).unwrap();
+
+    let pool_address = Address::generate(&env);
+    let token_a = Address::generate(&env);
+    let token_b = Address::generate(&env);
+    PriceOracle::register_amm_fallback(
+        env.clone(),
+        admin.clone(),
+        Symbol::new(&env, "XLM"),
+        pool_address,
+        token_a,
+        token_b,
+        1000000,
+    ).unwrap();
+
+    env.ledger().with_mut(|li| li.timestamp += 600);
+    PriceOracle::update_amm_twap(env.clone(), admin.clone(), Symbol::new(&env, "XLM"), 1100000).unwrap();
+
+    env.ledger().with_mut(|li| li.timestamp += 600);
+
+    let twap = PriceOracle::get_amm_twap(env.clone(), Symbol::new(&env, "XLM")).unwrap();
+    // Spent 600s at 1,000,000 and 600s (so far) at 1,100,000, so the TWAP sits between them.
+    assert!(twap > 1000000 && twap < 1100000);
+}
+
+#[test]
+fn test_remove_amm_fallback_disables_it() {
+    let (env, admin, _, _) = create_test_env();
+    init_contract(&env, &admin).unwrap();
+
+    let pool_address = Address::generate(&env);
+    let token_a = Address::generate(&env);
+    let token_b = Address::generate(&env);
+    PriceOracle::register_amm_fallback(
+        env.clone(),
+        admin.clone(),
+        Symbol::new(&env, "XLM"),
+        pool_address,
+        token_a,
+        token_b,
+        1000000,
+    ).unwrap();
+
+    PriceOracle::remove_amm_fallback(env.clone(), admin.clone(), Symbol::new(&env, "XLM")).unwrap();
+
+    let result = PriceOracle::get_price_with_amm_fallback(env.clone(), Symbol::new(&env, "XLM"));
+    assert_eq!(result, Err(Symbol::new(&env, "price_not_available")));
+}
+
+fn submit_reliable_xlm_price(env: &Env, oracle1: &Address, oracle2: &Address, oracle3: &Address) {
+    PriceOracle::submit_price(env.clone(), oracle1.clone(), create_test_price_update(env, "XLM", 1000000)).unwrap();
+    PriceOracle::submit_price(env.clone(), oracle2.clone(), create_test_price_update(env, "XLM", 1001000)).unwrap();
+    PriceOracle::submit_price(env.clone(), oracle3.clone(), create_test_price_update(env, "XLM", 999000)).unwrap();
+}
+
+#[test]
+fn test_get_price_ext_strict_matches_get_price() {
+    let (env, admin, oracle1, oracle2) = create_test_env();
+    init_contract(&env, &admin).unwrap();
+
+    let oracle3 = Address::generate(&env);
+    for oracle in [&oracle1, &oracle2, &oracle3] {
+        let registration = create_test_registration(&env, oracle);
+        PriceOracle::register_oracle_node(env.clone(), oracle.clone(), registration).unwrap();
+    }
+    submit_reliable_xlm_price(&env, &oracle1, &oracle2, &oracle3);
+
+    env.ledger().with_mut(|li| li.timestamp += PRICE_STALENESS_THRESHOLD + 1);
+
+    let result = PriceOracle::get_price_ext(env.clone(), Symbol::new(&env, "XLM"), StalenessMode::Strict);
+    assert_eq!(result, Err(Symbol::new(&env, "stale_price")));
+}
+
+#[test]
+fn test_get_price_ext_allow_stale_returns_flagged_price() {
+    let (env, admin, oracle1, oracle2) = create_test_env();
+    init_contract(&env, &admin).unwrap();
+
+    let oracle3 = Address::generate(&env);
+    for oracle in [&oracle1, &oracle2, &oracle3] {
+        let registration = create_test_registration(&env, oracle);
+        PriceOracle::register_oracle_node(env.clone(), oracle.clone(), registration).unwrap();
+    }
+    submit_reliable_xlm_price(&env, &oracle1, &oracle2, &oracle3);
+
+    env.ledger().with_mut(|li| li.timestamp += PRICE_STALENESS_THRESHOLD + 1);
+
+    let view = PriceOracle::get_price_ext(env.clone(), Symbol::new(&env, "XLM"), StalenessMode::AllowStale).unwrap();
+    assert!(view.is_stale);
+    assert!(view.age_seconds > PRICE_STALENESS_THRESHOLD);
+    assert!(view.price > 0);
+}
+
+#[test]
+fn test_get_price_ext_confidence_gated_ignores_age() {
+    let (env, admin, oracle1, oracle2) = create_test_env();
+    init_contract(&env, &admin).unwrap();
+
+    let oracle3 = Address::generate(&env);
+    for oracle in [&oracle1, &oracle2, &oracle3] {
+        let registration = create_test_registration(&env, oracle);
+        PriceOracle::register_oracle_node(env.clone(), oracle.clone(), registration).unwrap();
+    }
+    submit_reliable_xlm_price(&env, &oracle1, &oracle2, &oracle3);
+
+    env.ledger().with_mut(|li| li.timestamp += PRICE_STALENESS_THRESHOLD * 10);
+
+    let view = PriceOracle::get_price_ext(
+        env.clone(),
+        Symbol::new(&env, "XLM"),
+        StalenessMode::ConfidenceGated(10),
+    ).unwrap();
+    assert!(view.is_stale);
+
+    let result = PriceOracle::get_price_ext(
+        env.clone(),
+        Symbol::new(&env, "XLM"),
+        StalenessMode::ConfidenceGated(101),
+    );
+    assert_eq!(result, Err(Symbol::new(&env, "confidence_below_floor")));
+}
+
+#[test]
+fn test_assert_price_view_accepts_matching_view() {
+    let (env, admin, oracle1, oracle2) = create_test_env();
+    init_contract(&env, &admin).unwrap();
+
+    let oracle3 = Address::generate(&env);
+    for oracle in [&oracle1, &oracle2, &oracle3] {
+        let registration = create_test_registration(&env, oracle);
+        PriceOracle::register_oracle_node(env.clone(), oracle.clone(), registration).unwrap();
+    }
+    submit_reliable_xlm_price(&env, &oracle1, &oracle2, &oracle3);
+
+    let current = PriceOracle::get_price(env.clone(), Symbol::new(&env, "XLM")).unwrap();
+
+    let result = PriceOracle::assert_price_view(env.clone(), Symbol::new(&env, "XLM"), current.price, 300, 100);
+    assert!(result.is_ok());
+}
+
+#[test]
+fn test_assert_price_view_rejects_drifted_price() {
+    let (env, admin, oracle1, oracle2) = create_test_env();
+    init_contract(&env, &admin).unwrap();
+
+    let oracle3 = Address::generate(&env);
+    for oracle in [&oracle1, &oracle2, &oracle3] {
+        let registration = create_test_registration(&env, oracle);
+        PriceOracle::register_oracle_node(env.clone(), oracle.clone(), registration).unwrap();
+    }
+    submit_reliable_xlm_price(&env, &oracle1, &oracle2, &oracle3);
+
+    let current = PriceOracle::get_price(env.clone(), Symbol::new(&env, "XLM")).unwrap();
+
+    // Caller signed a price far from what's currently aggregated.
+    let stale_expectation = current.price * 2;
+    let result = PriceOracle::assert_price_view(env.clone(), Symbol::new(&env, "XLM"), stale_expectation, 300, 100);
+    assert_eq!(result, Err(Symbol::new(&env, "price_view_mismatch")));
+}
+
+#[test]
+fn test_assert_price_view_rejects_stale_view() {
+    let (env, admin, oracle1, oracle2) = create_test_env();
+    init_contract(&env, &admin).unwrap();
+
+    let oracle3 = Address::generate(&env);
+    for oracle in [&oracle1, &oracle2, &oracle3] {
+        let registration = create_test_registration(&env, oracle);
+        PriceOracle::register_oracle_node(env.clone(), oracle.clone(), registration).unwrap();
+    }
+    submit_reliable_xlm_price(&env, &oracle1, &oracle2, &oracle3);
+
+    let current = PriceOracle::get_price(env.clone(), Symbol::new(&env, "XLM")).unwrap();
+
+    env.ledger().with_mut(|li| li.timestamp += 60);
+
+    let result = PriceOracle::assert_price_view(env.clone(), Symbol::new(&env, "XLM"), current.price, 30, 10_000);
+    assert_eq!(result, Err(Symbol::new(&env, "price_view_stale")));
+}
+
+#[test]
+fn test_submit_price_rejects_duplicate_submission_in_same_round() {
+    let (env, admin, oracle1, oracle2) = create_test_env();
+    init_contract(&env, &admin).unwrap();
+
+    let oracle3 = Address::generate(&env);
+    for oracle in [&oracle1, &oracle2, &oracle3] {
+        let registration = create_test_registration(&env, oracle);
+        PriceOracle::register_oracle_node(env.clone(), oracle.clone(), registration).unwrap();
+    }
+
+    let price_update = create_test_price_update(&env, "XLM", 1000000);
+    PriceOracle::submit_price(env.clone(), oracle1.clone(), price_update).unwrap();
+
+    // A distinct, freshly-signed submission so this exercises the round's own
+    // one-submission-per-oracle rule rather than `ReplayWindow` rejecting a reused nonce.
+    let second_price_update = create_test_price_update(&env, "XLM", 1001000);
+    let result = PriceOracle::submit_price(env.clone(), oracle1.clone(), second_price_update);
+    assert_eq!(result, Err(Symbol::new(&env, "dup_submission")));
+}
+
+#[test]
+fn test_round_resolves_once_min_submissions_reached() {
+    let (env, admin, oracle1, oracle2) = create_test_env();
+    init_contract(&env, &admin).unwrap();
+
+    let oracle3 = Address::generate(&env);
+    for oracle in [&oracle1, &oracle2, &oracle3] {
+        let registration = create_test_registration(&env, oracle);
+        PriceOracle::register_oracle_node(env.clone(), oracle.clone(), registration).unwrap();
+    }
+
+    PriceOracle::submit_price(env.clone(), oracle1.clone(), create_test_price_update(&env, "XLM", 1000000)).unwrap();
+    PriceOracle::submit_price(env.clone(), oracle2.clone(), create_test_price_update(&env, "XLM", 1010000)).unwrap();
+
+    // Not enough submissions yet to resolve the round.
+    assert_eq!(
+        PriceOracle::get_price(env.clone(), Symbol::new(&env, "XLM")),
+        Err(Symbol::new(&env, "price_not_available"))
+    );
+
+    PriceOracle::submit_price(env.clone(), oracle3.clone(), create_test_price_update(&env, "XLM", 1005000)).unwrap();
+
+    let aggregated_price = PriceOracle::get_price(env.clone(), Symbol::new(&env, "XLM")).unwrap();
+    assert_eq!(aggregated_price.num_sources, 3);
+
+    // A fourth oracle submitting after the round resolved opens a new round rather than
+    // being folded into the already-resolved one.
+    let oracle4 = Address::generate(&env);
+    let registration4 = create_test_registration(&env, &oracle4);
+    PriceOracle::register_oracle_node(env.clone(), oracle4.clone(), registration4).unwrap();
+    PriceOracle::submit_price(env.clone(), oracle4.clone(), create_test_price_update(&env, "XLM", 1002000)).unwrap();
+}
+
+#[test]
+fn test_round_times_out_and_is_abandoned_for_a_fresh_one() {
+    let (env, admin, oracle1, oracle2) = create_test_env();
+    init_contract(&env, &admin).unwrap();
+
+    for oracle in [&oracle1, &oracle2] {
+        let registration = create_test_registration(&env, oracle);
+        PriceOracle::register_oracle_node(env.clone(), oracle.clone(), registration).unwrap();
+    }
+
+    // Only two submissions: below the default `min_submissions` of 3, so the round never
+    // resolves on its own.
+    PriceOracle::submit_price(env.clone(), oracle1.clone(), create_test_price_update(&env, "XLM", 1000000)).unwrap();
+
+    // Let the round time out (default `round_timeout` is 300 seconds).
+    env.ledger().with_mut(|li| li.timestamp += 301);
+
+    // oracle1 can submit again because the timed-out round was abandoned for a fresh one,
+    // not because it's still the same round accepting a second vote.
+    let result = PriceOracle::submit_price(env.clone(), oracle1.clone(), create_test_price_update(&env, "XLM", 1000000));
+    assert!(result.is_ok());
+}
+
+#[test]
+fn test_timed_out_round_is_finalized_once_it_meets_aggregation_minimums() {
+    let (env, admin, oracle1, oracle2) = create_test_env();
+    init_contract(&env, &admin).unwrap();
+
+    let oracle3 = Address::generate(&env);
+    let oracle4 = Address::generate(&env);
+    for oracle in [&oracle1, &oracle2, &oracle3, &oracle4] {
+        let registration = create_test_registration(&env, oracle);
+        PriceOracle::register_oracle_node(env.clone(), oracle.clone(), registration).unwrap();
+    }
+
+    // `min_submissions` set above what the round will actually collect, so it never
+    // auto-resolves on its own — but it still gathers enough submissions to clear
+    // `PriceAggregator`'s own `MIN_AGGREGATION_SOURCES` floor of 3.
+    PriceOracle::set_aggregator_config(env.clone(), admin.clone(), Symbol::new(&env, "XLM"), 5, 10, 100).unwrap();
+
+    PriceOracle::submit_price(env.clone(), oracle1.clone(), create_test_price_update(&env, "XLM", 1000000)).unwrap();
+    PriceOracle::submit_price(env.clone(), oracle2.clone(), create_test_price_update(&env, "XLM", 1001000)).unwrap();
+    PriceOracle::submit_price(env.clone(), oracle3.clone(), create_test_price_update(&env, "XLM", 999000)).unwrap();
+
+    // Still unresolved: only 3 of the required 5 submissions are in.
+    let still_unavailable = PriceOracle::get_price(env.clone(), Symbol::new(&env, "XLM"));
+    assert_eq!(still_unavailable, Err(Symbol::new(&env, "price_not_available")));
+
+    // Let the round run past its timeout.
+    env.ledger().with_mut(|li| li.timestamp += 101);
+
+    // oracle4's submission opens a fresh round for itself, but first gives the timed-out round
+    // one last finalization attempt over the 3 submissions it already collected.
+    PriceOracle::submit_price(env.clone(), oracle4.clone(), create_test_price_update(&env, "XLM", 1002000)).unwrap();
+
+    let result = PriceOracle::get_price(env.clone(), Symbol::new(&env, "XLM")).unwrap();
+    assert_eq!(result.num_sources, 3);
+}
+
+#[test]
+fn test_set_aggregator_config_caps_round_at_max_submissions() {
+    let (env, admin, oracle1, oracle2) = create_test_env();
+    init_contract(&env, &admin).unwrap();
+
+    for oracle in [&oracle1, &oracle2] {
+        let registration = create_test_registration(&env, oracle);
+        PriceOracle::register_oracle_node(env.clone(), oracle.clone(), registration).unwrap();
+    }
+
+    // A high `min_submissions` keeps the round from resolving so `max_submissions` can be
+    // observed on its own.
+    PriceOracle::set_aggregator_config(env.clone(), admin.clone(), Symbol::new(&env, "XLM"), 10, 1, 300).unwrap();
+
+    PriceOracle::submit_price(env.clone(), oracle1.clone(), create_test_price_update(&env, "XLM", 1000000)).unwrap();
+
+    let result = PriceOracle::submit_price(env.clone(), oracle2.clone(), create_test_price_update(&env, "XLM", 1010000));
+    assert_eq!(result, Err(Symbol::new(&env, "round_full")));
+}
+
+#[test]
+fn test_set_aggregator_config_rejects_invalid_bounds() {
+    let (env, admin, _, _) = create_test_env();
+    init_contract(&env, &admin).unwrap();
+
+    let result = PriceOracle::set_aggregator_config(env.clone(), admin.clone(), Symbol::new(&env, "XLM"), 5, 2, 300);
+    assert_eq!(result, Err(Symbol::new(&env, "invalid_config")));
+}
+
+#[test]
+fn test_circuit_breaker_trips_on_large_deviation_and_halts_only_that_asset() {
+    let (env, admin, oracle1, oracle2) = create_test_env();
+    init_contract(&env, &admin).unwrap();
+
+    let oracle3 = Address::generate(&env);
+    for oracle in [&oracle1, &oracle2, &oracle3] {
+        let registration = create_test_registration(&env, oracle);
+        PriceOracle::register_oracle_node(env.clone(), oracle.clone(), registration).unwrap();
+    }
+
+    // Establish a live XLM price and an unrelated, unaffected BTC price.
+    submit_reliable_xlm_price(&env, &oracle1, &oracle2, &oracle3);
+    PriceOracle::submit_price(env.clone(), oracle1.clone(), create_test_price_update(&env, "BTC", 50_000_0000000)).unwrap();
+    PriceOracle::submit_price(env.clone(), oracle2.clone(), create_test_price_update(&env, "BTC", 50_010_0000000)).unwrap();
+    PriceOracle::submit_price(env.clone(), oracle3.clone(), create_test_price_update(&env, "BTC", 49_990_0000000)).unwrap();
+
+    // A round that jumps 5x past the default 20% max_deviation_bps trips the breaker.
+    PriceOracle::submit_price(env.clone(), oracle1.clone(), create_test_price_update(&env, "XLM", 5_000_000)).unwrap();
+    PriceOracle::submit_price(env.clone(), oracle2.clone(), create_test_price_update(&env, "XLM", 5_010_000)).unwrap();
+    PriceOracle::submit_price(env.clone(), oracle3.clone(), create_test_price_update(&env, "XLM", 4_990_000)).unwrap();
+
+    let result = PriceOracle::get_price(env.clone(), Symbol::new(&env, "XLM"));
+    assert_eq!(result, Err(Symbol::new(&env, "circuit_breaker_tripped")));
+
+    // BTC was never involved, so it stays live.
+    let btc_price = PriceOracle::get_price(env.clone(), Symbol::new(&env, "BTC"));
+    assert!(btc_price.is_ok());
+}
+
+#[test]
+fn test_get_price_rejects_wide_confidence_spread() {
+    let (env, admin, oracle1, oracle2) = create_test_env();
+    init_contract(&env, &admin).unwrap();
+
+    let oracle3 = Address::generate(&env);
+    for oracle in [&oracle1, &oracle2, &oracle3] {
+        let registration = create_test_registration(&env, oracle);
+        PriceOracle::register_oracle_node(env.clone(), oracle.clone(), registration).unwrap();
+    }
+
+    // Tighten `max_spread_bps` well below what a normal, reliable quorum produces so the
+    // submissions below (a 2% spread, nowhere near wide enough to be filtered as outliers)
+    // still trip the confidence-band gate.
+    PriceOracle::set_circuit_breaker_config(env.clone(), admin.clone(), Symbol::new(&env, "XLM"), 2000, 900, 50).unwrap();
+
+    PriceOracle::submit_price(env.clone(), oracle1.clone(), create_test_price_update(&env, "XLM", 990000)).unwrap();
+    PriceOracle::submit_price(env.clone(), oracle2.clone(), create_test_price_update(&env, "XLM", 1000000)).unwrap();
+    PriceOracle::submit_price(env.clone(), oracle3.clone(), create_test_price_update(&env, "XLM", 1010000)).unwrap();
+
+    let result = PriceOracle::get_price(env.clone(), Symbol::new(&env, "XLM"));
+    assert_eq!(result, Err(Symbol::new(&env, "confidence_spread_too_wide")));
+}
+
+#[test]
+fn test_set_circuit_breaker_config_rejects_zero_values() {
+    let (env, admin, _, _) = create_test_env();
+    init_contract(&env, &admin).unwrap();
+
+    let result = PriceOracle::set_circuit_breaker_config(env.clone(), admin.clone(), Symbol::new(&env, "XLM"), 0, 900, 1500);
+    assert_eq!(result, Err(Symbol::new(&env, "invalid_config")));
+}
+#[test]
+fn test_update_config_rejects_zero_min_oracle_nodes() {
+    let (env, admin, _, _) = create_test_env();
+    init_contract(&env, &admin).unwrap();
+
+    let mut config = PriceOracle::get_config(&env).unwrap();
+    config.min_oracle_nodes = 0;
+
+    let result = PriceOracle::update_config(env.clone(), admin.clone(), config);
+    assert_eq!(result, Err(Symbol::new(&env, "invalid_config")));
+}
+
+#[test]
+fn test_update_config_rejects_zero_price_update_interval() {
+    let (env, admin, _, _) = create_test_env();
+    init_contract(&env, &admin).unwrap();
+
+    let mut config = PriceOracle::get_config(&env).unwrap();
+    config.price_update_interval = 0;
+
+    let result = PriceOracle::update_config(env.clone(), admin.clone(), config);
+    assert_eq!(result, Err(Symbol::new(&env, "invalid_config")));
+}
+
+#[test]
+fn test_update_config_rejects_cleanup_window_shorter_than_staleness_threshold() {
+    let (env, admin, _, _) = create_test_env();
+    init_contract(&env, &admin).unwrap();
+
+    let mut config = PriceOracle::get_config(&env).unwrap();
+    config.cleanup_window = config.price_staleness_threshold - 1;
+
+    let result = PriceOracle::update_config(env.clone(), admin.clone(), config);
+    assert_eq!(result, Err(Symbol::new(&env, "invalid_config")));
+}
+
+#[test]
+fn test_update_config_rejects_non_admin_caller() {
+    let (env, admin, oracle1, _) = create_test_env();
+    init_contract(&env, &admin).unwrap();
+
+    let config = PriceOracle::get_config(&env).unwrap();
+    let result = PriceOracle::update_config(env.clone(), oracle1.clone(), config);
+    assert_eq!(result, Err(Symbol::new(&env, "unauthorized")));
+}
+
+#[test]
+fn test_oracle_config_lowers_staleness_threshold_for_get_price() {
+    let (env, admin, oracle1, oracle2) = create_test_env();
+    init_contract(&env, &admin).unwrap();
+
+    let oracle3 = Address::generate(&env);
+    for oracle in [&oracle1, &oracle2, &oracle3] {
+        let registration = create_test_registration(&env, oracle);
+        PriceOracle::register_oracle_node(env.clone(), oracle.clone(), registration).unwrap();
+    }
+
+    submit_reliable_xlm_price(&env, &oracle1, &oracle2, &oracle3);
+    assert!(PriceOracle::get_price(env.clone(), Symbol::new(&env, "XLM")).is_ok());
+
+    // Tighten XLM's staleness threshold well below the default 300s so a 120s-old price,
+    // which would still be fresh under the default, is now rejected as stale.
+    PriceOracle::set_oracle_config(env.clone(), admin.clone(), Symbol::new(&env, "XLM"), 60, 1800, 3).unwrap();
+
+    env.ledger().with_mut(|li| li.timestamp += 120);
+
+    let result = PriceOracle::get_price(env.clone(), Symbol::new(&env, "XLM"));
+    assert_eq!(result, Err(Symbol::new(&env, "stale_price")));
+}
+
+#[test]
+fn test_set_oracle_config_rejects_fallback_tighter_than_live_threshold() {
+    let (env, admin, _, _) = create_test_env();
+    init_contract(&env, &admin).unwrap();
+
+    let result = PriceOracle::set_oracle_config(env.clone(), admin.clone(), Symbol::new(&env, "XLM"), 300, 60, 3);
+    assert_eq!(result, Err(Symbol::new(&env, "invalid_config")));
+}
+
+#[test]
+fn test_get_twap_computes_time_weighted_average_over_history() {
+    let (env, admin, oracle1, oracle2) = create_test_env();
+    init_contract(&env, &admin).unwrap();
+
+    let oracle3 = Address::generate(&env);
+    for oracle in [&oracle1, &oracle2, &oracle3] {
+        let registration = create_test_registration(&env, oracle);
+        PriceOracle::register_oracle_node(env.clone(), oracle.clone(), registration).unwrap();
+    }
+
+    PriceOracle::submit_price(env.clone(), oracle1.clone(), create_test_price_update(&env, "XLM", 1000000)).unwrap();
+    PriceOracle::submit_price(env.clone(), oracle2.clone(), create_test_price_update(&env, "XLM", 1001000)).unwrap();
+    PriceOracle::submit_price(env.clone(), oracle3.clone(), create_test_price_update(&env, "XLM", 999000)).unwrap();
+
+    env.ledger().with_mut(|li| li.timestamp += 100);
+    PriceOracle::submit_price(env.clone(), oracle1.clone(), create_test_price_update(&env, "XLM", 1010000)).unwrap();
+    PriceOracle::submit_price(env.clone(), oracle2.clone(), create_test_price_update(&env, "XLM", 1011000)).unwrap();
+    PriceOracle::submit_price(env.clone(), oracle3.clone(), create_test_price_update(&env, "XLM", 1009000)).unwrap();
+
+    env.ledger().with_mut(|li| li.timestamp += 100);
+    PriceOracle::submit_price(env.clone(), oracle1.clone(), create_test_price_update(&env, "XLM", 1020000)).unwrap();
+    PriceOracle::submit_price(env.clone(), oracle2.clone(), create_test_price_update(&env, "XLM", 1021000)).unwrap();
+    PriceOracle::submit_price(env.clone(), oracle3.clone(), create_test_price_update(&env, "XLM", 1019000)).unwrap();
+
+    // 3 history entries at t=0 (~1000000), t=100 (~1010000), t=200 (~1020000, now).
+    // weighted_sum = 1000000*100 + 1010000*100 + 1020000*0 = 201_000_000 over 200s = 1_005_000.
+    let twap = PriceOracle::get_twap(env.clone(), Symbol::new(&env, "XLM"), 300).unwrap();
+    assert_eq!(twap, 1_005_000);
+}
+
+#[test]
+fn test_get_twap_rejects_with_fewer_than_two_points_in_window() {
+    let (env, admin, oracle1, oracle2) = create_test_env();
+    init_contract(&env, &admin).unwrap();
+
+    let oracle3 = Address::generate(&env);
+    for oracle in [&oracle1, &oracle2, &oracle3] {
+        let registration = create_test_registration(&env, oracle);
+        PriceOracle::register_oracle_node(env.clone(), oracle.clone(), registration).unwrap();
+    }
+    submit_reliable_xlm_price(&env, &oracle1, &oracle2, &oracle3);
+
+    let result = PriceOracle::get_twap(env.clone(), Symbol::new(&env, "XLM"), 300);
+    assert_eq!(result, Err(Symbol::new(&env, "insufficient_history")));
+}
+
+#[test]
+fn test_get_twap_rejects_when_ending_on_stale_data() {
+    let (env, admin, oracle1, oracle2) = create_test_env();
+    init_contract(&env, &admin).unwrap();
+
+    let oracle3 = Address::generate(&env);
+    for oracle in [&oracle1, &oracle2, &oracle3] {
+        let registration = create_test_registration(&env, oracle);
+        PriceOracle::register_oracle_node(env.clone(), oracle.clone(), registration).unwrap();
+    }
+
+    PriceOracle::submit_price(env.clone(), oracle1.clone(), create_test_price_update(&env, "XLM", 1000000)).unwrap();
+    PriceOracle::submit_price(env.clone(), oracle2.clone(), create_test_price_update(&env, "XLM", 1001000)).unwrap();
+    PriceOracle::submit_price(env.clone(), oracle3.clone(), create_test_price_update(&env, "XLM", 999000)).unwrap();
+
+    env.ledger().with_mut(|li| li.timestamp += 100);
+    PriceOracle::submit_price(env.clone(), oracle1.clone(), create_test_price_update(&env, "XLM", 1010000)).unwrap();
+    PriceOracle::submit_price(env.clone(), oracle2.clone(), create_test_price_update(&env, "XLM", 1011000)).unwrap();
+    PriceOracle::submit_price(env.clone(), oracle3.clone(), create_test_price_update(&env, "XLM", 1009000)).unwrap();
+
+    // Let the last history entry go stale relative to `now` without it falling outside the
+    // (much wider) TWAP window.
+    env.ledger().with_mut(|li| li.timestamp += PRICE_STALENESS_THRESHOLD + 1);
+
+    let result = PriceOracle::get_twap(env.clone(), Symbol::new(&env, "XLM"), 1000);
+    assert_eq!(result, Err(Symbol::new(&env, "stale_price")));
+}
+
+#[test]
+fn test_settle_round_rewards_submitters_who_track_the_final_price() {
+    let (env, admin, oracle1, oracle2) = create_test_env();
+    init_contract(&env, &admin).unwrap();
+
+    let oracle3 = Address::generate(&env);
+    for oracle in [&oracle1, &oracle2, &oracle3] {
+        let registration = create_test_registration(&env, oracle);
+        PriceOracle::register_oracle_node(env.clone(), oracle.clone(), registration).unwrap();
+    }
+
+    // All three submissions land within the default 1% reward band of the median.
+    submit_reliable_xlm_price(&env, &oracle1, &oracle2, &oracle3);
+
+    assert_eq!(PriceOracle::get_reward_balance(env.clone(), oracle1.clone()), 1);
+    assert_eq!(PriceOracle::get_reward_balance(env.clone(), oracle2.clone()), 1);
+    assert_eq!(PriceOracle::get_reward_balance(env.clone(), oracle3.clone()), 1);
+}
+
+#[test]
+fn test_claim_rewards_withdraws_and_resets_balance() {
+    let (env, admin, oracle1, oracle2) = create_test_env();
+    init_contract(&env, &admin).unwrap();
+
+    let oracle3 = Address::generate(&env);
+    for oracle in [&oracle1, &oracle2, &oracle3] {
+        let registration = create_test_registration(&env, oracle);
+        PriceOracle::register_oracle_node(env.clone(), oracle.clone(), registration).unwrap();
+    }
+    submit_reliable_xlm_price(&env, &oracle1, &oracle2, &oracle3);
+
+    let claimed = PriceOracle::claim_rewards(env.clone(), oracle1.clone()).unwrap();
+    assert_eq!(claimed, 1);
+    assert_eq!(PriceOracle::get_reward_balance(env.clone(), oracle1.clone()), 0);
+
+    let result = PriceOracle::claim_rewards(env.clone(), oracle1.clone());
+    assert_eq!(result, Err(Symbol::new(&env, "no_rewards_to_claim")));
+}
+
+#[test]
+fn test_node_auto_deactivated_after_consecutive_missed_rounds() {
+    let (env, admin, oracle1, oracle2) = create_test_env();
+    init_contract(&env, &admin).unwrap();
+
+    let oracle3 = Address::generate(&env);
+    let oracle4 = Address::generate(&env);
+    for oracle in [&oracle1, &oracle2, &oracle3, &oracle4] {
+        let registration = create_test_registration(&env, oracle);
+        PriceOracle::register_oracle_node(env.clone(), oracle.clone(), registration).unwrap();
+    }
+
+    // Tighten max_missed_rounds to 1 so oracle4 is deactivated the first time it sits out a
+    // round that the other three resolve without it.
+    PriceOracle::set_reward_config(env.clone(), admin.clone(), 100, 0, 1).unwrap();
+
+    submit_reliable_xlm_price(&env, &oracle1, &oracle2, &oracle3);
+
+    let oracle4_node = PriceOracle::get_oracle_node_info(env.clone(), oracle4.clone()).unwrap();
+    assert!(!oracle4_node.is_active);
+}
+
+#[test]
+fn test_aggregate_prices_median_even_count_averages_middle_two() {
+    let env = Env::default();
+    let asset = Symbol::new(&env, "XLM");
+    let mut reports = Vec::new(&env);
+    for price in [999000u64, 1000000, 1001000, 1010000] {
+        reports.push_back(PriceData::new(&env, asset.clone(), price, Address::generate(&env), 90, 0));
+    }
+
+    let result = PriceAggregator::aggregate_prices_median(&env, asset, reports).unwrap();
+    assert_eq!(result.price, (1000000 + 1001000) / 2);
+    assert_eq!(result.num_sources, 4);
+    assert_eq!(result.confidence, 90);
+}
+
+#[test]
+fn test_aggregate_prices_median_drops_stale_reports() {
+    let env = Env::default();
+    let asset = Symbol::new(&env, "XLM");
+    let mut reports = Vec::new(&env);
+    reports.push_back(PriceData::new(&env, asset.clone(), 1000000, Address::generate(&env), 90, 0));
+
+    env.ledger().with_mut(|li| {
+        li.timestamp += PRICE_STALENESS_THRESHOLD + 100;
+    });
+    for (price, confidence) in [(1001000u64, 85), (999000, 95), (1002000, 90)] {
+        reports.push_back(PriceData::new(&env, asset.clone(), price, Address::generate(&env), confidence, 0));
+    }
+
+    // The first report is now older than the staleness threshold and gets dropped, leaving
+    // exactly the three fresh ones.
+    let result = PriceAggregator::aggregate_prices_median(&env, asset, reports).unwrap();
+    assert_eq!(result.num_sources, 3);
+    assert_eq!(result.confidence, 85);
+}
+
+#[test]
+fn test_aggregate_prices_median_drops_low_confidence_reports() {
+    let env = Env::default();
+    let asset = Symbol::new(&env, "XLM");
+    let mut reports = Vec::new(&env);
+    for (price, confidence) in [(1000000u64, 90), (1001000, 85), (999000, 50), (1002000, 95)] {
+        reports.push_back(PriceData::new(&env, asset.clone(), price, Address::generate(&env), confidence, 0));
+    }
+
+    // The 50%-confidence report is below MIN_CONFIDENCE_LEVEL and gets dropped, leaving
+    // exactly the three reports at or above it.
+    let result = PriceAggregator::aggregate_prices_median(&env, asset, reports).unwrap();
+    assert_eq!(result.num_sources, 3);
+    assert_eq!(result.confidence, 85);
+}
+
+#[test]
+fn test_aggregate_prices_median_rejects_wide_spread() {
+    let env = Env::default();
+    let asset = Symbol::new(&env, "XLM");
+    let mut reports = Vec::new(&env);
+    for price in [1000000u64, 1000000, 20000000] {
+        reports.push_back(PriceData::new(&env, asset.clone(), price, Address::generate(&env), 90, 0));
+    }
+
+    let result = PriceAggregator::aggregate_prices_median(&env, asset, reports);
+    assert_eq!(result, Err(Symbol::new(&env, "price_deviation_too_high")));
+}
+
+#[test]
+fn test_aggregate_prices_median_rejects_insufficient_sources() {
+    let env = Env::default();
+    let asset = Symbol::new(&env, "XLM");
+    let mut reports = Vec::new(&env);
+    reports.push_back(PriceData::new(&env, asset.clone(), 1000000, Address::generate(&env), 90, 0));
+    reports.push_back(PriceData::new(&env, asset.clone(), 1001000, Address::generate(&env), 90, 0));
+
+    let result = PriceAggregator::aggregate_prices_median(&env, asset, reports);
+    assert_eq!(result, Err(Symbol::new(&env, "insufficient_sources")));
+}
+
+#[test]
+fn test_submit_relayed_prices_aggregates_and_publishes() {
+    let (env, admin, _, _) = create_test_env();
+    init_contract(&env, &admin).unwrap();
+
+    let asset = Symbol::new(&env, "XLM");
+    let mut reports = Vec::new(&env);
+    for price in [999000u64, 1000000, 1001000, 1010000] {
+        reports.push_back(PriceData::new(&env, asset.clone(), price, Address::generate(&env), 90, 0));
+    }
+
+    let result = PriceOracle::submit_relayed_prices(env.clone(), admin.clone(), asset.clone(), reports).unwrap();
+    assert_eq!(result.price, (1000000 + 1001000) / 2);
+
+    // The relayed aggregate is published exactly like a node-weighted one, so a plain
+    // `get_price` call picks it up.
+    let published = PriceOracle::get_price(env.clone(), asset).unwrap();
+    assert_eq!(published.price, result.price);
+}
+
+#[test]
+fn test_price_history_twap_weights_by_duration_between_samples() {
+    let env = Env::default();
+    let asset = Symbol::new(&env, "XLM");
+    let mut history = PriceHistory::new(&env, asset, 10);
+
+    env.ledger().with_mut(|li| { li.timestamp = 1000; });
+    history.record(&env, 100);
+
+    env.ledger().with_mut(|li| { li.timestamp = 1050; });
+    history.record(&env, 200);
+
+    // 50 seconds at 100, 50 seconds at 200 (up to `now` = 1100): (100*50 + 200*50) / 100 = 150.
+    let twap = history.twap(200, 1100).unwrap();
+    assert_eq!(twap, 150);
+}
+
+#[test]
+fn test_price_history_twap_requires_at_least_two_samples_in_window() {
+    let env = Env::default();
+    let asset = Symbol::new(&env, "XLM");
+    let mut history = PriceHistory::new(&env, asset, 10);
+
+    env.ledger().with_mut(|li| { li.timestamp = 1000; });
+    history.record(&env, 100);
+
+    assert_eq!(history.twap(200, 1100), None);
+}
+
+#[test]
+fn test_price_history_record_evicts_oldest_beyond_max_entries() {
+    let env = Env::default();
+    let asset = Symbol::new(&env, "XLM");
+    let mut history = PriceHistory::new(&env, asset, 2);
+
+    env.ledger().with_mut(|li| { li.timestamp = 1000; });
+    history.record(&env, 100);
+    env.ledger().with_mut(|li| { li.timestamp = 1050; });
+    history.record(&env, 200);
+    env.ledger().with_mut(|li| { li.timestamp = 1100; });
+    history.record(&env, 300);
+
+    assert_eq!(history.prices.len(), 2);
+    assert_eq!(history.prices.get(0).unwrap(), 200);
+    assert_eq!(history.prices.get(1).unwrap(), 300);
+}
+
+#[test]
+fn test_confidence_band_mismatch_excludes_submission_and_marks_inaccurate() {
+    let (env, admin, oracle1, oracle2) = create_test_env();
+    init_contract(&env, &admin).unwrap();
+
+    let oracle3 = Address::generate(&env);
+    let oracle4 = Address::generate(&env);
+    for oracle in [&oracle1, &oracle2, &oracle3, &oracle4] {
+        let registration = create_test_registration(&env, oracle);
+        PriceOracle::register_oracle_node(env.clone(), oracle.clone(), registration).unwrap();
+    }
+
+    // All four prices are close enough together that none is removed by the MAD outlier
+    // filter, so the band-overlap check is the only thing standing between oracle4 and the
+    // aggregate. Three survivors still clears MIN_AGGREGATION_SOURCES once oracle4 is dropped.
+    PriceOracle::submit_price(env.clone(), oracle1.clone(), create_test_price_update(&env, "XLM", 1000000)).unwrap();
+    PriceOracle::submit_price(env.clone(), oracle2.clone(), create_test_price_update(&env, "XLM", 1005000)).unwrap();
+    PriceOracle::submit_price(env.clone(), oracle3.clone(), create_test_price_update(&env, "XLM", 1010000)).unwrap();
+    // oracle4's claimed band is far tighter than the group's median, so it doesn't overlap
+    // it even though its price itself is unremarkable.
+    PriceOracle::submit_price(
+        env.clone(),
+        oracle4.clone(),
+        create_test_price_update_with_interval(&env, "XLM", 1020000, 100),
+    ).unwrap();
+
+    let result = PriceOracle::get_price(env.clone(), Symbol::new(&env, "XLM")).unwrap();
+    assert_eq!(result.num_sources, 3);
+
+    let oracle4_info = PriceOracle::get_oracle_node_info(env.clone(), oracle4.clone()).unwrap();
+    assert_eq!(oracle4_info.total_submissions, 1);
+    assert_eq!(oracle4_info.accurate_submissions, 0);
+
+    let oracle1_info = PriceOracle::get_oracle_node_info(env.clone(), oracle1.clone()).unwrap();
+    assert_eq!(oracle1_info.accurate_submissions, 1);
+}
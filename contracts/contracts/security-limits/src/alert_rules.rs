@@ -9,6 +9,7 @@ pub enum AlertType {
     UnknownAddress,
     VelocityAnomaly,
     LargeTransaction,
+    StructuringAnomaly,
 }
 
 #[contracttype]
@@ -34,6 +35,9 @@ pub struct Alert {
     pub transaction_hash: Option<Bytes>,
     pub message: String,
     pub is_resolved: bool,
+    /// Distinct low-value-recipient count behind a `StructuringAnomaly` alert; `None` for every
+    /// other alert type.
+    pub fanout_count: Option<u32>,
 }
 
 impl AlertRule {
@@ -96,6 +100,20 @@ impl AlertRule {
             description: String::from_str(&soroban_sdk::Env::default(), "Transaction to unknown address"),
         }
     }
+
+    /// `dust_threshold` is carried in `threshold_amount` and `max_fanout` in `max_transactions`,
+    /// the same slots `new_velocity_rule` reuses for its own window/count parameters.
+    pub fn new_structuring_rule(rule_id: u64, dust_threshold: i128, max_fanout: u32, time_window: u64) -> Self {
+        Self {
+            rule_id,
+            alert_type: AlertType::StructuringAnomaly,
+            is_enabled: true,
+            threshold_amount: Some(dust_threshold),
+            time_window_seconds: Some(time_window),
+            max_transactions: Some(max_fanout),
+            description: String::from_str(&soroban_sdk::Env::default(), "Structuring (smurfing) pattern detected"),
+        }
+    }
 }
 
 impl Alert {
@@ -117,6 +135,27 @@ impl Alert {
             transaction_hash,
             message,
             is_resolved: false,
+            fanout_count: None,
+        }
+    }
+
+    pub fn new_structuring(
+        alert_id: u64,
+        user: Address,
+        triggered_at: u64,
+        fanout_count: u32,
+        message: String,
+    ) -> Self {
+        Self {
+            alert_id,
+            user,
+            alert_type: AlertType::StructuringAnomaly,
+            triggered_at,
+            amount: 0,
+            transaction_hash: None,
+            message,
+            is_resolved: false,
+            fanout_count: Some(fanout_count),
         }
     }
 
@@ -125,33 +164,70 @@ impl Alert {
     }
 }
 
+fn alert_rule_index_key(env: &Env) -> String {
+    String::from_str(env, "alert_rule_index")
+}
+
+fn user_alert_index_key(env: &Env, user: &Address) -> (String, Address) {
+    (String::from_str(env, "user_alert_index"), user.clone())
+}
+
+fn load_alert_rule_index(env: &Env) -> Vec<u64> {
+    env.storage().persistent().get(&alert_rule_index_key(env)).unwrap_or(Vec::new(env))
+}
+
+fn load_user_alert_index(env: &Env, user: &Address) -> Vec<u64> {
+    env.storage().persistent().get(&user_alert_index_key(env, user)).unwrap_or(Vec::new(env))
+}
+
 pub fn store_alert_rule(env: &Env, rule: &AlertRule) {
     let key = rule.rule_id;
     env.storage().persistent().set(&key, rule);
+
+    let index_key = alert_rule_index_key(env);
+    let mut index = load_alert_rule_index(env);
+    if !index.contains(&rule.rule_id) {
+        index.push_back(rule.rule_id);
+        env.storage().persistent().set(&index_key, &index);
+    }
 }
 
 pub fn load_alert_rule(env: &Env, rule_id: u64) -> Option<AlertRule> {
     env.storage().persistent().get(&rule_id)
 }
 
-pub fn get_all_alert_rules(env: &Env) -> Vec<AlertRule> {
+/// Enabled alert rules, paged through `AlertRuleIndex` rather than a fixed id range, so rules
+/// created past whatever cap an earlier scan used are no longer invisible.
+pub fn get_all_alert_rules(env: &Env, offset: u32, limit: u32) -> Vec<AlertRule> {
+    let index = load_alert_rule_index(env);
     let mut rules = Vec::new(env);
-    
-    // Reduced range to prevent stack overflow - only check first 20 rules
-    for rule_id in 1..=20u64 {
-        if let Some(rule) = load_alert_rule(env, rule_id) {
+    let mut count = 0u32;
+
+    for i in offset..index.len() {
+        if count >= limit {
+            break;
+        }
+        if let Some(rule) = load_alert_rule(env, index.get(i).unwrap()) {
             if rule.is_enabled {
                 rules.push_back(rule);
+                count += 1;
             }
         }
     }
-    
+
     rules
 }
 
 pub fn store_alert(env: &Env, alert: &Alert) {
     let key = (alert.user.clone(), alert.alert_id);
     env.storage().persistent().set(&key, alert);
+
+    let index_key = user_alert_index_key(env, &alert.user);
+    let mut index = load_user_alert_index(env, &alert.user);
+    if !index.contains(&alert.alert_id) {
+        index.push_back(alert.alert_id);
+        env.storage().persistent().set(&index_key, &index);
+    }
 }
 
 pub fn load_alert(env: &Env, user: &Address, alert_id: u64) -> Option<Alert> {
@@ -159,37 +235,44 @@ pub fn load_alert(env: &Env, user: &Address, alert_id: u64) -> Option<Alert> {
     env.storage().persistent().get(&key)
 }
 
-pub fn get_user_alerts(env: &Env, user: &Address, limit: u32) -> Vec<Alert> {
+/// `user`'s alerts, paged through `UserAlertIndex` rather than a fixed id range, so alerts past
+/// whatever cap an earlier scan used are no longer invisible.
+pub fn get_user_alerts(env: &Env, user: &Address, offset: u32, limit: u32) -> Vec<Alert> {
+    let index = load_user_alert_index(env, user);
     let mut alerts = Vec::new(env);
     let mut count = 0u32;
-    
-    // Reduced range to prevent stack overflow - only check recent alerts
-    for alert_id in 1..=50u64 {
+
+    for i in offset..index.len() {
         if count >= limit {
             break;
         }
-        
-        if let Some(alert) = load_alert(env, user, alert_id) {
+        if let Some(alert) = load_alert(env, user, index.get(i).unwrap()) {
             alerts.push_back(alert);
             count += 1;
         }
     }
-    
+
     alerts
 }
 
-pub fn get_unresolved_alerts(env: &Env, user: &Address) -> Vec<Alert> {
+/// `user`'s unresolved alerts, paged the same way `get_user_alerts` is.
+pub fn get_unresolved_alerts(env: &Env, user: &Address, offset: u32, limit: u32) -> Vec<Alert> {
+    let index = load_user_alert_index(env, user);
     let mut unresolved = Vec::new(env);
-    
-    // Reduced range to prevent stack overflow - only check recent alerts
-    for alert_id in 1..=50u64 {
-        if let Some(alert) = load_alert(env, user, alert_id) {
+    let mut count = 0u32;
+
+    for i in offset..index.len() {
+        if count >= limit {
+            break;
+        }
+        if let Some(alert) = load_alert(env, user, index.get(i).unwrap()) {
             if !alert.is_resolved {
                 unresolved.push_back(alert);
+                count += 1;
             }
         }
     }
-    
+
     unresolved
 }
 
@@ -0,0 +1,23 @@
+use soroban_sdk::contracterror;
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum SecurityError {
+    // Spending limit errors
+    DailyLimitExceeded = 1,
+    MonthlyLimitExceeded = 2,
+    LimitsInactive = 3,
+    LimitsNotFound = 4,
+
+    // Recipient/state errors
+    RecipientBlacklisted = 5,
+    EmergencyStopActive = 6,
+    TooManySuspiciousAlerts = 7,
+    AccountBlocked = 8,
+    RecipientNotWhitelisted = 11,
+
+    // Authorization and input errors
+    Unauthorized = 9,
+    AmountOverflow = 10,
+}
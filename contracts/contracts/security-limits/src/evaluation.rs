@@ -0,0 +1,168 @@
+use crate::alert_rules::{self, Alert, AlertType};
+use soroban_sdk::{Address, Env, String, Vec};
+
+/// Entries an `evaluate_transaction` windowed check (daily/monthly cumulative spend, velocity)
+/// keeps per `(user, rule_id)`, pruned of anything older than the rule's own `time_window_seconds`
+/// on every call.
+fn window_key(user: &Address, rule_id: u64) -> (Address, u64) {
+    (user.clone(), rule_id)
+}
+
+fn load_window(env: &Env, user: &Address, rule_id: u64) -> Vec<(u64, i128)> {
+    env.storage().persistent().get(&window_key(user, rule_id)).unwrap_or(Vec::new(env))
+}
+
+fn store_window(env: &Env, user: &Address, rule_id: u64, window: &Vec<(u64, i128)>) {
+    env.storage().persistent().set(&window_key(user, rule_id), window);
+}
+
+/// Drops entries older than `now - window_secs` from `user`'s `rule_id` window, appends
+/// `(now, amount)`, and returns the sum (including the just-appended entry) of what's left.
+fn record_and_sum_window(env: &Env, user: &Address, rule_id: u64, window_secs: u64, now: u64, amount: i128) -> i128 {
+    let cutoff = now.saturating_sub(window_secs);
+    let mut fresh = Vec::new(env);
+    for (timestamp, entry_amount) in load_window(env, user, rule_id).iter() {
+        if timestamp >= cutoff {
+            fresh.push_back((timestamp, entry_amount));
+        }
+    }
+    fresh.push_back((now, amount));
+
+    let sum = fresh.iter().fold(0i128, |total, (_, entry_amount)| total + entry_amount);
+    store_window(env, user, rule_id, &fresh);
+    sum
+}
+
+/// Per-user allowlist `evaluate_transaction`'s `UnknownAddress` check tests `to` against —
+/// distinct from the `security`/`registry` modules' global whitelist, since here it's each
+/// user's own list of addresses they've sent to before or approved.
+fn allowlist_key(env: &Env, user: &Address) -> (String, Address) {
+    (String::from_str(env, "allowlist"), user.clone())
+}
+
+pub fn is_address_allowed(env: &Env, user: &Address, address: &Address) -> bool {
+    let allowlist: Vec<Address> = env.storage().persistent().get(&allowlist_key(env, user)).unwrap_or(Vec::new(env));
+    allowlist.contains(address)
+}
+
+pub fn allow_address(env: &Env, user: &Address, address: &Address) {
+    let key = allowlist_key(env, user);
+    let mut allowlist: Vec<Address> = env.storage().persistent().get(&key).unwrap_or(Vec::new(env));
+    if !allowlist.contains(address) {
+        allowlist.push_back(address.clone());
+        env.storage().persistent().set(&key, &allowlist);
+    }
+}
+
+fn record_alert(env: &Env, user: &Address, alert_type: AlertType, now: u64, amount: i128, message: &str) -> Alert {
+    let alert_id = alert_rules::generate_alert_id(env);
+    let alert = Alert::new(alert_id, user.clone(), alert_type, now, amount, None, String::from_str(env, message));
+    alert_rules::store_alert(env, &alert);
+    alert
+}
+
+/// Walks every enabled `AlertRule` and actually acts on the thresholds `create_alert_rule`
+/// configured for it, recording an `Alert` (via `generate_alert_id`/`store_alert`) for each one
+/// `amount`/`to` breaches, and returns every `Alert` this call just triggered:
+///
+/// - `LargeTransaction`: `amount` against the rule's `threshold_amount`.
+/// - `DailyLimitExceeded`/`MonthlyLimitExceeded`: `amount` summed over the rule's own
+///   `time_window_seconds` window against `threshold_amount`.
+/// - `UnknownAddress`: `to` against `user`'s own allowlist (see `allow_address`).
+/// - `VelocityAnomaly`: transaction count over `time_window_seconds` against `max_transactions`.
+///
+/// Unlike `verify_transaction_checked`, this never blocks the transfer — it's meant for a
+/// caller that wants the alerts surfaced immediately without the transaction itself being
+/// gated on them.
+pub fn evaluate_transaction(env: &Env, user: &Address, amount: i128, to: &Address, now: u64) -> Vec<Alert> {
+    let mut triggered = Vec::new(env);
+
+    for rule in alert_rules::get_all_alert_rules(env, 0, u32::MAX).iter() {
+        if !rule.is_enabled {
+            continue;
+        }
+
+        match rule.alert_type {
+            AlertType::LargeTransaction => {
+                if let Some(threshold) = rule.threshold_amount {
+                    if amount > threshold {
+                        triggered.push_back(record_alert(
+                            env,
+                            user,
+                            AlertType::LargeTransaction,
+                            now,
+                            amount,
+                            "Large transaction amount detected",
+                        ));
+                    }
+                }
+            }
+            AlertType::DailyLimitExceeded | AlertType::MonthlyLimitExceeded => {
+                if let (Some(threshold), Some(window_secs)) = (rule.threshold_amount, rule.time_window_seconds) {
+                    let sum = record_and_sum_window(env, user, rule.rule_id, window_secs, now, amount);
+                    if sum > threshold {
+                        let message = if rule.alert_type == AlertType::DailyLimitExceeded {
+                            "Daily spending limit exceeded"
+                        } else {
+                            "Monthly spending limit exceeded"
+                        };
+                        triggered.push_back(record_alert(env, user, rule.alert_type.clone(), now, sum, message));
+                    }
+                }
+            }
+            AlertType::UnknownAddress => {
+                if !is_address_allowed(env, user, to) {
+                    triggered.push_back(record_alert(
+                        env,
+                        user,
+                        AlertType::UnknownAddress,
+                        now,
+                        amount,
+                        "Transaction to an address outside the user's allowlist",
+                    ));
+                }
+            }
+            AlertType::VelocityAnomaly => {
+                if let (Some(max_transactions), Some(window_secs)) = (rule.max_transactions, rule.time_window_seconds) {
+                    let cutoff = now.saturating_sub(window_secs);
+                    let mut recent: Vec<u64> = env
+                        .storage()
+                        .persistent()
+                        .get(&window_key(user, rule.rule_id))
+                        .unwrap_or(Vec::new(env));
+                    // Reuses `(user, rule_id)`-keyed storage, just with `i128` dropped since a
+                    // velocity rule only ever cares about the count.
+                    let mut fresh: Vec<u64> = Vec::new(env);
+                    for timestamp in recent.iter() {
+                        if timestamp >= cutoff {
+                            fresh.push_back(timestamp);
+                        }
+                    }
+                    recent = fresh;
+
+                    if recent.len() + 1 > max_transactions {
+                        triggered.push_back(record_alert(
+                            env,
+                            user,
+                            AlertType::VelocityAnomaly,
+                            now,
+                            amount,
+                            "Unusually high transaction velocity",
+                        ));
+                    } else {
+                        recent.push_back(now);
+                        env.storage().persistent().set(&window_key(user, rule.rule_id), &recent);
+                    }
+                }
+            }
+            AlertType::StructuringAnomaly | AlertType::SuspiciousActivity => {
+                // Not driven by `evaluate_transaction`: `StructuringAnomaly` needs the
+                // recipient fan-out tracking `velocity::TxRingBuffer` already does in
+                // `project_security_effects`, and `SuspiciousActivity` is derived from the
+                // risk score rather than a rule threshold.
+            }
+        }
+    }
+
+    triggered
+}
@@ -2,13 +2,21 @@
 
 use soroban_sdk::{contract, contractimpl, Address, Env, String, Vec, Bytes};
 
+mod error;
 mod user_limits;
 mod alert_rules;
 mod security;
+mod substate;
+mod velocity;
+mod registry;
+mod evaluation;
 
-use user_limits::{UserLimits, TransactionAttempt, store_user_limits, load_user_limits, store_transaction_attempt};
+pub use error::SecurityError;
+
+use user_limits::{UserLimits, TransactionAttempt, store_user_limits, load_user_limits, load_spending_record, remove_spending_record};
 use alert_rules::{AlertRule, Alert, AlertType, store_alert_rule, load_alert_rule, store_alert, get_user_alerts, get_unresolved_alerts};
-use security::{SecurityStatus, SecurityMetrics, detect_suspicious_activity, get_security_status, is_address_whitelisted, is_address_blacklisted, add_to_whitelist, add_to_blacklist, remove_from_whitelist, remove_from_blacklist, is_emergency_stop_active, activate_emergency_stop, deactivate_emergency_stop, reset_user_risk_score};
+use security::{SecurityStatus, SecurityMetrics, project_security_effects, get_security_status, is_address_whitelisted, is_address_blacklisted, add_to_whitelist, add_to_blacklist, remove_from_whitelist, remove_from_blacklist, is_emergency_stop_active, activate_emergency_stop, deactivate_emergency_stop, reset_user_risk_score};
+use substate::{BatchMode, SecuritySubstate, TransferRequest};
 
 #[contract]
 pub struct SecurityContract;
@@ -39,7 +47,8 @@ impl SecurityContract {
         load_user_limits(&env, &user)
     }
 
-    /// Verify if a transaction is within limits and not suspicious
+    /// Verify if a transaction is within limits and not suspicious. Thin bool wrapper over
+    /// `verify_transaction_checked` kept for callers that only care about pass/fail.
     pub fn verify_transaction(
         env: Env,
         user: Address,
@@ -47,59 +56,235 @@ impl SecurityContract {
         amount: i128,
         transaction_hash: Bytes,
     ) -> bool {
+        Self::verify_transaction_checked(env, user, recipient, amount, transaction_hash).is_ok()
+    }
+
+    /// Verify if a transaction is within limits and not suspicious, returning the specific
+    /// `SecurityError` a caller was blocked by instead of collapsing every reason into
+    /// `false`. Every gate is checked against a projected `SecuritySubstate` before anything
+    /// is written, so a transaction that fails any gate leaves no trace in persisted state.
+    pub fn verify_transaction_checked(
+        env: Env,
+        user: Address,
+        recipient: Address,
+        amount: i128,
+        transaction_hash: Bytes,
+    ) -> Result<(), SecurityError> {
         user.require_auth();
-        
+
         if is_emergency_stop_active(&env) {
-            return false;
+            return Err(SecurityError::EmergencyStopActive);
         }
-        
-        // Check if recipient is blacklisted
-        if is_address_blacklisted(&env, &recipient) {
-            return false;
+
+        // Check if recipient is blacklisted, locally or via the configured screening registry
+        if registry::is_blacklisted(&env, &recipient) {
+            return Err(SecurityError::RecipientBlacklisted);
         }
-        
+
+        // Deny-by-default mode: reject any recipient the registry hasn't explicitly whitelisted
+        if registry::is_refuse_unscreened(&env) && !registry::is_whitelisted(&env, &recipient) {
+            return Err(SecurityError::RecipientNotWhitelisted);
+        }
+
         let current_time = env.ledger().timestamp();
-        
+
         // Load and update user limits
         let mut limits = load_user_limits(&env, &user).unwrap_or_default();
         limits.reset_daily_if_needed(current_time);
         limits.reset_monthly_if_needed(current_time);
-        
-        // Check if transaction is within limits
-        if !limits.can_spend(amount) {
-            return false;
+
+        if !limits.is_active {
+            return Err(SecurityError::LimitsInactive);
         }
-        
+
+        let new_daily_spent = limits
+            .daily_spent
+            .checked_add(amount)
+            .ok_or(SecurityError::AmountOverflow)?;
+        let new_monthly_spent = limits
+            .monthly_spent
+            .checked_add(amount)
+            .ok_or(SecurityError::AmountOverflow)?;
+
+        if new_daily_spent > limits.daily_limit {
+            return Err(SecurityError::DailyLimitExceeded);
+        }
+        if new_monthly_spent > limits.monthly_limit {
+            return Err(SecurityError::MonthlyLimitExceeded);
+        }
+
         // Check security status
         let security_status = get_security_status(&env, &user);
         if matches!(security_status, SecurityStatus::Blocked) {
-            return false;
+            return Err(SecurityError::AccountBlocked);
         }
-        
-        // Detect suspicious activity and generate alerts
-        let alerts = detect_suspicious_activity(&env, &user, &recipient, amount, current_time);
-        for alert in alerts.iter() {
-            store_alert(&env, &alert);
-        }
-        
-        // If too many suspicious alerts, block transaction
+
+        // Project the alerts and risk-score change this transaction would cause, without
+        // touching storage.
+        let (alerts, risk_score_delta) = project_security_effects(&env, &user, &recipient, amount, current_time);
+
+        // If too many suspicious alerts, block the transaction before anything is committed.
         if alerts.len() > 2 {
-            return false;
+            return Err(SecurityError::TooManySuspiciousAlerts);
         }
-        
-        // Update spending limits
-        limits.add_spending(amount);
-        store_user_limits(&env, &user, &limits);
-        
-        // Store transaction attempt for audit trail
-        let attempt = TransactionAttempt {
+
+        let mut touched_recipients = Vec::new(&env);
+        touched_recipients.push_back(recipient.clone());
+        let mut attempts = Vec::new(&env);
+        attempts.push_back(TransactionAttempt {
             user: user.clone(),
             amount,
             timestamp: current_time,
             transaction_hash,
+        });
+        let substate = SecuritySubstate {
+            daily_spent_delta: amount,
+            monthly_spent_delta: amount,
+            risk_score_delta,
+            alerts,
+            touched_recipients,
+            attempts,
         };
-        store_transaction_attempt(&env, &attempt);
-        
+
+        // Every gate passed against the projected state: commit it all in one pass.
+        substate.commit(&env, &user, &mut limits);
+        store_user_limits(&env, &user, &limits);
+
+        Ok(())
+    }
+
+    /// Verifies a batch of transfers for `user` against one cumulative projected state, so the
+    /// i-th transfer is checked against `daily_spent`/`monthly_spent` plus every prior transfer
+    /// in the batch rather than stale pre-batch totals. `BatchMode::AllOrNothing` persists
+    /// nothing if any transfer fails a gate; `BatchMode::BestEffort` commits whichever subset
+    /// passed and reports a result per transfer.
+    pub fn verify_transaction_batch(
+        env: Env,
+        user: Address,
+        transfers: Vec<TransferRequest>,
+        mode: BatchMode,
+    ) -> Vec<bool> {
+        user.require_auth();
+
+        let mut results = Vec::new(&env);
+
+        if is_emergency_stop_active(&env) {
+            for _ in transfers.iter() {
+                results.push_back(false);
+            }
+            return results;
+        }
+
+        let current_time = env.ledger().timestamp();
+        let mut limits = load_user_limits(&env, &user).unwrap_or_default();
+        limits.reset_daily_if_needed(current_time);
+        limits.reset_monthly_if_needed(current_time);
+
+        let security_status = get_security_status(&env, &user);
+        if matches!(security_status, SecurityStatus::Blocked) {
+            for _ in transfers.iter() {
+                results.push_back(false);
+            }
+            return results;
+        }
+
+        let mut batch = SecuritySubstate::new(&env);
+        // Running cumulative totals: the i-th transfer is checked against
+        // daily_spent + sum(prior accepted amounts), not the pre-batch totals.
+        let mut projected_daily_spent = limits.daily_spent;
+        let mut projected_monthly_spent = limits.monthly_spent;
+        let mut any_rejected = false;
+
+        for transfer in transfers.iter() {
+            // `checked_add`, same as `verify_transaction_checked`: a transfer whose amount
+            // would overflow the running total is rejected like any other failed gate rather
+            // than trapping the whole batch.
+            let new_daily_spent = projected_daily_spent.checked_add(transfer.amount);
+            let new_monthly_spent = projected_monthly_spent.checked_add(transfer.amount);
+
+            let within_limits = limits.is_active
+                && new_daily_spent.map_or(false, |v| v <= limits.daily_limit)
+                && new_monthly_spent.map_or(false, |v| v <= limits.monthly_limit);
+            let blacklisted = registry::is_blacklisted(&env, &transfer.recipient);
+            let unscreened = registry::is_refuse_unscreened(&env)
+                && !registry::is_whitelisted(&env, &transfer.recipient);
+
+            let (alerts, risk_score_delta) =
+                project_security_effects(&env, &user, &transfer.recipient, transfer.amount, current_time);
+            let ok = within_limits && !blacklisted && !unscreened && alerts.len() <= 2;
+
+            if ok {
+                projected_daily_spent = new_daily_spent.unwrap();
+                projected_monthly_spent = new_monthly_spent.unwrap();
+
+                let mut touched_recipients = Vec::new(&env);
+                touched_recipients.push_back(transfer.recipient.clone());
+                let mut attempts = Vec::new(&env);
+                attempts.push_back(TransactionAttempt {
+                    user: user.clone(),
+                    amount: transfer.amount,
+                    timestamp: current_time,
+                    transaction_hash: transfer.transaction_hash.clone(),
+                });
+                batch.accrue(SecuritySubstate {
+                    daily_spent_delta: transfer.amount,
+                    monthly_spent_delta: transfer.amount,
+                    risk_score_delta,
+                    alerts,
+                    touched_recipients,
+                    attempts,
+                });
+            } else {
+                any_rejected = true;
+            }
+
+            results.push_back(ok);
+        }
+
+        if matches!(mode, BatchMode::AllOrNothing) && any_rejected {
+            let mut rejected = Vec::new(&env);
+            for _ in transfers.iter() {
+                rejected.push_back(false);
+            }
+            return rejected;
+        }
+
+        if batch.touched_recipients.is_empty() {
+            return results;
+        }
+
+        batch.commit(&env, &user, &mut limits);
+        store_user_limits(&env, &user, &limits);
+
+        results
+    }
+
+    /// Reclaims the daily/monthly allowance `verify_transaction` counted for `transaction_hash`,
+    /// for transactions that passed verification but later failed on submission. Net rather than
+    /// gross metering: the recorded amount is subtracted with saturating arithmetic and the
+    /// record is deleted, so a reversal can never be double-applied. If `last_daily_reset` (or
+    /// `last_monthly_reset`) has advanced past the reset the spending was counted against, that
+    /// window is no longer current and is left untouched.
+    pub fn reverse_transaction(env: Env, user: Address, transaction_hash: Bytes) -> bool {
+        user.require_auth();
+
+        let record = match load_spending_record(&env, &user, &transaction_hash) {
+            Some(record) => record,
+            None => return false,
+        };
+
+        let mut limits = match load_user_limits(&env, &user) {
+            Some(limits) => limits,
+            None => return false,
+        };
+
+        let refund_daily = limits.last_daily_reset <= record.daily_reset_at;
+        let refund_monthly = limits.last_monthly_reset <= record.monthly_reset_at;
+        limits.reverse_spending(record.amount, refund_daily, refund_monthly);
+
+        store_user_limits(&env, &user, &limits);
+        remove_spending_record(&env, &user, &transaction_hash);
+
         true
     }
 
@@ -179,13 +364,41 @@ impl SecurityContract {
     }
 
     /// Get user's alerts
-    pub fn get_alerts(env: Env, user: Address, limit: u32) -> Vec<Alert> {
-        get_user_alerts(&env, &user, limit)
+    pub fn get_alerts(env: Env, user: Address, offset: u32, limit: u32) -> Vec<Alert> {
+        get_user_alerts(&env, &user, offset, limit)
     }
 
     /// Get user's unresolved alerts
-    pub fn get_unresolved_alerts(env: Env, user: Address) -> Vec<Alert> {
-        get_unresolved_alerts(&env, &user)
+    pub fn get_unresolved_alerts(env: Env, user: Address, offset: u32, limit: u32) -> Vec<Alert> {
+        get_unresolved_alerts(&env, &user, offset, limit)
+    }
+
+    /// Get enabled alert rules
+    pub fn get_alert_rules(env: Env, offset: u32, limit: u32) -> Vec<AlertRule> {
+        alert_rules::get_all_alert_rules(&env, offset, limit)
+    }
+
+    /// Evaluates `amount`/`to` against every enabled `AlertRule`'s own configured threshold —
+    /// large-transaction, windowed daily/monthly spend, the sender's recipient allowlist, and
+    /// transaction velocity — recording and returning whatever `Alert`s it triggers. Unlike
+    /// `verify_transaction`, this never blocks the transfer; it's for a caller that wants the
+    /// alerts surfaced immediately without gating on them.
+    pub fn evaluate_transaction(env: Env, user: Address, amount: i128, to: Address, now: u64) -> Vec<Alert> {
+        user.require_auth();
+        evaluation::evaluate_transaction(&env, &user, amount, &to, now)
+    }
+
+    /// Adds `address` to `user`'s own allowlist, the list `evaluate_transaction`'s
+    /// `UnknownAddress` check tests a recipient against.
+    pub fn allow_address(env: Env, user: Address, address: Address) -> bool {
+        user.require_auth();
+        evaluation::allow_address(&env, &user, &address);
+        true
+    }
+
+    /// Whether `address` is on `user`'s own allowlist.
+    pub fn is_address_allowed_for_user(env: Env, user: Address, address: Address) -> bool {
+        evaluation::is_address_allowed(&env, &user, &address)
     }
 
     /// Resolve an alert
@@ -250,6 +463,34 @@ impl SecurityContract {
         is_emergency_stop_active(&env)
     }
 
+    /// Configure an external `AddressRegistryClient` contract for recipient screening, letting
+    /// several `SecurityContract` instances share one whitelist/blacklist instead of each
+    /// re-maintaining its own (admin only). The registry is unioned with the local lists.
+    pub fn set_screening_registry(env: Env, admin: Address, registry: Address) -> bool {
+        admin.require_auth();
+        registry::set_registry(&env, &registry);
+        true
+    }
+
+    /// Get the configured screening registry contract, if any.
+    pub fn get_screening_registry(env: Env) -> Option<Address> {
+        registry::get_registry(&env)
+    }
+
+    /// Toggle deny-by-default screening (admin only): when enabled, `verify_transaction` rejects
+    /// any recipient the registry hasn't explicitly whitelisted, for high-assurance deployments
+    /// that only want to send to known-good addresses.
+    pub fn set_refuse_unscreened(env: Env, admin: Address, enabled: bool) -> bool {
+        admin.require_auth();
+        registry::set_refuse_unscreened(&env, enabled);
+        true
+    }
+
+    /// Check whether deny-by-default screening is enabled.
+    pub fn is_refuse_unscreened(env: Env) -> bool {
+        registry::is_refuse_unscreened(&env)
+    }
+
     /// Update user limits (user can update their own limits)
     pub fn update_user_limits(
         env: Env,
@@ -0,0 +1,54 @@
+use soroban_sdk::{contractclient, Address, Env, String};
+
+/// Interface an external address-screening registry contract must implement so several
+/// `SecurityContract` instances can share one screening list instead of each re-maintaining its
+/// own whitelist/blacklist.
+#[contractclient(name = "AddressRegistryClient")]
+pub trait AddressRegistryInterface {
+    fn is_blacklisted(env: Env, address: Address) -> bool;
+    fn is_whitelisted(env: Env, address: Address) -> bool;
+}
+
+pub fn set_registry(env: &Env, registry: &Address) {
+    let key = String::from_str(env, "screening_registry");
+    env.storage().persistent().set(&key, registry);
+}
+
+pub fn get_registry(env: &Env) -> Option<Address> {
+    let key = String::from_str(env, "screening_registry");
+    env.storage().persistent().get(&key)
+}
+
+pub fn set_refuse_unscreened(env: &Env, enabled: bool) {
+    let key = String::from_str(env, "refuse_unscreened");
+    env.storage().persistent().set(&key, &enabled);
+}
+
+pub fn is_refuse_unscreened(env: &Env) -> bool {
+    let key = String::from_str(env, "refuse_unscreened");
+    env.storage().persistent().get(&key).unwrap_or(false)
+}
+
+/// True if `address` is blacklisted locally or, when a screening registry is configured, by
+/// that registry too.
+pub fn is_blacklisted(env: &Env, address: &Address) -> bool {
+    if crate::security::is_address_blacklisted(env, address) {
+        return true;
+    }
+    match get_registry(env) {
+        Some(registry) => AddressRegistryClient::new(env, &registry).is_blacklisted(address),
+        None => false,
+    }
+}
+
+/// True if `address` is whitelisted locally or, when a screening registry is configured, by
+/// that registry too.
+pub fn is_whitelisted(env: &Env, address: &Address) -> bool {
+    if crate::security::is_address_whitelisted(env, address) {
+        return true;
+    }
+    match get_registry(env) {
+        Some(registry) => AddressRegistryClient::new(env, &registry).is_whitelisted(address),
+        None => false,
+    }
+}
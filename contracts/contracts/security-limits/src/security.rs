@@ -1,6 +1,7 @@
 use soroban_sdk::{contracttype, Address, Env, String, Vec};
-use crate::alert_rules::{Alert, AlertType, generate_alert_id};
+use crate::alert_rules::{get_all_alert_rules, Alert, AlertType};
 use crate::user_limits::TransactionAttempt;
+use crate::velocity;
 
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -137,20 +138,23 @@ pub fn remove_from_blacklist(env: &Env, address: &Address) {
     env.storage().persistent().remove(&key);
 }
 
-pub fn detect_suspicious_activity(
-    env: &Env, 
-    user: &Address, 
-    recipient: &Address, 
+/// Projects the alerts `amount` would trigger for `user`/`recipient` and the signed change it
+/// would make to `user`'s risk score, without writing anything to storage. Alerts carry a
+/// placeholder id of `0`; `SecuritySubstate::commit` assigns the real ids at commit time so an
+/// id is never burned on a transaction that ends up being rejected.
+pub fn project_security_effects(
+    env: &Env,
+    user: &Address,
+    recipient: &Address,
     amount: i128,
-    current_time: u64
-) -> Vec<Alert> {
+    current_time: u64,
+) -> (Vec<Alert>, i32) {
     let mut alerts = Vec::new(env);
-    
-    // Check if recipient is blacklisted
-    if is_address_blacklisted(env, recipient) {
-        let alert_id = generate_alert_id(env);
+
+    // Check if recipient is blacklisted, locally or via the configured screening registry
+    if crate::registry::is_blacklisted(env, recipient) {
         let alert = Alert::new(
-            alert_id,
+            0,
             user.clone(),
             AlertType::UnknownAddress,
             current_time,
@@ -160,37 +164,78 @@ pub fn detect_suspicious_activity(
         );
         alerts.push_back(alert);
     }
-    
-    // Get user security metrics
-    let metrics_key = user.clone();
-    let mut metrics: SecurityMetrics = env.storage()
-        .persistent()
-        .get(&metrics_key)
+
+    // Project what this transaction would do to the user's security metrics, without
+    // persisting either the metrics or the alerts it implies.
+    let previous_score = load_security_metrics(env, user)
+        .map(|m| m.risk_score)
+        .unwrap_or(0);
+
+    let mut projected = load_security_metrics(env, user)
         .unwrap_or_else(|| SecurityMetrics::new(user.clone(), current_time));
-    
-    // Update metrics with current transaction
-    metrics.update_with_transaction(amount, recipient, current_time);
-    
-    // Check for velocity anomalies
-    if metrics.transaction_count_24h > 100 {
-        let alert_id = generate_alert_id(env);
-        let alert = Alert::new(
-            alert_id,
-            user.clone(),
-            AlertType::VelocityAnomaly,
-            current_time,
-            amount,
-            None,
-            String::from_str(env, "Unusually high transaction velocity"),
-        );
-        alerts.push_back(alert);
+    projected.update_with_transaction(amount, recipient, current_time);
+
+    // Check for velocity anomalies against the configured VelocityAnomaly rule, counting this
+    // transaction plus every recent one still inside the rule's window via the ring buffer
+    // (bounded O(capacity) scan, no per-second storage probing).
+    for rule in get_all_alert_rules(env, 0, u32::MAX).iter() {
+        if rule.alert_type != AlertType::VelocityAnomaly {
+            continue;
+        }
+        if let (Some(max_transactions), Some(window_secs)) = (rule.max_transactions, rule.time_window_seconds) {
+            let recent_count = velocity::count_in_window(env, user, window_secs, current_time) + 1;
+            if recent_count > max_transactions {
+                let alert = Alert::new(
+                    0,
+                    user.clone(),
+                    AlertType::VelocityAnomaly,
+                    current_time,
+                    amount,
+                    None,
+                    String::from_str(env, "Unusually high transaction velocity"),
+                );
+                alerts.push_back(alert);
+            }
+        }
+        break;
     }
-    
+
+    // Check for structuring/smurfing: many distinct low-value recipients within one window is a
+    // sign of evading limits by fanning a transfer out across fresh addresses instead of sending
+    // it as one large transaction.
+    for rule in get_all_alert_rules(env, 0, u32::MAX).iter() {
+        if rule.alert_type != AlertType::StructuringAnomaly {
+            continue;
+        }
+        if let (Some(dust_threshold), Some(max_fanout), Some(window_secs)) =
+            (rule.threshold_amount, rule.max_transactions, rule.time_window_seconds)
+        {
+            let fanout_count = velocity::count_distinct_low_value_recipients_in_window(
+                env,
+                user,
+                dust_threshold,
+                window_secs,
+                current_time,
+                Some((amount, recipient.clone())),
+            );
+            if fanout_count > max_fanout {
+                let alert = Alert::new_structuring(
+                    0,
+                    user.clone(),
+                    current_time,
+                    fanout_count,
+                    String::from_str(env, "Structuring (smurfing) pattern detected"),
+                );
+                alerts.push_back(alert);
+            }
+        }
+        break;
+    }
+
     // Check for large transactions
     if amount > 50000_0000000 { // > 50k XLM
-        let alert_id = generate_alert_id(env);
         let alert = Alert::new(
-            alert_id,
+            0,
             user.clone(),
             AlertType::LargeTransaction,
             current_time,
@@ -200,12 +245,11 @@ pub fn detect_suspicious_activity(
         );
         alerts.push_back(alert);
     }
-    
+
     // Check high risk score
-    if metrics.risk_score > 70 {
-        let alert_id = generate_alert_id(env);
+    if projected.risk_score > 70 {
         let alert = Alert::new(
-            alert_id,
+            0,
             user.clone(),
             AlertType::SuspiciousActivity,
             current_time,
@@ -215,11 +259,26 @@ pub fn detect_suspicious_activity(
         );
         alerts.push_back(alert);
     }
-    
-    // Store updated metrics
-    env.storage().persistent().set(&metrics_key, &metrics);
-    
-    alerts
+
+    (alerts, projected.risk_score as i32 - previous_score as i32)
+}
+
+/// Re-applies the transaction `project_security_effects` already projected to `user`'s
+/// persisted security metrics. Called only from `SecuritySubstate::commit`, once every
+/// validation gate has passed, so the metrics a later projection reads back stay in sync with
+/// the alerts and spending that were actually committed.
+pub fn record_transaction_metrics(
+    env: &Env,
+    user: &Address,
+    recipient: &Address,
+    amount: i128,
+    current_time: u64,
+) {
+    let mut metrics = load_security_metrics(env, user)
+        .unwrap_or_else(|| SecurityMetrics::new(user.clone(), current_time));
+    metrics.update_with_transaction(amount, recipient, current_time);
+    store_security_metrics(env, &metrics);
+    velocity::record_transaction(env, user, current_time, amount, recipient.clone());
 }
 
 pub fn get_security_status(env: &Env, user: &Address) -> SecurityStatus {
@@ -0,0 +1,102 @@
+use soroban_sdk::{contracttype, Address, Bytes, Env, Vec};
+use crate::alert_rules::{generate_alert_id, store_alert, Alert};
+use crate::security::record_transaction_metrics;
+use crate::user_limits::{store_spending_record, store_transaction_attempt, SpendingRecord, TransactionAttempt, UserLimits};
+
+/// One transfer in a `verify_transaction_batch` call.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TransferRequest {
+    pub recipient: Address,
+    pub amount: i128,
+    pub transaction_hash: Bytes,
+}
+
+/// Selects how `verify_transaction_batch` treats a transfer that fails a gate.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum BatchMode {
+    AllOrNothing, // Any failing transfer rejects the whole batch; nothing is persisted
+    BestEffort, // Failing transfers are skipped; the accepted subset is committed
+}
+
+/// Accumulates the pending effects of one or more checks — spending deltas, alerts, the
+/// risk-score change, the recipients touched, and the resulting audit-trail attempts —
+/// without writing to storage, so a caller can run every validation gate against the
+/// *projected* state and only make it durable once every gate has passed.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SecuritySubstate {
+    pub daily_spent_delta: i128,
+    pub monthly_spent_delta: i128,
+    pub risk_score_delta: i32,
+    pub alerts: Vec<Alert>,
+    pub touched_recipients: Vec<Address>,
+    pub attempts: Vec<TransactionAttempt>,
+}
+
+impl SecuritySubstate {
+    pub fn new(env: &Env) -> Self {
+        Self {
+            daily_spent_delta: 0,
+            monthly_spent_delta: 0,
+            risk_score_delta: 0,
+            alerts: Vec::new(env),
+            touched_recipients: Vec::new(env),
+            attempts: Vec::new(env),
+        }
+    }
+
+    /// Merges `other`'s pending effects into `self`: deltas are summed, alerts, touched
+    /// recipients and attempts are appended. Lets a batch or nested check project several
+    /// transactions and commit them together instead of one at a time.
+    pub fn accrue(&mut self, other: SecuritySubstate) {
+        self.daily_spent_delta += other.daily_spent_delta;
+        self.monthly_spent_delta += other.monthly_spent_delta;
+        self.risk_score_delta += other.risk_score_delta;
+        for alert in other.alerts.iter() {
+            self.alerts.push_back(alert);
+        }
+        for recipient in other.touched_recipients.iter() {
+            self.touched_recipients.push_back(recipient);
+        }
+        for attempt in other.attempts.iter() {
+            self.attempts.push_back(attempt);
+        }
+    }
+
+    /// Applies every accumulated effect in one pass: advances `user`'s spent totals, assigns
+    /// real ids to and persists the pending alerts, re-applies each attempt's transaction to
+    /// `user`'s security metrics (`touched_recipients` and `attempts` are paired by index),
+    /// stores every audit-trail attempt, and records the counted amount behind each one so
+    /// `reverse_transaction` can reclaim it later. Only ever called once every validation gate
+    /// has already passed against the projected state, so nothing here can leave storage
+    /// half-updated.
+    pub fn commit(self, env: &Env, user: &Address, limits: &mut UserLimits) {
+        limits.add_spending(self.daily_spent_delta);
+
+        for i in 0..self.alerts.len() {
+            let mut alert = self.alerts.get(i).unwrap();
+            alert.alert_id = generate_alert_id(env);
+            store_alert(env, &alert);
+        }
+
+        for i in 0..self.touched_recipients.len() {
+            let recipient = self.touched_recipients.get(i).unwrap();
+            let attempt = self.attempts.get(i).unwrap();
+            record_transaction_metrics(env, user, &recipient, attempt.amount, attempt.timestamp);
+        }
+
+        for i in 0..self.attempts.len() {
+            let attempt = self.attempts.get(i).unwrap();
+            store_transaction_attempt(env, &attempt);
+
+            let record = SpendingRecord {
+                amount: attempt.amount,
+                daily_reset_at: limits.last_daily_reset,
+                monthly_reset_at: limits.last_monthly_reset,
+            };
+            store_spending_record(env, user, &attempt.transaction_hash, &record);
+        }
+    }
+}
@@ -1,7 +1,35 @@
 #![cfg(test)]
 
 use super::*;
-use soroban_sdk::{testutils::{Address as _, Ledger}, Env};
+use soroban_sdk::{contract, contractimpl, testutils::{Address as _, Ledger}, Env};
+
+/// Minimal external screening registry used to exercise `AddressRegistryClient` cross-contract
+/// calls, standing in for a real registry contract deployed elsewhere.
+#[contract]
+pub struct MockRegistry;
+
+#[contractimpl]
+impl MockRegistry {
+    pub fn is_blacklisted(env: Env, address: Address) -> bool {
+        let key = (String::from_str(&env, "blacklisted"), address);
+        env.storage().persistent().has(&key)
+    }
+
+    pub fn is_whitelisted(env: Env, address: Address) -> bool {
+        let key = (String::from_str(&env, "whitelisted"), address);
+        env.storage().persistent().has(&key)
+    }
+
+    pub fn add_blacklisted(env: Env, address: Address) {
+        let key = (String::from_str(&env, "blacklisted"), address);
+        env.storage().persistent().set(&key, &true);
+    }
+
+    pub fn add_whitelisted(env: Env, address: Address) {
+        let key = (String::from_str(&env, "whitelisted"), address);
+        env.storage().persistent().set(&key, &true);
+    }
+}
 
 #[test]
 fn test_set_and_get_user_limits() {
@@ -547,3 +575,610 @@ fn test_comprehensive_security_workflow() {
     assert!(client.verify_transaction(&user, &good_recipient, &normal_amount, &transaction_hash));
 }
 
+
+#[test]
+fn test_verify_transaction_batch_best_effort_commits_accepted_subset() {
+    let env = Env::default();
+    let contract_id = env.register_contract(None, SecurityContract);
+    let client = SecurityContractClient::new(&env, &contract_id);
+
+    let user = Address::generate(&env);
+    let admin = Address::generate(&env);
+    let good_recipient = Address::generate(&env);
+    let blacklisted_recipient = Address::generate(&env);
+    let daily_limit = 1000_0000000; // 1000 XLM
+    let monthly_limit = 10000_0000000; // 10000 XLM
+    let transaction_hash = soroban_sdk::Bytes::from_array(&env, &[1u8; 32]);
+
+    env.mock_all_auths();
+
+    client.set_user_limits(&user, &daily_limit, &monthly_limit);
+    client.add_to_blacklist(&admin, &blacklisted_recipient);
+
+    let mut transfers = Vec::new(&env);
+    transfers.push_back(substate::TransferRequest {
+        recipient: good_recipient.clone(),
+        amount: 400_0000000, // within limits
+        transaction_hash: transaction_hash.clone(),
+    });
+    transfers.push_back(substate::TransferRequest {
+        recipient: blacklisted_recipient.clone(),
+        amount: 100_0000000, // blacklisted, should be rejected
+        transaction_hash: transaction_hash.clone(),
+    });
+    transfers.push_back(substate::TransferRequest {
+        recipient: good_recipient.clone(),
+        amount: 700_0000000, // would push cumulative daily spend over the limit
+        transaction_hash: transaction_hash.clone(),
+    });
+
+    let results = client.verify_transaction_batch(&user, &transfers, &substate::BatchMode::BestEffort);
+    assert!(results.get(0).unwrap());
+    assert!(!results.get(1).unwrap());
+    assert!(!results.get(2).unwrap());
+
+    // Only the first transfer's amount should have been committed.
+    let remaining = client.get_remaining_daily_limit(&user);
+    assert_eq!(remaining, daily_limit - 400_0000000);
+}
+
+#[test]
+fn test_verify_transaction_batch_all_or_nothing_persists_nothing_on_failure() {
+    let env = Env::default();
+    let contract_id = env.register_contract(None, SecurityContract);
+    let client = SecurityContractClient::new(&env, &contract_id);
+
+    let user = Address::generate(&env);
+    let admin = Address::generate(&env);
+    let good_recipient = Address::generate(&env);
+    let blacklisted_recipient = Address::generate(&env);
+    let daily_limit = 1000_0000000; // 1000 XLM
+    let monthly_limit = 10000_0000000; // 10000 XLM
+    let transaction_hash = soroban_sdk::Bytes::from_array(&env, &[1u8; 32]);
+
+    env.mock_all_auths();
+
+    client.set_user_limits(&user, &daily_limit, &monthly_limit);
+    client.add_to_blacklist(&admin, &blacklisted_recipient);
+
+    let mut transfers = Vec::new(&env);
+    transfers.push_back(substate::TransferRequest {
+        recipient: good_recipient.clone(),
+        amount: 400_0000000,
+        transaction_hash: transaction_hash.clone(),
+    });
+    transfers.push_back(substate::TransferRequest {
+        recipient: blacklisted_recipient.clone(),
+        amount: 100_0000000,
+        transaction_hash: transaction_hash.clone(),
+    });
+
+    let results = client.verify_transaction_batch(&user, &transfers, &substate::BatchMode::AllOrNothing);
+    assert!(!results.get(0).unwrap());
+    assert!(!results.get(1).unwrap());
+
+    // Nothing should have been committed, including the transfer that would have passed alone.
+    let remaining = client.get_remaining_daily_limit(&user);
+    assert_eq!(remaining, daily_limit);
+}
+
+#[test]
+fn test_verify_transaction_batch_checks_cumulative_spending_within_batch() {
+    let env = Env::default();
+    let contract_id = env.register_contract(None, SecurityContract);
+    let client = SecurityContractClient::new(&env, &contract_id);
+
+    let user = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let daily_limit = 1000_0000000; // 1000 XLM
+    let monthly_limit = 10000_0000000; // 10000 XLM
+    let transaction_hash = soroban_sdk::Bytes::from_array(&env, &[1u8; 32]);
+
+    env.mock_all_auths();
+
+    client.set_user_limits(&user, &daily_limit, &monthly_limit);
+
+    // Each transfer is within the daily limit alone, but the third pushes the running total
+    // over it, so it must be rejected even though no single transfer exceeds the limit.
+    let mut transfers = Vec::new(&env);
+    for _ in 0..3 {
+        transfers.push_back(substate::TransferRequest {
+            recipient: recipient.clone(),
+            amount: 400_0000000,
+            transaction_hash: transaction_hash.clone(),
+        });
+    }
+
+    let results = client.verify_transaction_batch(&user, &transfers, &substate::BatchMode::BestEffort);
+    assert!(results.get(0).unwrap());
+    assert!(results.get(1).unwrap());
+    assert!(!results.get(2).unwrap());
+
+    let remaining = client.get_remaining_daily_limit(&user);
+    assert_eq!(remaining, daily_limit - 800_0000000);
+}
+
+#[test]
+fn test_verify_transaction_batch_rejects_instead_of_overflowing_on_near_max_amount() {
+    let env = Env::default();
+    let contract_id = env.register_contract(None, SecurityContract);
+    let client = SecurityContractClient::new(&env, &contract_id);
+
+    let user = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let daily_limit = 1000_0000000; // 1000 XLM
+    let monthly_limit = 10000_0000000; // 10000 XLM
+    let transaction_hash = soroban_sdk::Bytes::from_array(&env, &[1u8; 32]);
+
+    env.mock_all_auths();
+
+    client.set_user_limits(&user, &daily_limit, &monthly_limit);
+
+    // An amount near `i128::MAX` overflows the running cumulative total when added to any
+    // prior spend; this must be rejected like any other failed gate, not panic the call.
+    let mut transfers = Vec::new(&env);
+    transfers.push_back(substate::TransferRequest {
+        recipient: recipient.clone(),
+        amount: 400_0000000,
+        transaction_hash: transaction_hash.clone(),
+    });
+    transfers.push_back(substate::TransferRequest {
+        recipient: recipient.clone(),
+        amount: i128::MAX - 1,
+        transaction_hash: transaction_hash.clone(),
+    });
+
+    let results = client.verify_transaction_batch(&user, &transfers, &substate::BatchMode::BestEffort);
+    assert!(results.get(0).unwrap());
+    assert!(!results.get(1).unwrap());
+
+    let remaining = client.get_remaining_daily_limit(&user);
+    assert_eq!(remaining, daily_limit - 400_0000000);
+}
+
+#[test]
+fn test_try_verify_transaction_reports_specific_error_reasons() {
+    let env = Env::default();
+    let contract_id = env.register_contract(None, SecurityContract);
+    let client = SecurityContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let blacklisted_recipient = Address::generate(&env);
+    let daily_limit = 1000_0000000; // 1000 XLM
+    let monthly_limit = 10000_0000000; // 10000 XLM
+    let transaction_hash = soroban_sdk::Bytes::from_array(&env, &[1u8; 32]);
+
+    env.mock_all_auths();
+
+    client.set_user_limits(&user, &daily_limit, &monthly_limit);
+    client.add_to_blacklist(&admin, &blacklisted_recipient);
+
+    // Blacklisted recipient is rejected with a specific reason.
+    let result = client.try_verify_transaction_checked(&user, &blacklisted_recipient, &500_0000000, &transaction_hash);
+    assert!(result.is_err());
+    assert_eq!(result.unwrap_err(), Ok(SecurityError::RecipientBlacklisted));
+
+    // A transfer exceeding the daily limit is rejected with a specific reason too.
+    let over_limit = daily_limit + 1;
+    let result = client.try_verify_transaction_checked(&user, &recipient, &over_limit, &transaction_hash);
+    assert!(result.is_err());
+    assert_eq!(result.unwrap_err(), Ok(SecurityError::DailyLimitExceeded));
+
+    // Emergency stop takes priority over any other gate.
+    client.emergency_stop(&admin);
+    let result = client.try_verify_transaction_checked(&user, &recipient, &500_0000000, &transaction_hash);
+    assert!(result.is_err());
+    assert_eq!(result.unwrap_err(), Ok(SecurityError::EmergencyStopActive));
+    client.resume_operations(&admin);
+
+    // A transfer that clears every gate succeeds, and the bool wrapper agrees.
+    let result = client.try_verify_transaction_checked(&user, &recipient, &500_0000000, &transaction_hash);
+    assert!(result.is_ok());
+    assert!(client.verify_transaction(&user, &recipient, &500_0000000, &transaction_hash));
+}
+
+#[test]
+fn test_reverse_transaction_reclaims_daily_and_monthly_spending() {
+    let env = Env::default();
+    let contract_id = env.register_contract(None, SecurityContract);
+    let client = SecurityContractClient::new(&env, &contract_id);
+
+    let user = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let daily_limit = 1000_0000000; // 1000 XLM
+    let monthly_limit = 10000_0000000; // 10000 XLM
+    let amount = 500_0000000; // 500 XLM
+    let transaction_hash = soroban_sdk::Bytes::from_array(&env, &[1u8; 32]);
+
+    env.mock_all_auths();
+
+    client.set_user_limits(&user, &daily_limit, &monthly_limit);
+    client.verify_transaction(&user, &recipient, &amount, &transaction_hash);
+
+    let remaining = client.get_remaining_daily_limit(&user);
+    assert_eq!(remaining, daily_limit - amount);
+
+    let result = client.reverse_transaction(&user, &transaction_hash);
+    assert!(result);
+
+    let limits = client.get_user_limits(&user).unwrap();
+    assert_eq!(limits.daily_spent, 0);
+    assert_eq!(limits.monthly_spent, 0);
+
+    // The record was deleted, so reversing the same hash again is a no-op.
+    let result = client.reverse_transaction(&user, &transaction_hash);
+    assert!(!result);
+}
+
+#[test]
+fn test_reverse_transaction_skips_daily_refund_across_reset_boundary() {
+    let env = Env::default();
+    let contract_id = env.register_contract(None, SecurityContract);
+    let client = SecurityContractClient::new(&env, &contract_id);
+
+    let user = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let daily_limit = 1000_0000000; // 1000 XLM
+    let monthly_limit = 10000_0000000; // 10000 XLM
+    let amount = 500_0000000; // 500 XLM
+    let transaction_hash = soroban_sdk::Bytes::from_array(&env, &[1u8; 32]);
+
+    env.mock_all_auths();
+
+    client.set_user_limits(&user, &daily_limit, &monthly_limit);
+    client.verify_transaction(&user, &recipient, &amount, &transaction_hash);
+
+    // Cross the daily reset boundary, but stay well within the monthly window, then make
+    // another transaction so `last_daily_reset` actually advances past the recorded one.
+    env.ledger().with_mut(|info| {
+        info.timestamp += 86401; // 24 hours and 1 second
+    });
+    let other_hash = soroban_sdk::Bytes::from_array(&env, &[2u8; 32]);
+    client.verify_transaction(&user, &recipient, &amount, &other_hash);
+
+    let daily_spent_before_reversal = client.get_user_limits(&user).unwrap().daily_spent;
+
+    // Reversing the original (now stale) transaction must not touch the new daily window.
+    let result = client.reverse_transaction(&user, &transaction_hash);
+    assert!(result);
+
+    let limits = client.get_user_limits(&user).unwrap();
+    assert_eq!(limits.daily_spent, daily_spent_before_reversal);
+    // The monthly window is still current, so it is refunded.
+    assert_eq!(limits.monthly_spent, amount);
+}
+
+#[test]
+fn test_velocity_anomaly_rule_triggers_on_ring_buffer_count() {
+    let env = Env::default();
+    let contract_id = env.register_contract(None, SecurityContract);
+    let client = SecurityContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let daily_limit = 1000_0000000; // 1000 XLM
+    let monthly_limit = 10000_0000000; // 10000 XLM
+    let small_amount = 10_0000000; // 10 XLM
+
+    env.mock_all_auths();
+
+    client.set_user_limits(&user, &daily_limit, &monthly_limit);
+    client.create_alert_rule(
+        &admin,
+        &1,
+        &alert_rules::AlertType::VelocityAnomaly,
+        &None,
+        &Some(300), // 5 minute window
+        &Some(3),   // max 3 transactions
+        &String::from_str(&env, "High transaction velocity"),
+    );
+
+    // Three transactions within the window stay at the configured maximum: no alert yet.
+    for i in 0..3u8 {
+        let hash = soroban_sdk::Bytes::from_array(&env, &[i; 32]);
+        client.verify_transaction(&user, &recipient, &small_amount, &hash);
+        env.ledger().with_mut(|info| info.timestamp += 1);
+    }
+    assert!(client.get_unresolved_alerts(&user, &0, &50).is_empty());
+
+    // The fourth transaction inside the same window exceeds it and raises a VelocityAnomaly alert.
+    let hash = soroban_sdk::Bytes::from_array(&env, &[3u8; 32]);
+    client.verify_transaction(&user, &recipient, &small_amount, &hash);
+
+    let alerts = client.get_unresolved_alerts(&user, &0, &50);
+    assert!(alerts
+        .iter()
+        .any(|a| a.alert_type == alert_rules::AlertType::VelocityAnomaly));
+}
+
+#[test]
+fn test_screening_registry_union_rejects_registry_blacklisted_recipient() {
+    let env = Env::default();
+    let contract_id = env.register_contract(None, SecurityContract);
+    let client = SecurityContractClient::new(&env, &contract_id);
+
+    let registry_id = env.register_contract(None, MockRegistry);
+    let registry_client = MockRegistryClient::new(&env, &registry_id);
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let daily_limit = 1000_0000000; // 1000 XLM
+    let monthly_limit = 10000_0000000; // 10000 XLM
+    let amount = 500_0000000; // 500 XLM
+    let transaction_hash = soroban_sdk::Bytes::from_array(&env, &[1u8; 32]);
+
+    env.mock_all_auths();
+
+    client.set_user_limits(&user, &daily_limit, &monthly_limit);
+    client.set_screening_registry(&admin, &registry_id);
+
+    // Not blacklisted anywhere yet: the transfer succeeds.
+    let result = client.verify_transaction(&user, &recipient, &amount, &transaction_hash);
+    assert!(result);
+
+    // The registry blacklists the recipient; the local lists never heard of it.
+    registry_client.add_blacklisted(&recipient);
+    let other_hash = soroban_sdk::Bytes::from_array(&env, &[2u8; 32]);
+    let result = client.try_verify_transaction_checked(&user, &recipient, &amount, &other_hash);
+    assert!(result.is_err());
+    assert_eq!(result.unwrap_err(), Ok(SecurityError::RecipientBlacklisted));
+}
+
+#[test]
+fn test_refuse_unscreened_rejects_recipient_not_whitelisted_by_registry() {
+    let env = Env::default();
+    let contract_id = env.register_contract(None, SecurityContract);
+    let client = SecurityContractClient::new(&env, &contract_id);
+
+    let registry_id = env.register_contract(None, MockRegistry);
+    let registry_client = MockRegistryClient::new(&env, &registry_id);
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let screened_recipient = Address::generate(&env);
+    let unscreened_recipient = Address::generate(&env);
+    let daily_limit = 1000_0000000; // 1000 XLM
+    let monthly_limit = 10000_0000000; // 10000 XLM
+    let amount = 500_0000000; // 500 XLM
+
+    env.mock_all_auths();
+
+    client.set_user_limits(&user, &daily_limit, &monthly_limit);
+    client.set_screening_registry(&admin, &registry_id);
+    client.set_refuse_unscreened(&admin, &true);
+    registry_client.add_whitelisted(&screened_recipient);
+
+    // Whitelisted by the registry: allowed even in deny-by-default mode.
+    let hash = soroban_sdk::Bytes::from_array(&env, &[1u8; 32]);
+    let result = client.try_verify_transaction_checked(&user, &screened_recipient, &amount, &hash);
+    assert!(result.is_ok());
+
+    // Never screened by the registry: rejected in deny-by-default mode.
+    let hash = soroban_sdk::Bytes::from_array(&env, &[2u8; 32]);
+    let result = client.try_verify_transaction_checked(&user, &unscreened_recipient, &amount, &hash);
+    assert!(result.is_err());
+    assert_eq!(result.unwrap_err(), Ok(SecurityError::RecipientNotWhitelisted));
+}
+
+#[test]
+fn test_structuring_anomaly_rule_triggers_on_low_value_fanout() {
+    let env = Env::default();
+    let contract_id = env.register_contract(None, SecurityContract);
+    let client = SecurityContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let daily_limit = 10000_0000000; // 10000 XLM
+    let monthly_limit = 100000_0000000; // 100000 XLM
+    let dust_amount = 40_0000000; // 40 XLM, below the dust threshold
+    let dust_threshold = 50_0000000; // 50 XLM
+
+    env.mock_all_auths();
+
+    client.set_user_limits(&user, &daily_limit, &monthly_limit);
+    client.create_alert_rule(
+        &admin,
+        &1,
+        &alert_rules::AlertType::StructuringAnomaly,
+        &Some(dust_threshold),
+        &Some(300), // 5 minute window
+        &Some(3),   // max 3 distinct low-value recipients
+        &String::from_str(&env, "Structuring (smurfing) pattern detected"),
+    );
+
+    // Three small transfers to three distinct recipients stay at the configured maximum.
+    for i in 0..3u8 {
+        let recipient = Address::generate(&env);
+        let hash = soroban_sdk::Bytes::from_array(&env, &[i; 32]);
+        client.verify_transaction(&user, &recipient, &dust_amount, &hash);
+    }
+    assert!(client.get_unresolved_alerts(&user, &0, &50).is_empty());
+
+    // A fourth small transfer to a fresh recipient exceeds the fan-out limit.
+    let recipient = Address::generate(&env);
+    let hash = soroban_sdk::Bytes::from_array(&env, &[3u8; 32]);
+    client.verify_transaction(&user, &recipient, &dust_amount, &hash);
+
+    let alerts = client.get_unresolved_alerts(&user, &0, &50);
+    let structuring_alert = alerts
+        .iter()
+        .find(|a| a.alert_type == alert_rules::AlertType::StructuringAnomaly);
+    assert!(structuring_alert.is_some());
+    assert_eq!(structuring_alert.unwrap().fanout_count, Some(4));
+}
+
+#[test]
+fn test_get_unresolved_alerts_pages_past_old_fixed_scan_cap() {
+    let env = Env::default();
+    let contract_id = env.register_contract(None, SecurityContract);
+    let client = SecurityContractClient::new(&env, &contract_id);
+
+    let user = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let daily_limit = i128::MAX / 2;
+    let monthly_limit = i128::MAX / 2;
+    let large_amount = 50001_0000000; // > 50k XLM large-transaction threshold
+
+    env.mock_all_auths();
+    client.set_user_limits(&user, &daily_limit, &monthly_limit);
+
+    // The old implementation only ever scanned alert ids 1..=50, silently undercounting past
+    // that point; drive a 51st alert to prove the index-backed getters see past it.
+    for i in 0..51u16 {
+        let mut bytes = [0u8; 32];
+        bytes[0] = (i & 0xff) as u8;
+        bytes[1] = (i >> 8) as u8;
+        let hash = soroban_sdk::Bytes::from_array(&env, &bytes);
+        client.verify_transaction(&user, &recipient, &large_amount, &hash);
+    }
+
+    assert_eq!(client.get_unresolved_alerts(&user, &0, &100).len(), 51);
+    assert_eq!(client.get_unresolved_alerts(&user, &0, &10).len(), 10);
+    assert_eq!(client.get_unresolved_alerts(&user, &50, &10).len(), 1);
+}
+
+#[test]
+fn test_get_alert_rules_pages_past_old_fixed_scan_cap() {
+    let env = Env::default();
+    let contract_id = env.register_contract(None, SecurityContract);
+    let client = SecurityContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    env.mock_all_auths();
+
+    // The old implementation only ever scanned rule ids 1..=20; create one past that cap.
+    for rule_id in 1..=21u64 {
+        client.create_alert_rule(
+            &admin,
+            &rule_id,
+            &alert_rules::AlertType::LargeTransaction,
+            &None,
+            &None,
+            &None,
+            &String::from_str(&env, "test rule"),
+        );
+    }
+
+    assert_eq!(client.get_alert_rules(&0, &u32::MAX).len(), 21);
+    assert_eq!(client.get_alert_rules(&0, &10).len(), 10);
+}
+
+#[test]
+fn test_evaluate_transaction_fires_large_transaction_alert() {
+    let env = Env::default();
+    let contract_id = env.register_contract(None, SecurityContract);
+    let client = SecurityContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    env.mock_all_auths();
+
+    client.create_alert_rule(
+        &admin,
+        &1,
+        &alert_rules::AlertType::LargeTransaction,
+        &Some(1_000_0000000),
+        &None,
+        &None,
+        &String::from_str(&env, "large transaction"),
+    );
+
+    let triggered = client.evaluate_transaction(&user, &2_000_0000000, &recipient, &1000);
+    assert_eq!(triggered.len(), 1);
+    assert_eq!(triggered.get(0).unwrap().alert_type, alert_rules::AlertType::LargeTransaction);
+
+    let unresolved = client.get_unresolved_alerts(&user, &0, &10);
+    assert_eq!(unresolved.len(), 1);
+}
+
+#[test]
+fn test_evaluate_transaction_fires_daily_limit_alert_on_windowed_sum() {
+    let env = Env::default();
+    let contract_id = env.register_contract(None, SecurityContract);
+    let client = SecurityContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    env.mock_all_auths();
+
+    client.create_alert_rule(
+        &admin,
+        &1,
+        &alert_rules::AlertType::DailyLimitExceeded,
+        &Some(1_000_0000000),
+        &Some(86400),
+        &None,
+        &String::from_str(&env, "daily limit"),
+    );
+
+    let first = client.evaluate_transaction(&user, &600_0000000, &recipient, &1000);
+    assert_eq!(first.len(), 0);
+
+    let second = client.evaluate_transaction(&user, &600_0000000, &recipient, &2000);
+    assert_eq!(second.len(), 1);
+    assert_eq!(second.get(0).unwrap().alert_type, alert_rules::AlertType::DailyLimitExceeded);
+}
+
+#[test]
+fn test_evaluate_transaction_fires_unknown_address_until_allowed() {
+    let env = Env::default();
+    let contract_id = env.register_contract(None, SecurityContract);
+    let client = SecurityContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    env.mock_all_auths();
+
+    client.create_alert_rule(
+        &admin,
+        &1,
+        &alert_rules::AlertType::UnknownAddress,
+        &None,
+        &None,
+        &None,
+        &String::from_str(&env, "unknown address"),
+    );
+
+    let triggered = client.evaluate_transaction(&user, &100_0000000, &recipient, &1000);
+    assert_eq!(triggered.len(), 1);
+
+    client.allow_address(&user, &recipient);
+
+    let after_allow = client.evaluate_transaction(&user, &100_0000000, &recipient, &2000);
+    assert_eq!(after_allow.len(), 0);
+}
+
+#[test]
+fn test_evaluate_transaction_fires_velocity_anomaly_once_threshold_exceeded() {
+    let env = Env::default();
+    let contract_id = env.register_contract(None, SecurityContract);
+    let client = SecurityContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    env.mock_all_auths();
+
+    client.create_alert_rule(
+        &admin,
+        &1,
+        &alert_rules::AlertType::VelocityAnomaly,
+        &None,
+        &Some(3600),
+        &Some(2),
+        &String::from_str(&env, "velocity anomaly"),
+    );
+
+    assert_eq!(client.evaluate_transaction(&user, &10_0000000, &recipient, &1000).len(), 0);
+    assert_eq!(client.evaluate_transaction(&user, &10_0000000, &recipient, &1100).len(), 0);
+    let triggered = client.evaluate_transaction(&user, &10_0000000, &recipient, &1200);
+    assert_eq!(triggered.len(), 1);
+    assert_eq!(triggered.get(0).unwrap().alert_type, alert_rules::AlertType::VelocityAnomaly);
+}
@@ -21,6 +21,18 @@ pub struct TransactionAttempt {
     pub transaction_hash: Bytes,
 }
 
+/// The counted amount behind one committed transaction, recorded so a later submission failure
+/// can reclaim it with `reverse_transaction` instead of the allowance being burned for good.
+/// `daily_reset_at`/`monthly_reset_at` capture which reset window the spending was counted
+/// against, so a reversal crossing a reset boundary only refunds the window(s) still current.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SpendingRecord {
+    pub amount: i128,
+    pub daily_reset_at: u64,
+    pub monthly_reset_at: u64,
+}
+
 impl Default for UserLimits {
     fn default() -> Self {
         Self {
@@ -70,20 +82,24 @@ impl UserLimits {
         }
     }
 
-    pub fn can_spend(&self, amount: i128) -> bool {
-        if !self.is_active {
-            return false;
-        }
-        
-        self.daily_spent + amount <= self.daily_limit &&
-        self.monthly_spent + amount <= self.monthly_limit
-    }
-
     pub fn add_spending(&mut self, amount: i128) {
         self.daily_spent += amount;
         self.monthly_spent += amount;
     }
 
+    /// Reclaims previously-counted spending. `refund_daily`/`refund_monthly` are false once the
+    /// respective window has already rolled past the reset the spending was counted against, so
+    /// a reversal crossing a reset boundary only refunds the window(s) still current. Saturates
+    /// at zero so a reversal can never push spending negative.
+    pub fn reverse_spending(&mut self, amount: i128, refund_daily: bool, refund_monthly: bool) {
+        if refund_daily {
+            self.daily_spent = (self.daily_spent - amount).max(0);
+        }
+        if refund_monthly {
+            self.monthly_spent = (self.monthly_spent - amount).max(0);
+        }
+    }
+
     pub fn get_remaining_daily(&self) -> i128 {
         (self.daily_limit - self.daily_spent).max(0)
     }
@@ -108,6 +124,21 @@ pub fn store_transaction_attempt(env: &Env, attempt: &TransactionAttempt) {
     env.storage().temporary().set(&key, attempt);
 }
 
+pub fn store_spending_record(env: &Env, user: &Address, transaction_hash: &Bytes, record: &SpendingRecord) {
+    let key = (user.clone(), transaction_hash.clone());
+    env.storage().persistent().set(&key, record);
+}
+
+pub fn load_spending_record(env: &Env, user: &Address, transaction_hash: &Bytes) -> Option<SpendingRecord> {
+    let key = (user.clone(), transaction_hash.clone());
+    env.storage().persistent().get(&key)
+}
+
+pub fn remove_spending_record(env: &Env, user: &Address, transaction_hash: &Bytes) {
+    let key = (user.clone(), transaction_hash.clone());
+    env.storage().persistent().remove(&key);
+}
+
 pub fn load_user_transactions(env: &Env, user: &Address, from_time: u64, to_time: u64) -> Map<u64, TransactionAttempt> {
     let mut transactions = Map::new(env);
     
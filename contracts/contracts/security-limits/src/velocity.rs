@@ -0,0 +1,118 @@
+use soroban_sdk::{contracttype, Address, Env, String, Vec};
+
+/// Maximum number of recent transactions kept per user. Once full, the oldest entry is
+/// overwritten, keeping `count_in_window` an O(capacity) bounded scan regardless of how much
+/// wall-clock time has elapsed, instead of `load_user_transactions`'s per-second storage probe.
+pub const TX_RING_CAPACITY: u32 = 50;
+
+/// An append-only, fixed-capacity log of a user's recent accepted transfers, used to answer
+/// "how many transactions in the last N seconds" for velocity-anomaly detection without scanning
+/// storage one timestamp at a time, and to spot structuring/smurfing fan-out across many
+/// low-value recipients.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TxRingBuffer {
+    pub head: u32,
+    pub len: u32,
+    pub entries: Vec<(u64, i128, Address)>,
+}
+
+impl TxRingBuffer {
+    pub fn new(env: &Env) -> Self {
+        Self {
+            head: 0,
+            len: 0,
+            entries: Vec::new(env),
+        }
+    }
+
+    /// Records one accepted transfer, overwriting the oldest slot once the buffer is full.
+    pub fn push(&mut self, timestamp: u64, amount: i128, recipient: Address) {
+        if self.len < TX_RING_CAPACITY {
+            self.entries.push_back((timestamp, amount, recipient));
+            self.len += 1;
+        } else {
+            self.entries.set(self.head, (timestamp, amount, recipient));
+        }
+        self.head = (self.head + 1) % TX_RING_CAPACITY;
+    }
+
+    /// Counts entries with `timestamp >= now - window_secs`, in one bounded pass over the buffer.
+    pub fn count_in_window(&self, window_secs: u64, now: u64) -> u32 {
+        let cutoff = now.saturating_sub(window_secs);
+        let mut count = 0u32;
+        for i in 0..self.entries.len() {
+            let (timestamp, _, _) = self.entries.get(i).unwrap();
+            if timestamp >= cutoff {
+                count += 1;
+            }
+        }
+        count
+    }
+
+    /// Counts distinct recipients that received a transfer at or below `dust_threshold` within
+    /// `window_secs` of `now` — the fan-out signal behind a `StructuringAnomaly` alert. `pending`
+    /// is folded into the same distinct count, letting a caller include a not-yet-committed
+    /// transfer in the projection without writing it to the buffer first.
+    pub fn distinct_low_value_recipients_in_window(
+        &self,
+        env: &Env,
+        dust_threshold: i128,
+        window_secs: u64,
+        now: u64,
+        pending: Option<(i128, Address)>,
+    ) -> u32 {
+        let cutoff = now.saturating_sub(window_secs);
+        let mut seen: Vec<Address> = Vec::new(env);
+        for i in 0..self.entries.len() {
+            let (timestamp, amount, recipient) = self.entries.get(i).unwrap();
+            if timestamp >= cutoff && amount <= dust_threshold && !seen.contains(&recipient) {
+                seen.push_back(recipient);
+            }
+        }
+        if let Some((amount, recipient)) = pending {
+            if amount <= dust_threshold && !seen.contains(&recipient) {
+                seen.push_back(recipient);
+            }
+        }
+        seen.len()
+    }
+}
+
+pub fn store_tx_ring(env: &Env, user: &Address, ring: &TxRingBuffer) {
+    let key = (String::from_str(env, "tx_ring"), user.clone());
+    env.storage().persistent().set(&key, ring);
+}
+
+pub fn load_tx_ring(env: &Env, user: &Address) -> TxRingBuffer {
+    let key = (String::from_str(env, "tx_ring"), user.clone());
+    env.storage()
+        .persistent()
+        .get(&key)
+        .unwrap_or_else(|| TxRingBuffer::new(env))
+}
+
+/// Records an accepted transfer in `user`'s ring buffer.
+pub fn record_transaction(env: &Env, user: &Address, timestamp: u64, amount: i128, recipient: Address) {
+    let mut ring = load_tx_ring(env, user);
+    ring.push(timestamp, amount, recipient);
+    store_tx_ring(env, user, &ring);
+}
+
+/// Counts how many of `user`'s recent accepted transfers fall within `window_secs` of `now`.
+pub fn count_in_window(env: &Env, user: &Address, window_secs: u64, now: u64) -> u32 {
+    load_tx_ring(env, user).count_in_window(window_secs, now)
+}
+
+/// Counts distinct recipients `user` sent a transfer at or below `dust_threshold` to within
+/// `window_secs` of `now`, optionally folding in a not-yet-committed `pending` transfer.
+pub fn count_distinct_low_value_recipients_in_window(
+    env: &Env,
+    user: &Address,
+    dust_threshold: i128,
+    window_secs: u64,
+    now: u64,
+    pending: Option<(i128, Address)>,
+) -> u32 {
+    load_tx_ring(env, user).distinct_low_value_recipients_in_window(env, dust_threshold, window_secs, now, pending)
+}